@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+# Regenerates pallets/quadratic-voting-pallet/src/weights.rs. Run it from the root of the repository, on the
+# reference hardware the weights are meant for, and commit the file it writes.
+set -euo pipefail
+
+cargo build --release --features runtime-benchmarks
+
+./target/release/parachain-template-node benchmark pallet \
+	--chain dev \
+	--execution wasm \
+	--wasm-execution compiled \
+	--pallet quadratic_voting_pallet \
+	--extrinsic '*' \
+	--steps 50 \
+	--repeat 20 \
+	--template .maintain/frame-weight-template.hbs \
+	--output pallets/quadratic-voting-pallet/src/weights.rs
@@ -1,5 +1,6 @@
 use cumulus_primitives_core::ParaId;
-use parachain_template_runtime::{AccountId, AuraId, Signature, EXISTENTIAL_DEPOSIT};
+use parachain_template_runtime::{AccountId, AuraId, Balance, Signature, EXISTENTIAL_DEPOSIT, UNIT};
+use quadratic_voting_pallet::{BallotMode, GenesisRound, ProposalClass, RoundMode};
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
@@ -102,6 +103,7 @@ pub fn development_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
 				1000.into(),
+				Default::default(),
 			)
 		},
 		Vec::new(),
@@ -157,6 +159,7 @@ pub fn local_testnet_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
 				1000.into(),
+				Default::default(),
 			)
 		},
 		// Bootnodes
@@ -181,6 +184,7 @@ fn testnet_genesis(
 	invulnerables: Vec<(AccountId, AuraId)>,
 	endowed_accounts: Vec<AccountId>,
 	id: ParaId,
+	quadratic_voting_pallet: parachain_template_runtime::QuadraticVotingPalletConfig,
 ) -> parachain_template_runtime::GenesisConfig {
 	parachain_template_runtime::GenesisConfig {
 		system: parachain_template_runtime::SystemConfig {
@@ -219,5 +223,91 @@ fn testnet_genesis(
 			safe_xcm_version: Some(SAFE_XCM_VERSION),
 		},
 		technical_committee: Default::default(),
+		quadratic_voting_pallet,
 	}
 }
+
+/// The voters of the `qv-demo` chain, by seed.
+const DEMO_VOTERS: [&str; 4] = ["Voter1", "Voter2", "Voter3", "Voter4"];
+
+/// Credits each demo voter registers with, in every bucket.
+const DEMO_CREDITS: Balance = 10_000;
+
+/// A development chain that boots straight into the voting phase of a governance round.
+///
+/// Alice is the identity registrar, and every well-known account and demo voter has a judged identity. Alice, Bob,
+/// Charlie and Dave each submitted a proposal, which fill buckets 0, 1, 2 and 1, and the demo voters are registered
+/// in every bucket.
+pub fn qv_demo_config() -> ChainSpec {
+	let mut properties = sc_chain_spec::Properties::new();
+	properties.insert("tokenSymbol".into(), "UNIT".into());
+	properties.insert("tokenDecimals".into(), 12.into());
+	properties.insert("ss58Format".into(), 42.into());
+
+	ChainSpec::from_genesis(
+		// Name
+		"Quadratic Voting Demo",
+		// ID
+		"qv_demo",
+		ChainType::Development,
+		move || {
+			let account = get_account_id_from_seed::<sr25519::Public>;
+			let well_known = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+			let voters: Vec<AccountId> = DEMO_VOTERS.iter().map(|seed| account(seed)).collect();
+
+			let proposals = vec![
+				(account("Alice"), ProposalClass::General),
+				(account("Bob"), ProposalClass::TreasurySpend),
+				(account("Charlie"), ProposalClass::RuntimeParameter),
+				(account("Dave"), ProposalClass::General),
+			];
+			let registrations = voters
+				.iter()
+				.flat_map(|voter| (0..3).map(move |bucket_id| (voter.clone(), bucket_id, DEMO_CREDITS)))
+				.collect();
+
+			testnet_genesis(
+				// initial collators.
+				vec![
+					(account("Alice"), get_collator_keys_from_seed("Alice")),
+					(account("Bob"), get_collator_keys_from_seed("Bob")),
+				],
+				well_known.iter().map(|seed| account(seed)).collect(),
+				1000.into(),
+				parachain_template_runtime::QuadraticVotingPalletConfig {
+					voting_round_id: 0,
+					voters: voters.iter().cloned().map(|voter| (voter, 1000 * UNIT)).collect(),
+					registrar: Some(account("Alice")),
+					identities: well_known
+						.iter()
+						.chain(DEMO_VOTERS.iter())
+						.map(|seed| (account(seed), seed.as_bytes().to_vec()))
+						.collect(),
+					initial_round: Some(GenesisRound {
+						initializer: account("Alice"),
+						mode: RoundMode::Governance,
+						ballot: BallotMode::Open,
+						proposals,
+						registrations,
+						open_voting: true,
+					}),
+				},
+			)
+		},
+		// Bootnodes
+		Vec::new(),
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		// Fork ID
+		None,
+		// Properties
+		Some(properties),
+		// Extensions
+		Extensions {
+			relay_chain: "rococo-local".into(), // You MUST set this to the correct network!
+			para_id: 1000,
+		},
+	)
+}
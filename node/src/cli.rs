@@ -37,6 +37,18 @@ pub enum Subcommand {
 
 	/// Try some testing command against a specified runtime state.
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Generate a threshold key for the decryption committee of threshold-encrypted voting rounds.
+	ThresholdKeygen(crate::threshold_keygen::ThresholdKeygenCmd),
+
+	/// Export the results of a voting round, from the database, as JSON or CSV.
+	ExportRound(crate::export_round::ExportRoundCmd),
+
+	/// Replay a voting round from the database, and check its tally on chain.
+	VerifyRound(crate::verify_round::VerifyRoundCmd),
+
+	/// Simulate a voting round over a synthetic population, with the pallet logic of this runtime.
+	SimulateRound(crate::simulate_round::SimulateRoundCmd),
 }
 
 #[derive(Debug, clap::Parser)]
@@ -62,11 +74,25 @@ pub struct Cli {
 	#[clap(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Run the runtime on its own, without a relay chain, sealing blocks as soon as transactions arrive
+	/// (`instant`) or only when asked to over RPC (`manual`). Meant for development, alongside `--dev`.
+	#[clap(long, value_enum)]
+	pub dev_seal: Option<DevSeal>,
+
 	/// Relay chain arguments
 	#[clap(raw = true)]
 	pub relay_chain_args: Vec<String>,
 }
 
+/// How a `--dev-seal` node seals its blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DevSeal {
+	/// A block is sealed for every transaction that enters the pool.
+	Instant,
+	/// Blocks are only sealed on `engine_createBlock` or `dev_createBlocks` calls.
+	Manual,
+}
+
 #[derive(Debug)]
 pub struct RelayChainCli {
 	/// The actual relay chain cli object.
@@ -26,6 +26,7 @@ use crate::{
 fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
 	Ok(match id {
 		"dev" => Box::new(chain_spec::development_config()),
+		"qv-demo" => Box::new(chain_spec::qv_demo_config()),
 		"template-rococo" => Box::new(chain_spec::local_testnet_config()),
 		"" | "local" => Box::new(chain_spec::local_testnet_config()),
 		path => Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
@@ -249,10 +250,47 @@ pub fn run() -> Result<()> {
 				Err("Try-runtime must be enabled by `--features try-runtime`.".into())
 			}
 		},
+		Some(Subcommand::ThresholdKeygen(cmd)) => cmd.run(),
+		Some(Subcommand::ExportRound(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let partials = new_partial::<RuntimeApi, TemplateRuntimeExecutor, _>(
+					&config,
+					crate::service::parachain_build_import_queue,
+				)?;
+				cmd.run(partials.client)
+			})
+		},
+		Some(Subcommand::VerifyRound(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let partials = new_partial::<RuntimeApi, TemplateRuntimeExecutor, _>(
+					&config,
+					crate::service::parachain_build_import_queue,
+				)?;
+				cmd.run(partials.client)
+			})
+		},
+		Some(Subcommand::SimulateRound(cmd)) => cmd.run(),
 		None => {
 			let runner = cli.create_runner(&cli.run.normalize())?;
 			let collator_options = cli.run.collator_options();
 
+			if let Some(seal) = cli.dev_seal {
+				return runner.run_node_until_exit(|config| async move {
+					let para_id = chain_spec::Extensions::try_get(&*config.chain_spec)
+						.map(|e| e.para_id)
+						.ok_or_else(|| "Could not find parachain ID in chain-spec.")?;
+
+					info!("Sealing blocks {:?}, without a relay chain", seal);
+
+					crate::service::start_dev_seal_node(config, seal, ParaId::from(para_id))
+						.await
+						.map(|r| r.0)
+						.map_err(Into::into)
+				})
+			}
+
 			runner.run_node_until_exit(|config| async move {
 				let hwbench = if !cli.no_hardware_benchmarks {
 					config.database.path().map(|database_path| {
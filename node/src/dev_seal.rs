@@ -0,0 +1,138 @@
+//! Pieces of the `--dev-seal` node, which runs the runtime without a relay chain and seals blocks with
+//! `sc-consensus-manual-seal`: the inherents it builds blocks with, and the `dev_createBlocks` RPC method, which
+//! fast-forwards the chain through the phases of a round.
+
+use std::error::Error as StdError;
+
+use codec::Decode;
+use cumulus_primitives_core::ParaId;
+use cumulus_primitives_parachain_inherent::{MockValidationDataInherentDataProvider, MockXcmConfig};
+use futures::{
+	channel::{mpsc, oneshot},
+	SinkExt,
+};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use parachain_template_runtime::{opaque::Block, Hash, SLOT_DURATION};
+use sc_client_api::{Backend, StorageProvider};
+use sc_consensus_manual_seal::EngineCommand;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::generic::BlockId;
+
+/// The most blocks a single `dev_createBlocks` call seals.
+pub const MAX_BLOCKS_PER_CALL: u32 = 1000;
+
+/// The inherents of a block sealed on top of `parent`.
+///
+/// Each block is one slot later than its parent, or at the current time if that is later, so that a chain can be
+/// fast-forwarded faster than it would be produced. The validation data is that of a relay chain that moves two
+/// blocks per parachain block.
+pub fn inherent_data_providers<C, BE>(
+	client: &C,
+	parent: Hash,
+	para_id: ParaId,
+) -> Result<(sp_timestamp::InherentDataProvider, MockValidationDataInherentDataProvider), Box<dyn StdError + Send + Sync>>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	let parent_number = client.number(parent)?.ok_or("The parent block is not known")?;
+
+	let key = StorageKey([twox_128(b"Timestamp"), twox_128(b"Now")].concat());
+	let parent_time = match client.storage(&BlockId::Hash(parent), &key)? {
+		Some(time) => u64::decode(&mut &time.0[..])?,
+		None => 0,
+	};
+	let now = *sp_timestamp::Timestamp::current();
+	let time = sp_timestamp::InherentDataProvider::new(now.max(parent_time + SLOT_DURATION).into());
+
+	let validation_data = MockValidationDataInherentDataProvider {
+		current_para_block: parent_number,
+		relay_offset: 1000,
+		relay_blocks_per_para_block: 2,
+		xcm_config: MockXcmConfig::new(client, parent, para_id, Default::default()),
+		raw_downward_messages: Vec::new(),
+		raw_horizontal_messages: Vec::new(),
+	};
+
+	Ok((time, validation_data))
+}
+
+/// Error codes of the `dev_*` methods.
+pub enum Error {
+	/// More blocks were asked for than `MAX_BLOCKS_PER_CALL`.
+	TooManyBlocks,
+	/// The block could not be sealed.
+	SealFailed,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::TooManyBlocks => 1,
+			Error::SealFailed => 2,
+		}
+	}
+}
+
+fn seal_error(e: impl ToString) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::SealFailed.into(), "Could not seal a block", Some(e.to_string()))).into()
+}
+
+/// Methods of `--dev-seal` nodes.
+#[rpc(server)]
+pub trait DevBlocksApi {
+	/// Seals `count` blocks, one after the other, and returns their hashes. The first block includes the
+	/// transactions that are ready in the pool; the others are empty unless more come in. The blocks are finalized
+	/// if `finalize` is set.
+	#[method(name = "dev_createBlocks")]
+	async fn create_blocks(&self, count: u32, finalize: Option<bool>) -> RpcResult<Vec<Hash>>;
+}
+
+/// Serves the `dev_*` methods, by sending seal commands to the manual-seal task.
+pub struct DevBlocks {
+	commands: mpsc::Sender<EngineCommand<Hash>>,
+}
+
+impl DevBlocks {
+	/// Sends its commands to the stream that `run_manual_seal` was started with.
+	pub fn new(commands: mpsc::Sender<EngineCommand<Hash>>) -> Self {
+		Self { commands }
+	}
+}
+
+#[async_trait]
+impl DevBlocksApiServer for DevBlocks {
+	async fn create_blocks(&self, count: u32, finalize: Option<bool>) -> RpcResult<Vec<Hash>> {
+		if count > MAX_BLOCKS_PER_CALL {
+			return Err(CallError::Custom(ErrorObject::owned(
+				Error::TooManyBlocks.into(),
+				format!("At most {} blocks can be created at once", MAX_BLOCKS_PER_CALL),
+				None::<()>,
+			))
+			.into())
+		}
+
+		let mut commands = self.commands.clone();
+		let mut hashes = Vec::with_capacity(count as usize);
+		for _ in 0..count {
+			let (sender, receiver) = oneshot::channel();
+			commands
+				.send(EngineCommand::SealNewBlock {
+					create_empty: true,
+					finalize: finalize.unwrap_or(false),
+					parent_hash: None,
+					sender: Some(sender),
+				})
+				.await
+				.map_err(seal_error)?;
+			let block = receiver.await.map_err(seal_error)?.map_err(seal_error)?;
+			hashes.push(block.hash);
+		}
+		Ok(hashes)
+	}
+}
@@ -0,0 +1,404 @@
+//! Export of the results of a voting round, read from the node's database, for auditors.
+
+use std::{fs, io::Write, path::PathBuf, sync::Arc};
+
+use parachain_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, QuadraticVotingPallet, Runtime};
+use quadratic_voting_pallet::{BucketId, ProposalCount, RoundExport, RoundMode, VoteDirection, VotingRoundId};
+use quadratic_voting_rpc::{ballot_name, class_name, direction_name, outcome_name, phase_name, threshold_name};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, StorageProvider};
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::{Storage, StorageKey};
+use sp_runtime::generic::BlockId;
+
+/// What the pallet holds on a round.
+pub type Export = RoundExport<AccountId, BlockNumber, Balance, Hash, <Runtime as quadratic_voting_pallet::Config>::MaxVotes>;
+
+/// Reads a round at the given block, out of a copy of the pallet's storage. None if there is no such round, or it has
+/// been reaped.
+pub fn read_round<C, BE>(client: &C, at: &BlockId<Block>, round: VotingRoundId) -> sc_cli::Result<Option<Export>>
+where
+	C: StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	let prefix = StorageKey(QuadraticVotingPallet::storage_prefix().to_vec());
+	let top = client.storage_pairs(at, &prefix)?.into_iter().map(|(key, value)| (key.0, value.0)).collect();
+	Ok(sp_io::TestExternalities::new(Storage { top, children_default: Default::default() })
+		.execute_with(|| QuadraticVotingPallet::export_round(round)))
+}
+
+/// The format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+	/// A single JSON document.
+	Json,
+	/// A directory of CSV files, one per table: `proposals`, `votes`, `voted`, `voters`, `buckets`, `contributions`
+	/// and `refunds`.
+	Csv,
+}
+
+/// Export the proposals, bucket assignments, voter credits, votes and outcomes of a round.
+///
+/// The round is read from the node's database, so the node need not be running. Balances are written as strings
+/// in JSON, and accounts in SS58.
+#[derive(Debug, clap::Parser)]
+pub struct ExportRoundCmd {
+	/// The round to export.
+	#[clap(value_name = "ROUND")]
+	pub round: VotingRoundId,
+
+	/// Block hash or number to read the round at. Defaults to the best block.
+	#[clap(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Format of the export.
+	#[clap(long, value_enum, default_value = "json")]
+	pub format: ExportFormat,
+
+	/// File to write the JSON document to, or directory to write the CSV files to. JSON goes to stdout if unset.
+	#[clap(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RoundDocument {
+	round: VotingRoundId,
+	block_number: BlockNumber,
+	block_hash: String,
+	initializer: String,
+	phase: &'static str,
+	mode: &'static str,
+	/// Only set for funding rounds.
+	matching_pool: Option<String>,
+	ballot: &'static str,
+	/// In parts per billion of the credits registered in a bucket.
+	quorum: u32,
+	/// Drawn when the proposals are shuffled into buckets.
+	randomness: Option<String>,
+	proposals: Vec<ProposalDocument>,
+	buckets: Vec<BucketDocument>,
+	voters: Vec<VoterDocument>,
+	unclaimed_refunds: Vec<AmountDocument>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposalDocument {
+	proposal_id: ProposalCount,
+	proposer: String,
+	class: &'static str,
+	/// Not set until the proposals are put into buckets.
+	bucket_id: Option<BucketId>,
+	ayes: Vec<String>,
+	nays: Vec<String>,
+	abstains: Vec<String>,
+	/// In the order they voted. Votes are not linked to their voter.
+	voted: Vec<String>,
+	contributions: Vec<AmountDocument>,
+	/// Only set once the proposal is tallied.
+	outcome: Option<OutcomeDocument>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutcomeDocument {
+	ayes: String,
+	nays: String,
+	abstains: String,
+	threshold: &'static str,
+	threshold_parameter: Option<String>,
+	outcome: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BucketDocument {
+	bucket_id: BucketId,
+	credits: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoterDocument {
+	who: String,
+	bucket_id: BucketId,
+	bonded: String,
+	remaining: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AmountDocument {
+	who: String,
+	amount: String,
+}
+
+impl ExportRoundCmd {
+	/// Run the command.
+	pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: StorageProvider<Block, BE> + HeaderBackend<Block>,
+		BE: Backend<Block>,
+	{
+		let at = match &self.at {
+			Some(at) => at.parse::<Block>()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let block_hash = client.expect_block_hash_from_id(&at)?;
+		let block_number = client.expect_block_number_from_id(&at)?;
+
+		let export = read_round(&*client, &at, self.round)?.ok_or_else(|| {
+			format!("Round {} does not exist at block {}, or has been reaped. Try an earlier block.", self.round, block_number)
+		})?;
+		let document = document(self.round, block_number, format!("{:?}", block_hash), export);
+
+		match (self.format, &self.output) {
+			(ExportFormat::Json, None) => {
+				serde_json::to_writer_pretty(std::io::stdout(), &document).map_err(|e| e.to_string())?;
+				println!();
+			},
+			(ExportFormat::Json, Some(path)) => {
+				let file = fs::File::create(path)?;
+				serde_json::to_writer_pretty(file, &document).map_err(|e| e.to_string())?;
+			},
+			(ExportFormat::Csv, None) => return Err("CSV exports need an `--output` directory.".into()),
+			(ExportFormat::Csv, Some(path)) => write_csv(path, &document)?,
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportRoundCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+fn document(round: VotingRoundId, block_number: BlockNumber, block_hash: String, export: Export) -> RoundDocument {
+	let amounts = |amounts: Vec<(AccountId, Balance)>| {
+		amounts
+			.into_iter()
+			.map(|(who, amount)| AmountDocument { who: who.to_string(), amount: amount.to_string() })
+			.collect::<Vec<_>>()
+	};
+	let strings = |balances: &[Balance]| balances.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+	let mut voted = export.voters;
+	let mut contributions = export.contributions;
+	let mut outcomes = export.outcomes;
+	let proposals = export
+		.proposals
+		.into_iter()
+		.enumerate()
+		.map(|(i, proposal)| {
+			let proposal_id = i as ProposalCount;
+			let outcome = outcomes.iter().position(|(id, _)| *id == proposal_id).map(|position| {
+				let result = outcomes.remove(position).1;
+				let (threshold, threshold_parameter) = threshold_name(&result.threshold);
+				OutcomeDocument {
+					ayes: result.ayes.to_string(),
+					nays: result.nays.to_string(),
+					abstains: result.abstains.to_string(),
+					threshold,
+					threshold_parameter,
+					outcome: outcome_name(&result.outcome),
+				}
+			});
+			ProposalDocument {
+				proposal_id,
+				proposer: proposal.initializer.to_string(),
+				class: class_name(&proposal.class),
+				bucket_id: proposal.bucket_id,
+				ayes: strings(&proposal.ayes),
+				nays: strings(&proposal.nays),
+				abstains: strings(&proposal.abstains),
+				voted: take(&mut voted, proposal_id).into_iter().map(|who| who.to_string()).collect(),
+				contributions: amounts(take(&mut contributions, proposal_id)),
+				outcome,
+			}
+		})
+		.collect();
+
+	let (mode, matching_pool) = match export.round.mode {
+		RoundMode::Governance => ("Governance", None),
+		RoundMode::Funding { matching_pool } => ("Funding", Some(matching_pool.to_string())),
+	};
+	RoundDocument {
+		round,
+		block_number,
+		block_hash,
+		initializer: export.round.initializer.to_string(),
+		phase: phase_name(&export.round.phase),
+		mode,
+		matching_pool,
+		ballot: ballot_name(&export.round.ballot),
+		quorum: export.round.quorum.deconstruct(),
+		randomness: export.randomness.map(|random| format!("{:?}", random)),
+		proposals,
+		buckets: export
+			.bucket_credits
+			.into_iter()
+			.map(|(bucket_id, credits)| BucketDocument { bucket_id, credits: credits.to_string() })
+			.collect(),
+		voters: export
+			.registrations
+			.into_iter()
+			.map(|(who, bucket_id, bond)| VoterDocument {
+				who: who.to_string(),
+				bucket_id,
+				bonded: bond.total.to_string(),
+				remaining: bond.remaining.to_string(),
+			})
+			.collect(),
+		unclaimed_refunds: amounts(export.unclaimed_refunds),
+	}
+}
+
+// the entry of a proposal in a list of them, if it has one
+fn take<V>(lists: &mut Vec<(ProposalCount, Vec<V>)>, proposal_id: ProposalCount) -> Vec<V> {
+	match lists.iter().position(|(id, _)| *id == proposal_id) {
+		Some(position) => lists.remove(position).1,
+		None => Vec::new(),
+	}
+}
+
+// none of the fields can hold a comma or a quote, so they are written as is
+fn write_table(path: PathBuf, header: &str, rows: impl IntoIterator<Item = Vec<String>>) -> std::io::Result<()> {
+	let mut file = fs::File::create(path)?;
+	writeln!(file, "{}", header)?;
+	for row in rows {
+		writeln!(file, "{}", row.join(","))?;
+	}
+	Ok(())
+}
+
+fn write_csv(dir: &PathBuf, document: &RoundDocument) -> std::io::Result<()> {
+	fs::create_dir_all(dir)?;
+	let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+
+	write_table(
+		dir.join("proposals.csv"),
+		"round,proposal_id,proposer,class,bucket_id,ayes,nays,abstains,threshold,threshold_parameter,outcome",
+		document.proposals.iter().map(|proposal| {
+			let outcome = proposal.outcome.as_ref();
+			vec![
+				document.round.to_string(),
+				proposal.proposal_id.to_string(),
+				proposal.proposer.clone(),
+				proposal.class.into(),
+				proposal.bucket_id.map(|id| id.to_string()).unwrap_or_default(),
+				outcome.map(|o| o.ayes.clone()).unwrap_or_default(),
+				outcome.map(|o| o.nays.clone()).unwrap_or_default(),
+				outcome.map(|o| o.abstains.clone()).unwrap_or_default(),
+				outcome.map(|o| o.threshold.into()).unwrap_or_default(),
+				outcome.map(|o| optional(&o.threshold_parameter)).unwrap_or_default(),
+				outcome.map(|o| o.outcome.into()).unwrap_or_default(),
+			]
+		}),
+	)?;
+
+	// in the order of the leaves of the votes tree, see `quadratic_voting_pallet::merkle::vote_leaves`
+	write_table(
+		dir.join("votes.csv"),
+		"round,proposal_id,direction,index,votes",
+		document.proposals.iter().flat_map(|proposal| {
+			[
+				(VoteDirection::Aye, &proposal.ayes),
+				(VoteDirection::Nay, &proposal.nays),
+				(VoteDirection::Abstain, &proposal.abstains),
+			]
+			.into_iter()
+			.flat_map(move |(direction, votes)| {
+				votes.iter().enumerate().map(move |(index, vote)| {
+					vec![
+						document.round.to_string(),
+						proposal.proposal_id.to_string(),
+						direction_name(&direction).into(),
+						index.to_string(),
+						vote.clone(),
+					]
+				})
+			})
+		}),
+	)?;
+
+	write_table(
+		dir.join("voted.csv"),
+		"round,proposal_id,position,who",
+		document.proposals.iter().flat_map(|proposal| {
+			proposal.voted.iter().enumerate().map(move |(position, who)| {
+				vec![document.round.to_string(), proposal.proposal_id.to_string(), position.to_string(), who.clone()]
+			})
+		}),
+	)?;
+
+	write_table(
+		dir.join("voters.csv"),
+		"round,who,bucket_id,bonded,remaining",
+		document.voters.iter().map(|voter| {
+			vec![
+				document.round.to_string(),
+				voter.who.clone(),
+				voter.bucket_id.to_string(),
+				voter.bonded.clone(),
+				voter.remaining.clone(),
+			]
+		}),
+	)?;
+
+	write_table(
+		dir.join("buckets.csv"),
+		"round,bucket_id,credits",
+		document
+			.buckets
+			.iter()
+			.map(|bucket| vec![document.round.to_string(), bucket.bucket_id.to_string(), bucket.credits.clone()]),
+	)?;
+
+	write_table(
+		dir.join("contributions.csv"),
+		"round,proposal_id,who,amount",
+		document.proposals.iter().flat_map(|proposal| {
+			proposal.contributions.iter().map(move |contribution| {
+				vec![
+					document.round.to_string(),
+					proposal.proposal_id.to_string(),
+					contribution.who.clone(),
+					contribution.amount.clone(),
+				]
+			})
+		}),
+	)?;
+
+	write_table(
+		dir.join("refunds.csv"),
+		"round,who,amount",
+		document
+			.unclaimed_refunds
+			.iter()
+			.map(|refund| vec![document.round.to_string(), refund.who.clone(), refund.amount.clone()]),
+	)
+}
@@ -7,7 +7,12 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod dev_seal;
+mod export_round;
 mod rpc;
+mod simulate_round;
+mod threshold_keygen;
+mod verify_round;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
@@ -7,9 +7,11 @@
 
 use std::sync::Arc;
 
-use parachain_template_runtime::{opaque::Block, AccountId, Balance, Index as Nonce};
+use futures::channel::mpsc;
+use parachain_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index as Nonce, Runtime};
 
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, Backend, BlockchainEvents, StorageProvider};
+use sc_consensus_manual_seal::EngineCommand;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
@@ -27,15 +29,22 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Runs the tasks that feed subscriptions
+	pub subscription_executor: SubscriptionTaskExecutor,
+	/// Sends seal commands to a `--dev-seal` node, which serves `engine_*` and `dev_*` methods with it
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
 }
 
 /// Instantiate all RPC extensions.
-pub fn create_full<C, P>(
+pub fn create_full<C, P, B>(
 	deps: FullDeps<C, P>,
 ) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
 where
+	B: Backend<Block> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, B>
 		+ AuxStore
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ Send
@@ -43,16 +52,29 @@ where
 		+ 'static,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+	C::Api: quadratic_voting_rpc::QuadraticVotingRuntimeApi<Block, AccountId, BlockNumber, Balance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 {
+	use crate::dev_seal::{DevBlocks, DevBlocksApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use quadratic_voting_rpc::{
+		QuadraticVoting, QuadraticVotingApiServer, QuadraticVotingEvents, QuadraticVotingEventsApiServer,
+	};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, subscription_executor, command_sink } = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(QuadraticVoting::<_, _, Balance>::new(client.clone()).into_rpc())?;
+	module.merge(QuadraticVotingEvents::<_, _, B, Runtime>::new(client, subscription_executor).into_rpc())?;
+
+	if let Some(command_sink) = command_sink {
+		module.merge(ManualSeal::new(command_sink.clone()).into_rpc())?;
+		module.merge(DevBlocks::new(command_sink).into_rpc())?;
+	}
 	Ok(module)
 }
@@ -4,12 +4,13 @@
 use std::{sync::Arc, time::Duration};
 
 // rpc
+use futures::{channel::mpsc, stream, StreamExt};
 use jsonrpsee::RpcModule;
 
 use cumulus_client_cli::CollatorOptions;
 // Local Runtime Types
 use parachain_template_runtime::{
-	opaque::Block, AccountId, Balance, Hash, Index as Nonce, RuntimeApi,
+	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index as Nonce, RuntimeApi,
 };
 
 // Cumulus Imports
@@ -26,6 +27,8 @@ use cumulus_relay_chain_rpc_interface::RelayChainRPCInterface;
 
 // Substrate Imports
 use sc_client_api::ExecutorProvider;
+use sc_consensus::LongestChain;
+use sc_consensus_manual_seal::{run_manual_seal, EngineCommand, ManualSealParams};
 use sc_executor::NativeElseWasmExecutor;
 use sc_network::NetworkService;
 use sc_service::{Configuration, PartialComponents, Role, TFullBackend, TFullClient, TaskManager};
@@ -37,6 +40,8 @@ use substrate_prometheus_endpoint::Registry;
 
 use polkadot_service::CollatorPair;
 
+use crate::cli::DevSeal;
+
 /// Native executor instance.
 pub struct TemplateRuntimeExecutor;
 
@@ -220,7 +225,8 @@ where
 		+ sp_block_builder::BlockBuilder<Block>
 		+ cumulus_primitives_core::CollectCollationInfo<Block>
 		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
-		+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+		+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
+		+ quadratic_voting_rpc::QuadraticVotingRuntimeApi<Block, AccountId, BlockNumber, Balance>,
 	sc_client_api::StateBackendFor<TFullBackend<Block>, Block>: sp_api::StateBackend<BlakeTwo256>,
 	Executor: sc_executor::NativeExecutionDispatch + 'static,
 	RB: Fn(
@@ -308,14 +314,16 @@ where
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				subscription_executor,
+				command_sink: None,
 			};
 
-			crate::rpc::create_full(deps).map_err(Into::into)
+			crate::rpc::create_full::<_, _, TFullBackend<Block>>(deps).map_err(Into::into)
 		})
 	};
 
@@ -533,3 +541,138 @@ pub async fn start_parachain_node(
 	)
 	.await
 }
+
+/// Build the import queue of a `--dev-seal` node, which imports its own blocks without checking their seal.
+#[allow(clippy::type_complexity)]
+pub fn dev_seal_build_import_queue(
+	client: Arc<TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<TemplateRuntimeExecutor>>>,
+	config: &Configuration,
+	_telemetry: Option<TelemetryHandle>,
+	task_manager: &TaskManager,
+) -> Result<
+	sc_consensus::DefaultImportQueue<
+		Block,
+		TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<TemplateRuntimeExecutor>>,
+	>,
+	sc_service::Error,
+> {
+	Ok(sc_consensus_manual_seal::import_queue(
+		Box::new(client),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	))
+}
+
+/// Start the runtime on its own, without a relay chain, sealing its blocks with `sc-consensus-manual-seal`.
+///
+/// The parachain inherent is mocked, and blocks are sealed as soon as a transaction arrives with
+/// [`DevSeal::Instant`], or only over RPC with [`DevSeal::Manual`]. Either way `engine_createBlock` and
+/// `dev_createBlocks` can seal blocks on demand, to fast-forward the chain.
+pub async fn start_dev_seal_node(
+	config: Configuration,
+	seal: DevSeal,
+	para_id: ParaId,
+) -> sc_service::error::Result<(
+	TaskManager,
+	Arc<TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<TemplateRuntimeExecutor>>>,
+)> {
+	let params = new_partial::<RuntimeApi, TemplateRuntimeExecutor, _>(&config, dev_seal_build_import_queue)?;
+	let (mut telemetry, _) = params.other;
+
+	let client = params.client.clone();
+	let backend = params.backend.clone();
+	let mut task_manager = params.task_manager;
+	let transaction_pool = params.transaction_pool.clone();
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	let (network, system_rpc_tx, start_network) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue: params.import_queue,
+			block_announce_validator_builder: None,
+			warp_sync: None,
+		})?;
+
+	let (command_sink, commands) = mpsc::channel(1024);
+	let commands = match seal {
+		DevSeal::Manual => commands.boxed(),
+		// as `run_instant_seal` would, while still taking commands over RPC
+		DevSeal::Instant => stream::select(
+			commands,
+			transaction_pool.pool().validated_pool().import_notification_stream().map(|_| {
+				EngineCommand::SealNewBlock {
+					create_empty: false,
+					finalize: false,
+					parent_hash: None,
+					sender: None,
+				}
+			}),
+		)
+		.boxed(),
+	};
+
+	let rpc_builder = {
+		let client = client.clone();
+		let transaction_pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: transaction_pool.clone(),
+				deny_unsafe,
+				subscription_executor,
+				command_sink: Some(command_sink.clone()),
+			};
+
+			crate::rpc::create_full::<_, _, TFullBackend<Block>>(deps).map_err(Into::into)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		rpc_builder,
+		client: client.clone(),
+		transaction_pool: transaction_pool.clone(),
+		task_manager: &mut task_manager,
+		config,
+		keystore: params.keystore_container.sync_keystore(),
+		backend: backend.clone(),
+		network,
+		system_rpc_tx,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		telemetry.as_ref().map(|t| t.handle()),
+	);
+
+	let inherent_client = client.clone();
+	let authorship = run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream: commands,
+		select_chain: LongestChain::new(backend),
+		consensus_data_provider: None,
+		create_inherent_data_providers: move |parent, ()| {
+			let client = inherent_client.clone();
+			async move {
+				crate::dev_seal::inherent_data_providers::<_, TFullBackend<Block>>(&*client, parent, para_id)
+			}
+		},
+	});
+	task_manager
+		.spawn_essential_handle()
+		.spawn_blocking("manual-seal", Some("block-authoring"), authorship);
+
+	start_network.start_network();
+
+	Ok((task_manager, client))
+}
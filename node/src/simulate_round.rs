@@ -0,0 +1,432 @@
+//! Simulation of a voting round over a synthetic population, to see the effect of the runtime's parameters.
+
+use std::collections::BTreeMap;
+
+use codec::Encode;
+use frame_support::{
+	traits::{GenesisBuild, Get, Hooks},
+	weights::Weight,
+};
+use parachain_template_runtime::{
+	AccountId, Balance, BlockNumber, Call, Hash, Origin, QuadraticVotingPallet, QuadraticVotingPalletConfig,
+	RandomnessCollectiveFlip, Runtime, RuntimeBlockWeights, System, UNIT,
+};
+use polkadot_primitives::v2::MAX_POV_SIZE;
+use quadratic_voting_pallet::{
+	BallotMode, BucketId, Config, GenesisRound, Outcome, ProposalClass, ProposalCount, RoundMode, VoteDirection,
+	VotingPhases, VotingRoundId,
+};
+use quadratic_voting_rpc::{class_name, outcome_name, phase_name};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use sp_core::blake2_256;
+use sp_runtime::{traits::Dispatchable, Perbill};
+
+/// The round of the simulation, started at genesis.
+const ROUND: VotingRoundId = 1;
+
+/// What every account is endowed with on top of its credits, for bonds and identity deposits.
+const FUNDS: Balance = 1000 * UNIT;
+
+/// How the credits of the voters are distributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CreditDistribution {
+	/// Every voter has the mean.
+	Equal,
+	/// Uniformly between one credit and twice the mean.
+	Uniform,
+	/// A Pareto distribution of the given mean: a few voters hold most of the credits.
+	Pareto,
+}
+
+/// How the voters spend the credits they registered in a bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum VotingStrategy {
+	/// All of them on a single proposal of the bucket.
+	Concentrated,
+	/// Evenly across every proposal of the bucket.
+	Spread,
+	/// Over a random subset of the proposals of the bucket, in random shares.
+	Random,
+}
+
+/// Simulate a governance or funding round with the pallet logic and parameters of this runtime.
+///
+/// The round runs in memory, from a genesis with the given number of proposals and voters, through to its
+/// finalization. Everything random, from the population to the shuffle of the proposals, is drawn from the seed, so
+/// the same arguments give the same report. The parameters of the pallet are those the runtime is built with:
+/// changing `BucketSize`, `MaxProposals` or the bonds takes a rebuild of the node.
+#[derive(Debug, clap::Parser)]
+pub struct SimulateRoundCmd {
+	/// Number of proposals. Defaults to `MaxProposals`.
+	#[clap(long)]
+	pub proposals: Option<u32>,
+
+	/// Number of voters.
+	#[clap(long, default_value_t = 100)]
+	pub voters: u32,
+
+	/// Distribution of the credits of the voters.
+	#[clap(long, value_enum, default_value = "uniform")]
+	pub credits: CreditDistribution,
+
+	/// Mean of the credits of a voter.
+	#[clap(long, default_value_t = 10_000)]
+	pub mean_credits: Balance,
+
+	/// Number of buckets each voter registers in, splitting their credits evenly.
+	#[clap(long, default_value_t = 1)]
+	pub buckets_per_voter: u32,
+
+	/// How the voters spend their credits.
+	#[clap(long, value_enum, default_value = "spread")]
+	pub strategy: VotingStrategy,
+
+	/// Percentage of the registered voters who vote.
+	#[clap(long, default_value_t = 80)]
+	pub turnout: u8,
+
+	/// Percentage of the votes that are ayes, the rest being nays. Funding rounds only take ayes.
+	#[clap(long, default_value_t = 60)]
+	pub ayes: u8,
+
+	/// Run a funding round with a matching pool of this size, instead of a governance round.
+	#[clap(long)]
+	pub matching_pool: Option<Balance>,
+
+	/// Seed of the simulation.
+	#[clap(long, default_value_t = 0)]
+	pub seed: u64,
+}
+
+// the work done by the pallet's hook in a block
+struct HookStep {
+	block: BlockNumber,
+	from: VotingPhases,
+	to: VotingPhases,
+	weight: Weight,
+	// of the storage proof of the hook, without the runtime code
+	pov: usize,
+}
+
+#[derive(Default)]
+struct Calls {
+	accepted: u32,
+	// by error
+	rejected: BTreeMap<&'static str, u32>,
+}
+
+impl Calls {
+	fn dispatch(&mut self, who: &AccountId, call: quadratic_voting_pallet::Call<Runtime>) -> bool {
+		match Call::QuadraticVotingPallet(call).dispatch(Origin::signed(who.clone())) {
+			Ok(_) => {
+				self.accepted += 1;
+				true
+			},
+			Err(e) => {
+				*self.rejected.entry(e.error.into()).or_default() += 1;
+				false
+			},
+		}
+	}
+
+	fn summary(&self) -> String {
+		let rejected: u32 = self.rejected.values().sum();
+		let mut summary = format!("{} accepted, {} rejected", self.accepted, rejected);
+		if rejected > 0 {
+			let errors: Vec<String> = self.rejected.iter().map(|(error, count)| format!("{}: {}", error, count)).collect();
+			summary.push_str(&format!(" ({})", errors.join(", ")));
+		}
+		summary
+	}
+}
+
+impl SimulateRoundCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let max_proposals = <Runtime as Config>::MaxProposals::get();
+		let proposal_count = self.proposals.unwrap_or(max_proposals);
+		if proposal_count == 0 || proposal_count > max_proposals {
+			return Err(format!("The number of proposals must be between 1 and MaxProposals ({}).", max_proposals).into())
+		}
+		if self.turnout > 100 || self.ayes > 100 {
+			return Err("The turnout and the share of ayes are percentages.".into())
+		}
+		if self.buckets_per_voter == 0 {
+			return Err("Voters must register in at least one bucket.".into())
+		}
+
+		let mut rng = StdRng::seed_from_u64(self.seed);
+		let initializer = account("initializer", 0);
+		let proposers: Vec<(AccountId, ProposalClass)> = (0..proposal_count)
+			.map(|i| {
+				let class = *[ProposalClass::General, ProposalClass::TreasurySpend, ProposalClass::RuntimeParameter]
+					.choose(&mut rng)
+					.expect("there are classes to choose from");
+				(account("proposer", i), class)
+			})
+			.collect();
+		let voters: Vec<(AccountId, Balance)> =
+			(0..self.voters).map(|i| (account("voter", i), self.draw_credits(&mut rng))).collect();
+		let mode = match self.matching_pool {
+			Some(matching_pool) => RoundMode::Funding { matching_pool },
+			None => RoundMode::Governance,
+		};
+
+		let mut storage = frame_system::GenesisConfig::default().build_storage::<Runtime>()?;
+		let genesis = QuadraticVotingPalletConfig {
+			voting_round_id: ROUND - 1,
+			voters: std::iter::once((initializer.clone(), FUNDS + self.matching_pool.unwrap_or_default()))
+				.chain(proposers.iter().map(|(proposer, _)| (proposer.clone(), FUNDS)))
+				.chain(voters.iter().map(|(voter, credits)| (voter.clone(), FUNDS + credits)))
+				.collect(),
+			registrar: Some(initializer.clone()),
+			identities: std::iter::once(&initializer)
+				.chain(proposers.iter().map(|(proposer, _)| proposer))
+				.chain(voters.iter().map(|(voter, _)| voter))
+				.enumerate()
+				.map(|(i, who)| (who.clone(), format!("simulated {}", i).into_bytes()))
+				.collect(),
+			initial_round: Some(GenesisRound {
+				initializer,
+				mode: mode.clone(),
+				ballot: BallotMode::Open,
+				proposals: proposers.clone(),
+				registrations: Vec::new(),
+				open_voting: false,
+			}),
+		};
+		GenesisBuild::<Runtime>::assimilate_storage(&genesis, &mut storage)?;
+		let mut ext = sp_io::TestExternalities::new(storage);
+
+		let last_block = ext
+			.execute_with(|| QuadraticVotingPallet::round_info(ROUND))
+			.ok_or("The round could not be started at genesis.")?
+			.enactment_phase
+			.end_block
+			.saturating_add(1000);
+
+		let mut steps = Vec::new();
+		let mut buckets: BTreeMap<BucketId, Vec<ProposalCount>> = BTreeMap::new();
+		// voter, and the bucket and credits of each of their registrations
+		let mut registrations: Vec<(AccountId, Vec<(BucketId, Balance)>)> = Vec::new();
+		let mut registered = Calls::default();
+		let mut votes = Calls::default();
+		let mut voted = 0u32;
+		let mut credits_voted: Balance = 0;
+		let mut phase = VotingPhases::Proposal;
+		let mut block: BlockNumber = 0;
+		while !matches!(phase, VotingPhases::Finalized | VotingPhases::Cancelled) {
+			block += 1;
+			if block > last_block {
+				return Err(format!("The round was not finalized by block {}.", last_block).into())
+			}
+
+			// the proof only records reads of the backend, so the previous blocks are committed to it first
+			ext.commit_all()?;
+			let ((weight, next), proof) = ext.execute_and_prove(|| {
+				System::initialize(&block, &parent_hash(self.seed, block), &Default::default());
+				RandomnessCollectiveFlip::on_initialize(block);
+				let weight = QuadraticVotingPallet::on_initialize(block);
+				(weight, QuadraticVotingPallet::round_info(ROUND).map_or(VotingPhases::Cancelled, |round| round.phase))
+			});
+			let entered = next != phase;
+			if entered || matches!(phase, VotingPhases::Tallying | VotingPhases::Settling) {
+				steps.push(HookStep { block, from: phase, to: next.clone(), weight, pov: proof.encoded_size() });
+			}
+			phase = next;
+
+			// the voters act as soon as the phase they act in starts
+			ext.execute_with(|| match phase {
+				VotingPhases::PreVoting if entered => {
+					let proposals = QuadraticVotingPallet::export_round(ROUND).map(|round| round.proposals).unwrap_or_default();
+					for (i, proposal) in proposals.iter().enumerate() {
+						buckets.entry(proposal.bucket_id.unwrap_or_default()).or_default().push(i as ProposalCount);
+					}
+					let bucket_ids: Vec<BucketId> = buckets.keys().copied().collect();
+					for (voter, credits) in &voters {
+						let chosen: Vec<BucketId> =
+							bucket_ids.choose_multiple(&mut rng, self.buckets_per_voter as usize).copied().collect();
+						let share = credits / chosen.len().max(1) as Balance;
+						let mut bonds = Vec::new();
+						for bucket_id in chosen {
+							let call = quadratic_voting_pallet::Call::register_to_vote { bucket_id, votes: share };
+							if share > 0 && registered.dispatch(voter, call) {
+								bonds.push((bucket_id, share));
+							}
+						}
+						registrations.push((voter.clone(), bonds));
+					}
+				},
+				VotingPhases::Voting if entered => {
+					for (voter, bonds) in &registrations {
+						if bonds.is_empty() || !rng.gen_ratio(self.turnout.into(), 100) {
+							continue
+						}
+						voted += 1;
+						for (bucket_id, bond) in bonds {
+							for (proposal_id, vote) in self.spend(&mut rng, &buckets[bucket_id], *bond) {
+								let direction = match mode {
+									RoundMode::Funding { .. } => VoteDirection::Aye,
+									RoundMode::Governance if rng.gen_ratio(self.ayes.into(), 100) => VoteDirection::Aye,
+									RoundMode::Governance => VoteDirection::Nay,
+								};
+								let call = quadratic_voting_pallet::Call::vote { proposal_id, vote, direction };
+								if votes.dispatch(voter, call) {
+									credits_voted += vote;
+								}
+							}
+						}
+					}
+				},
+				_ => {},
+			});
+		}
+
+		let export = ext.execute_with(|| QuadraticVotingPallet::export_round(ROUND)).ok_or("The round has been reaped.")?;
+
+		let mode_name = match mode {
+			RoundMode::Governance => "governance",
+			RoundMode::Funding { .. } => "funding",
+		};
+		println!(
+			"Simulated a {} round with open ballots, {} proposals and {} voters, from seed {}.",
+			mode_name, proposal_count, self.voters, self.seed
+		);
+		println!(
+			"BucketSize {}, MaxProposals {}, MaxVotes {}, MaxTalliesPerBlock {}, BondForVotingRound {}, BondForProposal {}, quorum {}%.",
+			<Runtime as Config>::BucketSize::get(),
+			max_proposals,
+			<Runtime as Config>::MaxVotes::get(),
+			<Runtime as Config>::MaxTalliesPerBlock::get(),
+			<Runtime as Config>::BondForVotingRound::get(),
+			<Runtime as Config>::BondForProposal::get(),
+			percent(export.round.quorum),
+		);
+
+		println!();
+		println!("Buckets:");
+		for (bucket_id, proposals) in &buckets {
+			let bonds: Vec<Balance> = registrations
+				.iter()
+				.flat_map(|(_, bonds)| bonds.iter().filter(|(id, _)| id == bucket_id).map(|(_, bond)| *bond))
+				.collect();
+			println!(
+				"\tbucket {}: {} proposals, {} registrations, {} credits",
+				bucket_id,
+				proposals.len(),
+				bonds.len(),
+				bonds.iter().sum::<Balance>(),
+			);
+		}
+		println!("Registrations: {}.", registered.summary());
+		println!("Votes: {}, from {} voters spending {} credits.", votes.summary(), voted, credits_voted);
+
+		let max_weight = RuntimeBlockWeights::get().max_block;
+		println!();
+		println!("Hook of the pallet, in share of a block (PoV without the runtime code):");
+		for step in &steps {
+			let transition = if step.from == step.to {
+				format!("{} step", phase_name(&step.to))
+			} else {
+				format!("{} -> {}", phase_name(&step.from), phase_name(&step.to))
+			};
+			println!(
+				"\tblock {}: {}, weight {} ({}%), PoV {} bytes ({}%)",
+				step.block,
+				transition,
+				step.weight,
+				percent(Perbill::from_rational(step.weight, max_weight)),
+				step.pov,
+				percent(Perbill::from_rational(step.pov as u32, MAX_POV_SIZE)),
+			);
+		}
+
+		println!();
+		println!("Outcomes:");
+		let mut by_class: BTreeMap<&'static str, [u32; 3]> = BTreeMap::new();
+		for (proposal_id, result) in &export.outcomes {
+			let class = class_name(&export.proposals[*proposal_id as usize].class);
+			let counts = by_class.entry("all").or_default();
+			counts[outcome_index(&result.outcome)] += 1;
+			by_class.entry(class).or_default()[outcome_index(&result.outcome)] += 1;
+		}
+		for (class, counts) in &by_class {
+			let counts: Vec<String> = [Outcome::Aye, Outcome::Nay, Outcome::NoQuorum]
+				.iter()
+				.map(|outcome| format!("{} {}", outcome_name(outcome), counts[outcome_index(outcome)]))
+				.collect();
+			println!("\t{}: {}", class, counts.join(", "));
+		}
+		if let RoundMode::Funding { .. } = mode {
+			let contributed: Balance =
+				export.contributions.iter().flat_map(|(_, contributions)| contributions.iter().map(|(_, amount)| amount)).sum();
+			println!("\tcontributed: {}", contributed);
+		}
+
+		Ok(())
+	}
+
+	fn draw_credits(&self, rng: &mut StdRng) -> Balance {
+		let mean = self.mean_credits.max(1);
+		match self.credits {
+			CreditDistribution::Equal => mean,
+			CreditDistribution::Uniform => rng.gen_range(1..=mean.saturating_mul(2)),
+			CreditDistribution::Pareto => {
+				// the shape of the 80/20 rule, scaled to the mean
+				const SHAPE: f64 = 1.16;
+				let scale = mean as f64 * (SHAPE - 1.0) / SHAPE;
+				let u: f64 = rng.gen_range(f64::EPSILON..=1.0);
+				((scale / u.powf(1.0 / SHAPE)) as Balance).max(1)
+			},
+		}
+	}
+
+	// the proposals a voter votes on with the credits of a bucket, and the credits of each vote
+	fn spend(&self, rng: &mut StdRng, proposals: &[ProposalCount], bond: Balance) -> Vec<(ProposalCount, Balance)> {
+		let shares: Vec<(ProposalCount, Balance)> = match self.strategy {
+			VotingStrategy::Concentrated =>
+				proposals.choose(rng).map(|proposal_id| (*proposal_id, 1)).into_iter().collect(),
+			VotingStrategy::Spread => proposals.iter().map(|proposal_id| (*proposal_id, 1)).collect(),
+			VotingStrategy::Random => {
+				let mut chosen = Vec::new();
+				for proposal_id in proposals {
+					if rng.gen_bool(0.5) {
+						chosen.push((*proposal_id, rng.gen_range(1..=100)));
+					}
+				}
+				if chosen.is_empty() {
+					chosen.extend(proposals.choose(rng).map(|proposal_id| (*proposal_id, 1)));
+				}
+				chosen
+			},
+		};
+		let total: Balance = shares.iter().map(|(_, share)| share).sum();
+		shares
+			.into_iter()
+			.map(|(proposal_id, share)| (proposal_id, bond.saturating_mul(share) / total.max(1)))
+			.filter(|(_, credits)| *credits > 0)
+			.collect()
+	}
+}
+
+// a simulated account, out of its role and index
+fn account(role: &str, index: u32) -> AccountId {
+	AccountId::from(blake2_256(&(b"simulate-round", role, index).encode()))
+}
+
+// the chain is not built, so the parents of the blocks, which feed the randomness of the round, are drawn from the seed
+fn parent_hash(seed: u64, block: BlockNumber) -> Hash {
+	Hash::from(blake2_256(&(seed, block).encode()))
+}
+
+fn percent(share: Perbill) -> String {
+	format!("{:.2}", share.deconstruct() as f64 / 10_000_000.0)
+}
+
+fn outcome_index(outcome: &Outcome) -> usize {
+	match outcome {
+		Outcome::Aye => 0,
+		Outcome::Nay => 1,
+		Outcome::NoQuorum => 2,
+	}
+}
@@ -0,0 +1,50 @@
+//! Key generation for the decryption committee of threshold-encrypted voting rounds.
+
+use curve25519_dalek::scalar::Scalar;
+use quadratic_voting_pallet::threshold;
+use rand::{rngs::OsRng, RngCore};
+use sp_core::hexdisplay::HexDisplay;
+
+/// Deal a threshold key for the decryption committee.
+///
+/// This is a trusted dealer: whoever runs it sees every secret share, and should hand each one to its
+/// member over a secure channel and then discard it. The public key and verification keys go on chain
+/// with `set_threshold_key`, in the order of the share indices.
+#[derive(Debug, clap::Parser)]
+pub struct ThresholdKeygenCmd {
+	/// Number of shares needed to decrypt a ballot.
+	#[clap(long)]
+	pub threshold: u32,
+
+	/// Number of committee members.
+	#[clap(long)]
+	pub members: u32,
+}
+
+impl ThresholdKeygenCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		if self.threshold == 0 || self.threshold > self.members {
+			return Err("The threshold must be between 1 and the number of members.".into())
+		}
+
+		let dealing = threshold::deal(self.threshold, self.members, || {
+			let mut bytes = [0u8; 64];
+			OsRng.fill_bytes(&mut bytes);
+			Scalar::from_bytes_mod_order_wide(&bytes)
+		});
+
+		println!("public key: 0x{}", HexDisplay::from(&dealing.public_key));
+		println!("threshold: {}", self.threshold);
+		for (index, secret, verification_key) in &dealing.shares {
+			println!(
+				"member {}: verification key 0x{}, secret share 0x{}",
+				index,
+				HexDisplay::from(verification_key),
+				HexDisplay::from(secret),
+			);
+		}
+
+		Ok(())
+	}
+}
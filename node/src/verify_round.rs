@@ -0,0 +1,630 @@
+//! Offline verification of a voting round: its extrinsics and events are replayed from the node's database, and the
+//! bucket assignments, credits and tallies they lead to are compared with what is on chain.
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::Arc,
+};
+
+use codec::{Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use parachain_template_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, Call, Event, Hash, QuadraticVotingPallet, Runtime,
+	UncheckedExtrinsic,
+};
+use quadratic_voting_pallet::{
+	BallotMode, BucketId, Config, Outcome, ProposalClass, ProposalCount, ProposalResult, RoundMode, Threshold,
+	VoteDirection, VoterBond, VotingPhases, VotingRoundId,
+};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Get, IntegerSquareRoot},
+	MultiAddress, Perbill,
+};
+
+use crate::export_round::{read_round, Export};
+
+type PalletEvent = quadratic_voting_pallet::Event<Runtime>;
+
+// the denominator of a `Perbill`
+const BILLION: Balance = 1_000_000_000;
+
+/// Replay a round from the node's database, and check the result on chain.
+///
+/// Every quadratic-voting extrinsic and event of the round is replayed, from the block the round started at: the
+/// proposals are shuffled into buckets with the randomness recorded for the round, the credits of every voter are
+/// spent as their votes are cast, and the proposals are tallied. Any difference with the events, the storage or the
+/// `ProposalOutcome` of the round is reported, and makes the command fail.
+///
+/// Only open and commit-reveal rounds can be replayed, once they are tallied.
+#[derive(Debug, clap::Parser)]
+pub struct VerifyRoundCmd {
+	/// The round to verify.
+	#[clap(value_name = "ROUND")]
+	pub round: VotingRoundId,
+
+	/// Block hash or number to replay the round up to, and to compare it with. Defaults to the best block.
+	#[clap(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VerifyRoundCmd {
+	/// Run the command.
+	pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		C: StorageProvider<Block, BE> + HeaderBackend<Block> + BlockBackend<Block>,
+		BE: Backend<Block>,
+	{
+		let at = match &self.at {
+			Some(at) => at.parse::<Block>()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let last = client.expect_block_number_from_id(&at)?;
+		let export = read_round(&*client, &at, self.round)?.ok_or_else(|| {
+			format!("Round {} does not exist at block {}, or has been reaped. Try an earlier block.", self.round, last)
+		})?;
+
+		match export.round.ballot {
+			BallotMode::Open | BallotMode::CommitReveal => {},
+			// their votes are never on chain
+			BallotMode::Encrypted | BallotMode::ThresholdEncrypted =>
+				return Err(format!("Round {} has encrypted ballots, which cannot be replayed.", self.round).into()),
+		}
+		match export.round.phase {
+			VotingPhases::PostVoting | VotingPhases::Settling | VotingPhases::Enactment | VotingPhases::Finalized => {},
+			VotingPhases::Proposal |
+			VotingPhases::PreVoting |
+			VotingPhases::Voting |
+			VotingPhases::Reveal |
+			VotingPhases::Tallying |
+			VotingPhases::Cancelled =>
+				return Err(format!("Round {} is not tallied at block {}. Try a later block.", self.round, last).into()),
+		}
+
+		// a round started at genesis has no extrinsics for what it was started with
+		let first = export.round.proposal_phase.start_block;
+		let mut replay = match first {
+			0 => match read_round(&*client, &BlockId::Number(0), self.round)? {
+				Some(genesis) => Replay::from_genesis(self.round, &export, genesis),
+				None => Replay::new(self.round, &export),
+			},
+			_ => Replay::new(self.round, &export),
+		};
+
+		let events_key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+		for number in first.max(1)..=last {
+			let hash = client.hash(number)?.ok_or_else(|| format!("Block {} is not in the database.", number))?;
+			let id = BlockId::Hash(hash);
+			let extrinsics = client
+				.block_body(&id)?
+				.ok_or_else(|| format!("The body of block {} is not in the database.", number))?
+				.into_iter()
+				.map(|extrinsic| UncheckedExtrinsic::decode(&mut &extrinsic.encode()[..]).ok())
+				.collect::<Vec<_>>();
+			let events = match client.storage(&id, &events_key)? {
+				Some(events) => Vec::<EventRecord<Event, Hash>>::decode(&mut &events.0[..])
+					.map_err(|e| format!("Could not decode the events of block {}: {}", number, e))?,
+				None => Vec::new(),
+			};
+			replay.block(number, &extrinsics, events);
+		}
+
+		let mismatches = replay.finish(&export);
+		println!(
+			"Replayed round {} from block {} to block {}: {} proposals, {} registrations, {} votes.",
+			self.round,
+			first,
+			last,
+			replay.proposals.len(),
+			replay.bonds.len(),
+			replay.voted.len(),
+		);
+		if mismatches.is_empty() {
+			println!("The tally on chain matches the replay.");
+			return Ok(())
+		}
+		for mismatch in &mismatches {
+			println!("mismatch: {}", mismatch);
+		}
+		Err(format!("{} mismatches between round {} on chain and its replay.", mismatches.len(), self.round).into())
+	}
+}
+
+impl CliConfiguration for VerifyRoundCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+struct ReplayedProposal {
+	proposer: AccountId,
+	class: ProposalClass,
+	bucket_id: Option<BucketId>,
+	ayes: Vec<Balance>,
+	nays: Vec<Balance>,
+	abstains: Vec<Balance>,
+}
+
+// the state of a round, as its extrinsics and events leave it
+struct Replay {
+	round: VotingRoundId,
+	mode: RoundMode<Balance>,
+	randomness: Option<Hash>,
+	// in the order of the proposal ids: the submission order until they are shuffled, and the bucket order after
+	proposals: Vec<ReplayedProposal>,
+	// the bucket assignments of the block being replayed
+	assigned: Vec<(ProposalCount, ProposalCount, BucketId)>,
+	bonds: BTreeMap<(AccountId, BucketId), VoterBond<Balance>>,
+	bucket_credits: BTreeMap<BucketId, Balance>,
+	// proposal, voter: the commitment and the credits it holds
+	commitments: BTreeMap<(ProposalCount, AccountId), (Hash, Balance)>,
+	voted: BTreeSet<(ProposalCount, AccountId)>,
+	contributions: BTreeMap<ProposalCount, Vec<(AccountId, Balance)>>,
+	tallied: BTreeMap<ProposalCount, (Balance, Balance, Balance, Outcome)>,
+	mismatches: Vec<String>,
+}
+
+// the events that the replay of an extrinsic gives back
+fn is_replayed(event: &PalletEvent) -> bool {
+	matches!(
+		event,
+		PalletEvent::ProposalSubmitted { .. } |
+			PalletEvent::VoterRegistered { .. } |
+			PalletEvent::VoteCommitted { .. } |
+			PalletEvent::VoteCast { .. }
+	)
+}
+
+impl Replay {
+	fn new(round: VotingRoundId, export: &Export) -> Self {
+		Replay {
+			round,
+			mode: export.round.mode.clone(),
+			randomness: export.randomness,
+			proposals: Vec::new(),
+			assigned: Vec::new(),
+			bonds: BTreeMap::new(),
+			bucket_credits: BTreeMap::new(),
+			commitments: BTreeMap::new(),
+			voted: BTreeSet::new(),
+			contributions: BTreeMap::new(),
+			tallied: BTreeMap::new(),
+			mismatches: Vec::new(),
+		}
+	}
+
+	// the proposals and registrations of a round started at genesis, as they are at block 0
+	fn from_genesis(round: VotingRoundId, export: &Export, genesis: Export) -> Self {
+		let mut replay = Replay::new(round, export);
+		replay.proposals = genesis
+			.proposals
+			.into_iter()
+			.map(|proposal| ReplayedProposal {
+				proposer: proposal.initializer,
+				class: proposal.class,
+				bucket_id: proposal.bucket_id,
+				ayes: Vec::new(),
+				nays: Vec::new(),
+				abstains: Vec::new(),
+			})
+			.collect();
+		replay.bonds = genesis.registrations.into_iter().map(|(who, bucket_id, bond)| ((who, bucket_id), bond)).collect();
+		replay.bucket_credits = genesis.bucket_credits.into_iter().collect();
+		replay
+	}
+
+	fn block(&mut self, number: BlockNumber, extrinsics: &[Option<UncheckedExtrinsic>], events: Vec<EventRecord<Event, Hash>>) {
+		let mut succeeded = BTreeSet::new();
+		let mut by_extrinsic: BTreeMap<u32, Vec<PalletEvent>> = BTreeMap::new();
+		for record in &events {
+			match (&record.phase, &record.event) {
+				(Phase::ApplyExtrinsic(i), Event::System(frame_system::Event::ExtrinsicSuccess { .. })) => {
+					succeeded.insert(*i);
+				},
+				(Phase::ApplyExtrinsic(i), Event::QuadraticVotingPallet(event)) if event.round() == Some(self.round) =>
+					by_extrinsic.entry(*i).or_default().push(event.clone()),
+				_ => {},
+			}
+		}
+
+		// the events of the hooks come before those of the extrinsics, which are replayed one at a time
+		let mut replayed = BTreeSet::new();
+		for record in events {
+			let event = match record.event {
+				Event::QuadraticVotingPallet(event) if event.round() == Some(self.round) => event,
+				_ => continue,
+			};
+			match record.phase {
+				Phase::ApplyExtrinsic(i) =>
+					if replayed.insert(i) {
+						let extrinsic = extrinsics.get(i as usize).and_then(Option::as_ref);
+						self.extrinsic(number, i, extrinsic, succeeded.contains(&i), &by_extrinsic[&i]);
+					},
+				Phase::Initialization | Phase::Finalization => self.hook_event(number, event),
+			}
+		}
+	}
+
+	fn extrinsic(
+		&mut self,
+		number: BlockNumber,
+		index: u32,
+		extrinsic: Option<&UncheckedExtrinsic>,
+		succeeded: bool,
+		events: &[PalletEvent],
+	) {
+		let found: Vec<_> = events.iter().filter(|event| is_replayed(event)).cloned().collect();
+		let signer = extrinsic.and_then(|extrinsic| match &extrinsic.signature {
+			Some((MultiAddress::Id(who), _, _)) => Some(who.clone()),
+			_ => None,
+		});
+		let expected = match (extrinsic.map(|extrinsic| &extrinsic.function), signer) {
+			(Some(Call::QuadraticVotingPallet(call)), Some(who)) if succeeded => self.call(who, call),
+			// calls dispatched through other pallets are not replayed
+			_ if found.is_empty() => Ok(Vec::new()),
+			_ => Err("the extrinsic cannot be replayed".into()),
+		};
+		match expected {
+			Ok(expected) if expected == found => {},
+			Ok(expected) => self.mismatches.push(format!(
+				"block {}, extrinsic {}: the replay gives the events {:?}, but the chain has {:?}",
+				number, index, expected, found
+			)),
+			Err(e) => self.mismatches.push(format!(
+				"block {}, extrinsic {}: {}, but the chain has the events {:?}",
+				number, index, e, found
+			)),
+		}
+	}
+
+	// the events the call emits, or why it should have failed
+	fn call(&mut self, who: AccountId, call: &quadratic_voting_pallet::Call<Runtime>) -> Result<Vec<PalletEvent>, String> {
+		use quadratic_voting_pallet::Call as PalletCall;
+
+		let round = self.round;
+		let event = match call {
+			PalletCall::submit_proposal { class } => {
+				let proposal = self.proposals.len() as ProposalCount;
+				self.proposals.push(ReplayedProposal {
+					proposer: who.clone(),
+					class: *class,
+					bucket_id: None,
+					ayes: Vec::new(),
+					nays: Vec::new(),
+					abstains: Vec::new(),
+				});
+				PalletEvent::ProposalSubmitted { round, proposal, proposer: who, class: *class }
+			},
+			PalletCall::register_to_vote { bucket_id, votes } => {
+				// re-registering replaces the previous registration
+				let previous = self.bonds.get(&(who.clone(), *bucket_id)).map_or(0, |bond| bond.total);
+				let credits = self.bucket_credits.entry(*bucket_id).or_default();
+				*credits = credits.saturating_sub(previous).saturating_add(*votes);
+				self.bonds.insert((who.clone(), *bucket_id), VoterBond { total: *votes, remaining: *votes });
+				PalletEvent::VoterRegistered { round, bucket: *bucket_id, who, credits: *votes }
+			},
+			PalletCall::vote { proposal_id, vote, direction } => self.cast(who, *proposal_id, *vote, direction.clone())?,
+			PalletCall::commit_vote { proposal_id, commitment, credits } => {
+				let bond = self.bond(&who, *proposal_id)?;
+				if *credits > bond.remaining {
+					return Err(format!("{} committed more credits than they have left", who))
+				}
+				bond.remaining -= *credits;
+				if self.commitments.insert((*proposal_id, who.clone()), (*commitment, *credits)).is_some() {
+					return Err(format!("{} committed twice to proposal {}", who, proposal_id))
+				}
+				PalletEvent::VoteCommitted { round, proposal: *proposal_id, who, credits: *credits }
+			},
+			PalletCall::reveal_vote { proposal_id, vote, direction, salt } => {
+				let (commitment, credits) = match self.commitments.remove(&(*proposal_id, who.clone())) {
+					Some(commitment) => commitment,
+					None => return Err(format!("{} revealed a vote they did not commit to", who)),
+				};
+				if QuadraticVotingPallet::vote_commitment(&who, *proposal_id, *vote, direction, salt) != commitment {
+					return Err(format!("{} revealed a vote that does not open their commitment", who))
+				}
+				if *vote > credits {
+					return Err(format!("{} revealed more credits than they committed", who))
+				}
+				// the held credits are released, and spent like a regular vote
+				self.bond(&who, *proposal_id)?.remaining += credits;
+				self.cast(who, *proposal_id, *vote, direction.clone())?
+			},
+			_ => return Ok(Vec::new()),
+		};
+		Ok(vec![event])
+	}
+
+	// the bond of a voter in the bucket of a proposal
+	fn bond(&mut self, who: &AccountId, proposal_id: ProposalCount) -> Result<&mut VoterBond<Balance>, String> {
+		let bucket_id = match self.proposals.get(proposal_id as usize).and_then(|proposal| proposal.bucket_id) {
+			Some(bucket_id) => bucket_id,
+			None => return Err(format!("proposal {} is not in a bucket", proposal_id)),
+		};
+		self.bonds
+			.get_mut(&(who.clone(), bucket_id))
+			.ok_or_else(|| format!("{} is not registered in bucket {}", who, bucket_id))
+	}
+
+	fn cast(
+		&mut self,
+		who: AccountId,
+		proposal_id: ProposalCount,
+		credits: Balance,
+		direction: VoteDirection,
+	) -> Result<PalletEvent, String> {
+		if !self.voted.insert((proposal_id, who.clone())) {
+			return Err(format!("{} voted twice on proposal {}", who, proposal_id))
+		}
+		let funding = matches!(self.mode, RoundMode::Funding { .. });
+		if funding && direction != VoteDirection::Aye {
+			return Err(format!("{} voted against proposal {} of a funding round", who, proposal_id))
+		}
+		let bond = self.bond(&who, proposal_id)?;
+		if credits > bond.remaining {
+			return Err(format!("{} voted with more credits than they have left", who))
+		}
+
+		let votes = credits.integer_sqrt();
+		// contributions are spent in full, while other votes only spend their square root
+		bond.remaining -= if funding { credits } else { votes };
+		if funding {
+			self.contributions.entry(proposal_id).or_default().push((who.clone(), credits));
+		}
+		let proposal = &mut self.proposals[proposal_id as usize];
+		match direction {
+			VoteDirection::Aye => proposal.ayes.push(votes),
+			VoteDirection::Nay => proposal.nays.push(votes),
+			VoteDirection::Abstain => proposal.abstains.push(votes),
+		}
+		Ok(PalletEvent::VoteCast { round: self.round, proposal: proposal_id, who, votes, direction })
+	}
+
+	fn hook_event(&mut self, number: BlockNumber, event: PalletEvent) {
+		match event {
+			PalletEvent::BucketAssigned { submitted, proposal, bucket, .. } => self.assigned.push((submitted, proposal, bucket)),
+			// the buckets are assigned just before the transition
+			PalletEvent::PhaseTransition { phase: VotingPhases::PreVoting, .. } => self.shuffle(number),
+			PalletEvent::UnrevealedCommitReleased { proposal, who, credits, .. } =>
+				match self.commitments.remove(&(proposal, who.clone())) {
+					Some((_, held)) if held == credits => match self.bond(&who, proposal) {
+						Ok(bond) => bond.remaining += credits,
+						Err(e) => self.mismatches.push(format!("block {}: {}", number, e)),
+					},
+					_ => self.mismatches.push(format!(
+						"block {}: {} credits of {} were released, but they did not commit them to proposal {}",
+						number, credits, who, proposal
+					)),
+				},
+			PalletEvent::UnrevealedCommitSlashed { proposal, who, amount, .. } =>
+				match self.commitments.remove(&(proposal, who.clone())) {
+					Some((_, held)) if amount <= held => match self.bond(&who, proposal) {
+						Ok(bond) => {
+							bond.total = bond.total.saturating_sub(amount);
+							// the slashed credits leave the bucket too, which has to exist for the bond to be found
+							if let Some(bucket_id) = self.proposals[proposal as usize].bucket_id {
+								let credits = self.bucket_credits.entry(bucket_id).or_default();
+								*credits = credits.saturating_sub(amount);
+							}
+						},
+						Err(e) => self.mismatches.push(format!("block {}: {}", number, e)),
+					},
+					_ => self.mismatches.push(format!(
+						"block {}: {} credits of {} were slashed, but they did not commit them to proposal {}",
+						number, amount, who, proposal
+					)),
+				},
+			PalletEvent::ProposalTallied { proposal, ayes, nays, abstains, outcome, .. } => {
+				self.tallied.insert(proposal, (ayes, nays, abstains, outcome));
+			},
+			event if is_replayed(&event) =>
+				self.mismatches.push(format!("block {}: {:?} was not emitted by an extrinsic", number, event)),
+			_ => {},
+		}
+	}
+
+	// puts the proposals into buckets, in the order drawn from the randomness of the round
+	fn shuffle(&mut self, number: BlockNumber) {
+		let assigned = std::mem::take(&mut self.assigned);
+		let random = match self.randomness {
+			Some(random) => random,
+			None => {
+				self.mismatches.push(format!("block {}: no randomness is recorded for the round", number));
+				return
+			},
+		};
+
+		// the submission order is shuffled by a ChaCha20 generator, seeded with the last byte of the randomness
+		// repeated, and the proposal that lands at position `i` goes to bucket `BucketSize % (i + 1)`
+		let mut rng = ChaChaRng::from_seed([random.as_bytes()[31]; 32]);
+		let mut order: Vec<ProposalCount> = (0..self.proposals.len() as ProposalCount).collect();
+		order.shuffle(&mut rng);
+		let bucket_size: BucketId = <Runtime as Config>::BucketSize::get();
+		let expected: Vec<_> = order
+			.iter()
+			.enumerate()
+			.map(|(i, submitted)| (*submitted, i as ProposalCount, bucket_size % (i as BucketId + 1)))
+			.collect();
+		if expected != assigned {
+			self.mismatches.push(format!(
+				"block {}: the randomness of the round puts the proposals into buckets as {:?}, but the chain has {:?}",
+				number, expected, assigned
+			));
+		}
+
+		let mut submitted: Vec<_> = std::mem::take(&mut self.proposals).into_iter().map(Some).collect();
+		self.proposals = expected
+			.into_iter()
+			.filter_map(|(from, _, bucket_id)| {
+				submitted[from as usize].take().map(|proposal| ReplayedProposal { bucket_id: Some(bucket_id), ..proposal })
+			})
+			.collect();
+	}
+
+	// the result a proposal gets from the votes cast on it
+	fn result(&self, export: &Export, proposal: &ReplayedProposal) -> ProposalResult<Balance> {
+		let ayes: Balance = proposal.ayes.iter().sum();
+		let nays: Balance = proposal.nays.iter().sum();
+		let abstains: Balance = proposal.abstains.iter().sum();
+		let credits: Balance =
+			proposal.ayes.iter().chain(&proposal.nays).chain(&proposal.abstains).map(|vote| vote.saturating_mul(*vote)).sum();
+		let registered = proposal.bucket_id.and_then(|bucket_id| self.bucket_credits.get(&bucket_id)).copied().unwrap_or(0);
+
+		// the credits spent on the proposal must reach the quorum share of the credits registered in its bucket
+		let threshold = class_threshold(proposal.class);
+		let outcome = if credits == 0 || credits < share_of(registered, export.round.quorum, false) {
+			Outcome::NoQuorum
+		} else if approves(&threshold, ayes, nays) {
+			Outcome::Aye
+		} else {
+			Outcome::Nay
+		};
+		ProposalResult { ayes, nays, abstains, credits, threshold, outcome }
+	}
+
+	// the differences between the replay and the round on chain
+	fn finish(&mut self, export: &Export) -> Vec<String> {
+		let mut mismatches = std::mem::take(&mut self.mismatches);
+
+		if self.proposals.len() != export.proposals.len() {
+			mismatches.push(format!(
+				"the replay has {} proposals, but the chain has {}",
+				self.proposals.len(),
+				export.proposals.len()
+			));
+		}
+		for (i, (replayed, proposal)) in self.proposals.iter().zip(&export.proposals).enumerate() {
+			if replayed.proposer != proposal.initializer ||
+				replayed.class != proposal.class ||
+				replayed.bucket_id != proposal.bucket_id
+			{
+				mismatches.push(format!(
+					"proposal {}: the replay has it from {} in bucket {:?}, but the chain has it from {} in bucket {:?}",
+					i, replayed.proposer, replayed.bucket_id, proposal.initializer, proposal.bucket_id
+				));
+			}
+			for (direction, replayed, votes) in [
+				("ayes", &replayed.ayes, &proposal.ayes),
+				("nays", &replayed.nays, &proposal.nays),
+				("abstentions", &replayed.abstains, &proposal.abstains),
+			] {
+				if replayed[..] != votes[..] {
+					mismatches.push(format!(
+						"proposal {}: the replay has the {} {:?}, but the chain has {:?}",
+						i, direction, replayed, votes
+					));
+				}
+			}
+		}
+
+		let bonds: BTreeMap<_, _> =
+			export.registrations.iter().map(|(who, bucket_id, bond)| ((who.clone(), *bucket_id), bond.clone())).collect();
+		for key in self.bonds.keys().chain(bonds.keys()).collect::<BTreeSet<_>>() {
+			if self.bonds.get(key) != bonds.get(key) {
+				mismatches.push(format!(
+					"{} in bucket {}: the replay leaves them with {:?}, but the chain has {:?}",
+					key.0,
+					key.1,
+					self.bonds.get(key),
+					bonds.get(key)
+				));
+			}
+		}
+		let credits: BTreeMap<_, _> = export.bucket_credits.iter().cloned().filter(|(_, credits)| *credits > 0).collect();
+		self.bucket_credits.retain(|_, credits| *credits > 0);
+		if self.bucket_credits != credits {
+			mismatches.push(format!(
+				"the replay registers the credits {:?} in the buckets, but the chain has {:?}",
+				self.bucket_credits, credits
+			));
+		}
+		let contributions: BTreeMap<_, _> = export.contributions.iter().cloned().collect();
+		if self.contributions != contributions {
+			mismatches.push(format!(
+				"the replay has the contributions {:?}, but the chain has {:?}",
+				self.contributions, contributions
+			));
+		}
+		for (proposal_id, who) in self.commitments.keys() {
+			mismatches.push(format!("the commitment of {} to proposal {} was never settled", who, proposal_id));
+		}
+
+		let outcomes: BTreeMap<_, _> = export.outcomes.iter().cloned().collect();
+		for (i, proposal) in self.proposals.iter().enumerate() {
+			let proposal_id = i as ProposalCount;
+			let expected = self.result(export, proposal);
+			match outcomes.get(&proposal_id) {
+				Some(result) if *result == expected => {},
+				Some(result) => mismatches.push(format!(
+					"proposal {}: the replay tallies it as {:?}, but its outcome on chain is {:?}",
+					proposal_id, expected, result
+				)),
+				None => mismatches.push(format!("proposal {}: there is no outcome on chain", proposal_id)),
+			}
+			let tallied = (expected.ayes, expected.nays, expected.abstains, expected.outcome.clone());
+			match self.tallied.get(&proposal_id) {
+				Some(event) if *event == tallied => {},
+				Some(event) => mismatches.push(format!(
+					"proposal {}: the replay tallies it as {:?}, but it was announced as {:?}",
+					proposal_id, tallied, event
+				)),
+				None => mismatches.push(format!("proposal {}: its tally was never announced", proposal_id)),
+			}
+		}
+
+		mismatches
+	}
+}
+
+// the threshold the runtime sets for each class of proposals
+fn class_threshold(class: ProposalClass) -> Threshold<Balance> {
+	match class {
+		ProposalClass::General => Threshold::SimpleMajority,
+		ProposalClass::TreasurySpend => Threshold::SuperMajority(Perbill::from_percent(66)),
+		ProposalClass::RuntimeParameter => Threshold::AbsoluteMinimum(1_000),
+	}
+}
+
+// whether the ayes and nays of a proposal pass its threshold
+fn approves(threshold: &Threshold<Balance>, ayes: Balance, nays: Balance) -> bool {
+	match threshold {
+		Threshold::SimpleMajority => ayes > nays,
+		// the share of all the ayes and nays is rounded up
+		Threshold::SuperMajority(share) => ayes > 0 && ayes >= share_of(ayes.saturating_add(nays), *share, true),
+		Threshold::AbsoluteMinimum(minimum) => ayes > nays && ayes >= *minimum,
+	}
+}
+
+// a share of an amount, rounded up, or to the nearest with halves rounded down, without overflowing
+fn share_of(amount: Balance, share: Perbill, round_up: bool) -> Balance {
+	let parts = share.deconstruct() as Balance;
+	let (whole, rest) = (amount / BILLION, amount % BILLION);
+	let rounding = if round_up { BILLION - 1 } else { BILLION / 2 - 1 };
+	whole.saturating_mul(parts).saturating_add((rest * parts + rounding) / BILLION)
+}
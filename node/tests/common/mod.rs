@@ -0,0 +1,72 @@
+//! Helpers to run the node binary from integration tests.
+
+#![allow(dead_code)]
+
+use std::{
+	net::TcpListener,
+	path::Path,
+	process::{Child, Command},
+	time::Duration,
+};
+
+use jsonrpsee::{
+	core::client::ClientT,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use serde_json::Value;
+
+/// The node binary, to be given its arguments.
+pub fn node_command() -> Command {
+	Command::new(env!("CARGO_BIN_EXE_parachain-template-node"))
+}
+
+/// A running node, killed when dropped.
+pub struct Node(Child);
+
+impl Drop for Node {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// A port that nothing listens on.
+pub fn free_port() -> u16 {
+	TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Starts a `--dev-seal manual` node of the given chain, with its database in `base_path`, or in a temporary
+/// directory if unset.
+///
+/// The node takes a while to come up, the first time the runtime is compiled in particular.
+pub async fn start_node(chain: &str, base_path: Option<&Path>) -> (Node, WsClient) {
+	let ws_port = free_port();
+	let database = match base_path {
+		Some(path) => vec!["--base-path".into(), path.display().to_string()],
+		None => vec!["--tmp".to_string()],
+	};
+	let node = Node(
+		node_command()
+			.args(["--chain", chain, "--dev-seal", "manual", "--no-prometheus", "--no-telemetry"])
+			.args(database)
+			.args(["--port", &free_port().to_string()])
+			.args(["--rpc-port", &free_port().to_string()])
+			.args(["--ws-port", &ws_port.to_string()])
+			.spawn()
+			.unwrap(),
+	);
+
+	for _ in 0..120 {
+		if let Ok(client) = WsClientBuilder::default().build(format!("ws://127.0.0.1:{}", ws_port)).await {
+			return (node, client)
+		}
+		tokio::time::sleep(Duration::from_millis(500)).await;
+	}
+	panic!("the node did not open its websocket port");
+}
+
+/// The number of the best block.
+pub async fn best_number(client: &WsClient) -> u64 {
+	let header: Value = client.request("chain_getHeader", None).await.unwrap();
+	u64::from_str_radix(header["number"].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
@@ -0,0 +1,60 @@
+//! Runs `--dev-seal manual` nodes, and fast-forwards their chain over RPC.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{best_number, start_node};
+use futures::StreamExt;
+use jsonrpsee::{
+	core::client::{ClientT, SubscriptionClientT},
+	rpc_params,
+};
+use serde_json::Value;
+
+#[tokio::test]
+async fn creates_blocks_on_demand() {
+	let (_node, client) = start_node("dev", None).await;
+	assert_eq!(best_number(&client).await, 0);
+
+	// nothing is sealed until asked for
+	tokio::time::sleep(Duration::from_secs(1)).await;
+	assert_eq!(best_number(&client).await, 0);
+
+	let hashes: Vec<String> = client.request("dev_createBlocks", rpc_params![5]).await.unwrap();
+	assert_eq!(hashes.len(), 5);
+	assert_eq!(best_number(&client).await, 5);
+
+	let best: String = client.request("chain_getBlockHash", None).await.unwrap();
+	assert_eq!(&best, hashes.last().unwrap());
+
+	// the blocks are a slot apart even though they were sealed at once, so the timestamp pallet accepts them
+	let hashes: Vec<String> = client.request("dev_createBlocks", rpc_params![50, true]).await.unwrap();
+	assert_eq!(hashes.len(), 50);
+	let finalized: String = client.request("chain_getFinalizedHead", None).await.unwrap();
+	assert_eq!(&finalized, hashes.last().unwrap());
+
+	assert!(client.request::<Vec<String>>("dev_createBlocks", rpc_params![1001]).await.is_err());
+}
+
+#[tokio::test]
+async fn streams_the_round_of_the_demo_chain() {
+	let (_node, client) = start_node("qv-demo", None).await;
+
+	let round: Value = client.request("quadraticVoting_roundInfo", rpc_params![1]).await.unwrap();
+	assert_eq!(round["phase"], "Voting");
+	let voting_end = round["votingPhase"]["end"].as_u64().unwrap();
+
+	let mut events = client
+		.subscribe::<Value>("quadraticVoting_subscribeRound", rpc_params![1], "quadraticVoting_unsubscribeRound")
+		.await
+		.unwrap();
+
+	// the demo round votes from block 1, and leaves the voting phase at its last block
+	let _: Vec<String> = client.request("dev_createBlocks", rpc_params![voting_end]).await.unwrap();
+
+	let event = tokio::time::timeout(Duration::from_secs(30), events.next()).await.unwrap().unwrap().unwrap();
+	assert_eq!(event["round"], 1);
+	assert_eq!(event["activity"]["type"], "PhaseTransition");
+	assert_eq!(event["activity"]["phase"], "Tallying");
+}
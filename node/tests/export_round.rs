@@ -0,0 +1,65 @@
+//! Exports the demo round from the database of a stopped node.
+
+mod common;
+
+use std::fs;
+
+use common::{node_command, start_node};
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use serde_json::Value;
+
+#[tokio::test]
+async fn exports_a_tallied_round() {
+	let base_path = tempfile::tempdir().unwrap();
+	let (node, client) = start_node("qv-demo", Some(base_path.path())).await;
+
+	let round: Value = client.request("quadraticVoting_roundInfo", rpc_params![1]).await.unwrap();
+	let voting_end = round["votingPhase"]["end"].as_u64().unwrap();
+	let _: Vec<String> = client.request("dev_createBlocks", rpc_params![voting_end + 1, true]).await.unwrap();
+	drop(client);
+	drop(node);
+
+	let export_round = |args: &[&str]| {
+		let output = node_command()
+			.args(["export-round", "1", "--chain", "qv-demo", "--base-path"])
+			.arg(base_path.path())
+			.args(args)
+			.output()
+			.unwrap();
+		assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+		output.stdout
+	};
+
+	let export: Value = serde_json::from_slice(&export_round(&[])).unwrap();
+	assert_eq!(export["phase"], "PostVoting");
+	assert_eq!(export["mode"], "Governance");
+	let proposals = export["proposals"].as_array().unwrap();
+	assert_eq!(proposals.len(), 4);
+	for proposal in proposals {
+		assert!(proposal["bucketId"].is_u64());
+		assert_eq!(proposal["outcome"]["outcome"], "NoQuorum");
+	}
+	// every demo voter registered in every bucket
+	assert_eq!(export["voters"].as_array().unwrap().len(), 4 * 3);
+	assert_eq!(export["buckets"].as_array().unwrap().len(), 3);
+
+	// the round as it was before it was tallied
+	let export: Value = serde_json::from_slice(&export_round(&["--at", "1"])).unwrap();
+	assert_eq!(export["phase"], "Voting");
+	assert!(export["proposals"][0]["outcome"].is_null());
+
+	let csv = base_path.path().join("csv");
+	export_round(&["--format", "csv", "--output", csv.to_str().unwrap()]);
+	let proposals = fs::read_to_string(csv.join("proposals.csv")).unwrap();
+	assert_eq!(proposals.lines().count(), 1 + 4);
+	assert!(proposals.lines().skip(1).all(|line| line.starts_with("1,") && line.ends_with(",NoQuorum")));
+	assert_eq!(fs::read_to_string(csv.join("voters.csv")).unwrap().lines().count(), 1 + 4 * 3);
+	assert_eq!(fs::read_to_string(csv.join("votes.csv")).unwrap().lines().count(), 1);
+
+	assert!(!node_command()
+		.args(["export-round", "2", "--chain", "qv-demo", "--base-path"])
+		.arg(base_path.path())
+		.status()
+		.unwrap()
+		.success());
+}
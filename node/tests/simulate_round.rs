@@ -0,0 +1,48 @@
+//! Simulates rounds offline, without a chain.
+
+mod common;
+
+use std::process::Output;
+
+use common::node_command;
+
+fn simulate_round(args: &[&str]) -> Output {
+	node_command().arg("simulate-round").args(args).output().unwrap()
+}
+
+#[test]
+fn simulates_a_round_deterministically() {
+	let args = ["--proposals", "6", "--voters", "40", "--strategy", "random", "--seed", "7"];
+	let output = simulate_round(&args);
+	let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+	assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+	assert!(stdout.contains("6 proposals and 40 voters, from seed 7"), "{}", stdout);
+	assert!(stdout.contains("Proposal -> PreVoting"), "{}", stdout);
+	assert!(stdout.contains("Enactment -> Finalized"), "{}", stdout);
+	// every proposal is tallied
+	let all = stdout.lines().find(|line| line.trim_start().starts_with("all:")).unwrap();
+	let tallied: u32 = all
+		.split(|c: char| !c.is_ascii_digit())
+		.filter_map(|count| count.parse::<u32>().ok())
+		.sum();
+	assert_eq!(tallied, 6, "{}", stdout);
+
+	// the same seed gives the same report, another one a different population
+	assert_eq!(simulate_round(&args).stdout, output.stdout);
+	let reseeded = simulate_round(&["--proposals", "6", "--voters", "40", "--strategy", "random", "--seed", "8"]);
+	assert_ne!(reseeded.stdout, output.stdout);
+}
+
+#[test]
+fn simulates_a_funding_round() {
+	let output = simulate_round(&["--voters", "20", "--matching-pool", "1000000", "--credits", "pareto"]);
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+	assert!(stdout.contains("Simulated a funding round"), "{}", stdout);
+	assert!(stdout.contains("contributed: "), "{}", stdout);
+}
+
+#[test]
+fn refuses_more_proposals_than_the_runtime_takes() {
+	assert!(!simulate_round(&["--proposals", "1000"]).status.success());
+}
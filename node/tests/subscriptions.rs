@@ -0,0 +1,88 @@
+//! Subscribes to the events of the `qv-demo` round on a `--dev-seal manual` node, and seals the blocks that
+//! tally it.
+
+mod common;
+
+use std::time::Duration;
+
+use common::start_node;
+use futures::StreamExt;
+use jsonrpsee::{
+	core::client::{ClientT, Subscription, SubscriptionClientT},
+	rpc_params,
+};
+use serde_json::Value;
+
+/// The proposals of the demo round.
+const PROPOSALS: usize = 4;
+
+// the next event of a subscription, which must arrive in time
+async fn next(subscription: &mut Subscription<Value>) -> Value {
+	tokio::time::timeout(Duration::from_secs(30), subscription.next()).await.unwrap().unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn streams_phases_and_the_activity_of_a_round() {
+	let (_node, client) = start_node("qv-demo", None).await;
+
+	let round: Value = client.request("quadraticVoting_roundInfo", rpc_params![1]).await.unwrap();
+	assert_eq!(round["phase"], "Voting");
+	let voting_end = round["votingPhase"]["end"].as_u64().unwrap();
+
+	let mut phases = client
+		.subscribe::<Value>("quadraticVoting_subscribePhases", None, "quadraticVoting_unsubscribePhases")
+		.await
+		.unwrap();
+	let mut finalized_phases = client
+		.subscribe::<Value>("quadraticVoting_subscribePhases", rpc_params![true], "quadraticVoting_unsubscribePhases")
+		.await
+		.unwrap();
+	let mut activity = client
+		.subscribe::<Value>("quadraticVoting_subscribeRound", rpc_params![1], "quadraticVoting_unsubscribeRound")
+		.await
+		.unwrap();
+	let mut other_round = client
+		.subscribe::<Value>("quadraticVoting_subscribeRound", rpc_params![2], "quadraticVoting_unsubscribeRound")
+		.await
+		.unwrap();
+
+	// the round leaves the voting phase at its last block, and is tallied in that block
+	let _: Vec<String> = client.request("dev_createBlocks", rpc_params![voting_end]).await.unwrap();
+
+	// the round streams its tallies between its phase transitions
+	let event = next(&mut activity).await;
+	assert_eq!((&event["round"], &event["activity"]["type"]), (&Value::from(1), &Value::from("PhaseTransition")));
+	assert_eq!(event["activity"]["phase"], "Tallying");
+	assert!(event["blockHash"].as_str().unwrap().starts_with("0x"));
+	let mut tallied = Vec::new();
+	let event = loop {
+		let event = next(&mut activity).await;
+		if event["activity"]["type"] != "ProposalTallied" {
+			break event
+		}
+		assert!(["Aye", "Nay", "NoQuorum"].contains(&event["activity"]["outcome"].as_str().unwrap()));
+		tallied.push(event["activity"]["proposalId"].as_u64().unwrap());
+	};
+	tallied.sort();
+	assert_eq!(tallied, (0..PROPOSALS as u64).collect::<Vec<_>>());
+	assert_eq!(event["activity"]["type"], "PhaseTransition");
+	assert_eq!(event["activity"]["phase"], "PostVoting");
+
+	// phase subscriptions only stream the transitions
+	for phase in ["Tallying", "PostVoting"] {
+		let event = next(&mut phases).await;
+		assert_eq!(event["round"], 1);
+		assert_eq!(event["activity"], serde_json::json!({ "type": "PhaseTransition", "phase": phase }));
+	}
+
+	// nothing is finalized yet, and the other round has no events
+	assert!(tokio::time::timeout(Duration::from_secs(1), finalized_phases.next()).await.is_err());
+	assert!(tokio::time::timeout(Duration::from_secs(1), other_round.next()).await.is_err());
+
+	// finalizing a block finalizes its ancestors, whose events are streamed too
+	let _: Vec<String> = client.request("dev_createBlocks", rpc_params![1, true]).await.unwrap();
+	for phase in ["Tallying", "PostVoting"] {
+		let event = next(&mut finalized_phases).await;
+		assert_eq!(event["activity"]["phase"], phase);
+	}
+}
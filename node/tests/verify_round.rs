@@ -0,0 +1,40 @@
+//! Replays the demo round from the database of a stopped node.
+
+mod common;
+
+use std::process::Output;
+
+use common::{node_command, start_node};
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use serde_json::Value;
+
+#[tokio::test]
+async fn verifies_a_tallied_round() {
+	let base_path = tempfile::tempdir().unwrap();
+	let (node, client) = start_node("qv-demo", Some(base_path.path())).await;
+
+	let round: Value = client.request("quadraticVoting_roundInfo", rpc_params![1]).await.unwrap();
+	let voting_end = round["votingPhase"]["end"].as_u64().unwrap();
+	let _: Vec<String> = client.request("dev_createBlocks", rpc_params![voting_end + 1, true]).await.unwrap();
+	drop(client);
+	drop(node);
+
+	let verify_round = |args: &[&str]| -> Output {
+		node_command()
+			.args(["verify-round", "1", "--chain", "qv-demo", "--base-path"])
+			.arg(base_path.path())
+			.args(args)
+			.output()
+			.unwrap()
+	};
+
+	let output = verify_round(&[]);
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+	// every demo voter registered in every bucket, and nobody voted
+	assert!(stdout.contains("4 proposals, 12 registrations, 0 votes"), "{}", stdout);
+	assert!(stdout.contains("The tally on chain matches the replay."));
+
+	// the round cannot be checked before it is tallied
+	assert!(!verify_round(&["--at", "1"]).status.success());
+}
@@ -0,0 +1,13 @@
+//! Drives the voting state machine of the mock runtime with sequences of calls and blocks decoded from the fuzzer's
+//! input, and fails on the first broken check of the model.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use quadratic_voting_pallet::model::{self, Action};
+
+fuzz_target!(|actions: Vec<Action>| {
+	if let Err(e) = model::run(&actions) {
+		panic!("{}", e);
+	}
+});
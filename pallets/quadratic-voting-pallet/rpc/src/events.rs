@@ -0,0 +1,256 @@
+//! `quadraticVoting_subscribe*` pub-sub methods, which stream the pallet's events as blocks are imported or
+//! finalized, so that clients need not poll its storage.
+
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_system::EventRecord;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{proc_macros::rpc, types::SubscriptionResult, SubscriptionSink};
+use quadratic_voting_pallet::{
+	BalanceOf, BucketId, Config, Event, ProposalCount, VoteDirection, VotingPhases, VotingRoundId,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_core::{storage::StorageKey, traits::SpawnNamed};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use crate::{outcome_name, phase_name};
+
+/// An event of a round, and the block it was emitted in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundEvent<BlockHash, AccountId> {
+	pub block_hash: BlockHash,
+	pub round: VotingRoundId,
+	pub activity: RoundActivity<AccountId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RoundActivity<AccountId> {
+	PhaseTransition {
+		phase: String,
+	},
+	#[serde(rename_all = "camelCase")]
+	ProposalSubmitted {
+		proposal_id: ProposalCount,
+		proposer: AccountId,
+	},
+	#[serde(rename_all = "camelCase")]
+	BucketAssigned {
+		submitted_as: ProposalCount,
+		proposal_id: ProposalCount,
+		bucket_id: BucketId,
+	},
+	#[serde(rename_all = "camelCase")]
+	VoterRegistered {
+		bucket_id: BucketId,
+		who: AccountId,
+		credits: String,
+	},
+	#[serde(rename_all = "camelCase")]
+	VoteCast {
+		proposal_id: ProposalCount,
+		who: AccountId,
+		votes: String,
+		direction: String,
+	},
+	#[serde(rename_all = "camelCase")]
+	ProposalTallied {
+		proposal_id: ProposalCount,
+		ayes: String,
+		nays: String,
+		abstains: String,
+		outcome: String,
+	},
+}
+
+#[rpc(client, server)]
+pub trait QuadraticVotingEventsApi<BlockHash, AccountId> {
+	/// The phase transitions of every round, from new best blocks, or from finalized blocks if `finalized` is set.
+	#[subscription(
+		name = "quadraticVoting_subscribePhases",
+		unsubscribe = "quadraticVoting_unsubscribePhases",
+		item = RoundEvent<BlockHash, AccountId>
+	)]
+	fn subscribe_phases(&self, finalized: Option<bool>);
+
+	/// The phase transitions, proposals, bucket assignments, registrations, votes and tallies of a round, from new best blocks, or from
+	/// finalized blocks if `finalized` is set.
+	#[subscription(
+		name = "quadraticVoting_subscribeRound",
+		unsubscribe = "quadraticVoting_unsubscribeRound",
+		item = RoundEvent<BlockHash, AccountId>
+	)]
+	fn subscribe_round(&self, voting_round_id: VotingRoundId, finalized: Option<bool>);
+}
+
+/// Serves the `quadraticVoting_subscribe*` methods, for the given runtime.
+pub struct QuadraticVotingEvents<C, Block, BE, Runtime> {
+	client: Arc<C>,
+	executor: Arc<dyn SpawnNamed>,
+	_marker: PhantomData<(Block, BE, Runtime)>,
+}
+
+impl<C, Block, BE, Runtime> QuadraticVotingEvents<C, Block, BE, Runtime> {
+	pub fn new(client: Arc<C>, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+/// Whether `quadraticVoting_subscribePhases` streams the event.
+pub fn is_phase_event<BlockHash, AccountId>(event: &RoundEvent<BlockHash, AccountId>) -> bool {
+	matches!(event.activity, RoundActivity::PhaseTransition { .. })
+}
+
+/// The name a vote direction is reported by.
+pub fn direction_name(direction: &VoteDirection) -> &'static str {
+	match direction {
+		VoteDirection::Aye => "Aye",
+		VoteDirection::Nay => "Nay",
+		VoteDirection::Abstain => "Abstain",
+	}
+}
+
+/// The round events among the encoded `System::Events` of a block. Other events are skipped, and so are the
+/// events of a block that cannot be decoded with the given runtime.
+pub fn round_events<Runtime, BlockHash>(
+	block_hash: BlockHash,
+	mut events: &[u8],
+) -> Vec<RoundEvent<BlockHash, Runtime::AccountId>>
+where
+	Runtime: Config,
+	<Runtime as frame_system::Config>::Event: TryInto<Event<Runtime>>,
+	BalanceOf<Runtime>: Display,
+	BlockHash: Clone,
+{
+	let records = match Vec::<EventRecord<<Runtime as frame_system::Config>::Event, Runtime::Hash>>::decode(&mut events) {
+		Ok(records) => records,
+		Err(_) => return Vec::new(),
+	};
+
+	records
+		.into_iter()
+		.filter_map(|record| record.event.try_into().ok())
+		.filter_map(|event| {
+			let (round, activity) = match event {
+				Event::PhaseTransition { round, phase } =>
+					(round, RoundActivity::PhaseTransition { phase: phase_name(&phase).into() }),
+				// the pallet moves a round to `Cancelled` without a phase transition event
+				Event::RoundCancelled { round } =>
+					(round, RoundActivity::PhaseTransition { phase: phase_name(&VotingPhases::Cancelled).into() }),
+				Event::ProposalSubmitted { round, proposal, proposer, .. } =>
+					(round, RoundActivity::ProposalSubmitted { proposal_id: proposal, proposer }),
+				Event::BucketAssigned { round, submitted, proposal, bucket } => (
+					round,
+					RoundActivity::BucketAssigned { submitted_as: submitted, proposal_id: proposal, bucket_id: bucket },
+				),
+				Event::VoterRegistered { round, bucket, who, credits } =>
+					(round, RoundActivity::VoterRegistered { bucket_id: bucket, who, credits: credits.to_string() }),
+				Event::VoteCast { round, proposal, who, votes, direction } => (
+					round,
+					RoundActivity::VoteCast {
+						proposal_id: proposal,
+						who,
+						votes: votes.to_string(),
+						direction: direction_name(&direction).into(),
+					},
+				),
+				Event::ProposalTallied { round, proposal, ayes, nays, abstains, outcome } => (
+					round,
+					RoundActivity::ProposalTallied {
+						proposal_id: proposal,
+						ayes: ayes.to_string(),
+						nays: nays.to_string(),
+						abstains: abstains.to_string(),
+						outcome: outcome_name(&outcome).into(),
+					},
+				),
+				_ => return None,
+			};
+			Some(RoundEvent { block_hash: block_hash.clone(), round, activity })
+		})
+		.collect()
+}
+
+impl<C, Block, BE, Runtime> QuadraticVotingEvents<C, Block, BE, Runtime>
+where
+	Block: BlockT,
+	BE: Backend<Block> + Send + Sync + 'static,
+	C: BlockchainEvents<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+	Runtime: Config + Send + Sync + 'static,
+	<Runtime as frame_system::Config>::Event: TryInto<Event<Runtime>>,
+	Runtime::AccountId: Serialize,
+	BalanceOf<Runtime>: Display,
+{
+	fn subscribe(
+		&self,
+		mut sink: SubscriptionSink,
+		finalized: bool,
+		filter: impl Fn(&RoundEvent<Block::Hash, Runtime::AccountId>) -> bool + Send + 'static,
+	) -> SubscriptionResult {
+		// finality notifications skip the blocks finalized along with the notified one, but list them in their route
+		let blocks = if finalized {
+			self.client
+				.finality_notification_stream()
+				.map(|notification| {
+					let mut blocks = notification.tree_route.to_vec();
+					blocks.push(notification.hash);
+					stream::iter(blocks)
+				})
+				.flatten()
+				.boxed()
+		} else {
+			self.client
+				.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.map(|notification| notification.hash)
+				.boxed()
+		};
+
+		let client = self.client.clone();
+		let key = StorageKey(frame_support::storage::storage_prefix(b"System", b"Events").to_vec());
+		let events = blocks
+			.map(move |hash| {
+				let events = match client.storage(&BlockId::Hash(hash), &key) {
+					Ok(Some(events)) => round_events::<Runtime, _>(hash, &events.0),
+					Ok(None) | Err(_) => Vec::new(),
+				};
+				stream::iter(events.into_iter().filter(|event| filter(event)).collect::<Vec<_>>())
+			})
+			.flatten()
+			.boxed();
+
+		let fut = async move {
+			sink.pipe_from_stream(events).await;
+		};
+		self.executor.spawn("quadratic-voting-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+impl<C, Block, BE, Runtime> QuadraticVotingEventsApiServer<Block::Hash, Runtime::AccountId>
+	for QuadraticVotingEvents<C, Block, BE, Runtime>
+where
+	Block: BlockT,
+	BE: Backend<Block> + Send + Sync + 'static,
+	C: BlockchainEvents<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+	Runtime: Config + Send + Sync + 'static,
+	<Runtime as frame_system::Config>::Event: TryInto<Event<Runtime>>,
+	Runtime::AccountId: Serialize + DeserializeOwned,
+	BalanceOf<Runtime>: Display,
+{
+	fn subscribe_phases(&self, sink: SubscriptionSink, finalized: Option<bool>) -> SubscriptionResult {
+		self.subscribe(sink, finalized.unwrap_or(false), is_phase_event)
+	}
+
+	fn subscribe_round(
+		&self,
+		sink: SubscriptionSink,
+		voting_round_id: VotingRoundId,
+		finalized: Option<bool>,
+	) -> SubscriptionResult {
+		self.subscribe(sink, finalized.unwrap_or(false), move |event| event.round == voting_round_id)
+	}
+}
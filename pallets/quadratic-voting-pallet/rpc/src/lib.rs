@@ -0,0 +1,360 @@
+//! `quadraticVoting_*` JSON-RPC endpoints, on top of the pallet's runtime API. The pub-sub methods are in
+//! [`events`].
+//!
+//! Responses are made for JSON clients: balances are strings, since they do not fit in a javascript number, and
+//! enums are reported by name.
+
+use std::{fmt::Display, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use quadratic_voting_pallet::{
+	BallotMode, BucketId, Outcome, ProposalClass, ProposalCount, RoundMode, Threshold, VotingPhases,
+	VotingPhaseData, VotingRoundId,
+};
+pub use quadratic_voting_runtime_api::QuadraticVotingApi as QuadraticVotingRuntimeApi;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub mod events;
+#[cfg(test)]
+mod tests;
+
+pub use events::{direction_name, QuadraticVotingEvents, QuadraticVotingEventsApiServer};
+
+/// The first and last block of a phase.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRange<BlockNumber> {
+	pub start: BlockNumber,
+	pub end: BlockNumber,
+}
+
+impl<BlockNumber> From<VotingPhaseData<BlockNumber>> for BlockRange<BlockNumber> {
+	fn from(phase: VotingPhaseData<BlockNumber>) -> Self {
+		BlockRange { start: phase.start_block, end: phase.end_block }
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundInfo<AccountId, BlockNumber> {
+	pub initializer: AccountId,
+	pub phase: String,
+	pub mode: String,
+	/// Only set for funding rounds.
+	pub matching_pool: Option<String>,
+	pub ballot: String,
+	/// In parts per billion of the credits registered in a bucket.
+	pub quorum: u32,
+	pub proposal_phase: BlockRange<BlockNumber>,
+	pub pre_voting_phase: BlockRange<BlockNumber>,
+	pub voting_phase: BlockRange<BlockNumber>,
+	/// Only set for rounds whose ballots are not open.
+	pub reveal_phase: Option<BlockRange<BlockNumber>>,
+	pub post_voting_phase: BlockRange<BlockNumber>,
+	pub enactment_phase: BlockRange<BlockNumber>,
+	pub finalized_block: BlockNumber,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketProposal<AccountId> {
+	pub proposal_id: ProposalCount,
+	pub proposer: AccountId,
+	pub class: String,
+	/// Ayes, nays and abstentions cast so far.
+	pub votes: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Registration {
+	pub bucket_id: BucketId,
+	pub bonded: String,
+	/// Credits left to vote with.
+	pub remaining: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterStatus {
+	pub registrations: Vec<Registration>,
+	/// Bonds held for the voter until they claim them back.
+	pub unclaimed_refund: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectedResult {
+	pub proposal_id: ProposalCount,
+	pub ayes: String,
+	pub nays: String,
+	pub abstains: String,
+	pub threshold: String,
+	/// Parts per billion of a super majority, or the aye weight of an absolute minimum.
+	pub threshold_parameter: Option<String>,
+	pub outcome: String,
+}
+
+#[rpc(client, server)]
+pub trait QuadraticVotingApi<BlockHash, AccountId, BlockNumber> {
+	/// The phase of a round, and the blocks of each of its phases.
+	#[method(name = "quadraticVoting_roundInfo")]
+	fn round_info(
+		&self,
+		voting_round_id: VotingRoundId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<RoundInfo<AccountId, BlockNumber>>>;
+
+	/// The proposals in a bucket of a round.
+	#[method(name = "quadraticVoting_bucketContents")]
+	fn bucket_contents(
+		&self,
+		voting_round_id: VotingRoundId,
+		bucket_id: BucketId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<BucketProposal<AccountId>>>;
+
+	/// The registrations of a voter in a round, and what they can claim back.
+	#[method(name = "quadraticVoting_voterStatus")]
+	fn voter_status(&self, voting_round_id: VotingRoundId, who: AccountId, at: Option<BlockHash>) -> RpcResult<VoterStatus>;
+
+	/// The result of every proposal of a round, recorded or projected from the votes cast so far.
+	#[method(name = "quadraticVoting_projectedTally")]
+	fn projected_tally(&self, voting_round_id: VotingRoundId, at: Option<BlockHash>) -> RpcResult<Vec<ProjectedResult>>;
+
+	/// The credits it takes to cast `votes` votes on a proposal.
+	#[method(name = "quadraticVoting_voteCost")]
+	fn vote_cost(&self, votes: u64, at: Option<BlockHash>) -> RpcResult<String>;
+}
+
+/// Serves the `quadraticVoting_*` methods, for a runtime with the given balance type.
+pub struct QuadraticVoting<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> QuadraticVoting<C, Block, Balance> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error codes of the `quadraticVoting_*` methods.
+pub enum Error {
+	/// The runtime API call failed.
+	RuntimeError,
+	/// The result does not fit in a balance.
+	Overflow,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::Overflow => 2,
+		}
+	}
+}
+
+fn runtime_error(message: &'static str, e: impl Display) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), message, Some(e.to_string()))).into()
+}
+
+/// The name a phase is reported by.
+pub fn phase_name(phase: &VotingPhases) -> &'static str {
+	match phase {
+		VotingPhases::Proposal => "Proposal",
+		VotingPhases::PreVoting => "PreVoting",
+		VotingPhases::Voting => "Voting",
+		VotingPhases::Reveal => "Reveal",
+		VotingPhases::Tallying => "Tallying",
+		VotingPhases::PostVoting => "PostVoting",
+		VotingPhases::Settling => "Settling",
+		VotingPhases::Enactment => "Enactment",
+		VotingPhases::Finalized => "Finalized",
+		VotingPhases::Cancelled => "Cancelled",
+	}
+}
+
+/// The name a ballot mode is reported by.
+pub fn ballot_name(ballot: &BallotMode) -> &'static str {
+	match ballot {
+		BallotMode::Open => "Open",
+		BallotMode::CommitReveal => "CommitReveal",
+		BallotMode::Encrypted => "Encrypted",
+		BallotMode::ThresholdEncrypted => "ThresholdEncrypted",
+	}
+}
+
+/// The name a proposal class is reported by.
+pub fn class_name(class: &ProposalClass) -> &'static str {
+	match class {
+		ProposalClass::General => "General",
+		ProposalClass::TreasurySpend => "TreasurySpend",
+		ProposalClass::RuntimeParameter => "RuntimeParameter",
+	}
+}
+
+/// The name an outcome is reported by.
+pub fn outcome_name(outcome: &Outcome) -> &'static str {
+	match outcome {
+		Outcome::Aye => "Aye",
+		Outcome::Nay => "Nay",
+		Outcome::NoQuorum => "NoQuorum",
+	}
+}
+
+/// The name a threshold is reported by, and its parameter: parts per billion of a super majority, or the aye weight
+/// of an absolute minimum.
+pub fn threshold_name<Balance: Display>(threshold: &Threshold<Balance>) -> (&'static str, Option<String>) {
+	match threshold {
+		Threshold::SimpleMajority => ("SimpleMajority", None),
+		Threshold::SuperMajority(share) => ("SuperMajority", Some(share.deconstruct().to_string())),
+		Threshold::AbsoluteMinimum(minimum) => ("AbsoluteMinimum", Some(minimum.to_string())),
+	}
+}
+
+#[async_trait]
+impl<C, Block, AccountId, BlockNumber, Balance>
+	QuadraticVotingApiServer<<Block as BlockT>::Hash, AccountId, BlockNumber> for QuadraticVoting<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: QuadraticVotingRuntimeApi<Block, AccountId, BlockNumber, Balance>,
+	AccountId: Codec + Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+	BlockNumber: Codec + Clone + Serialize + Send + Sync + 'static,
+	Balance: Codec + Display + From<u64> + Send + Sync + 'static,
+{
+	fn round_info(
+		&self,
+		voting_round_id: VotingRoundId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<RoundInfo<AccountId, BlockNumber>>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let round = self
+			.client
+			.runtime_api()
+			.round_info(&at, voting_round_id)
+			.map_err(|e| runtime_error("Unable to query the round.", e))?;
+
+		Ok(round.map(|round| {
+			let (mode, matching_pool) = match round.mode {
+				RoundMode::Governance => ("Governance", None),
+				RoundMode::Funding { matching_pool } => ("Funding", Some(matching_pool.to_string())),
+			};
+			RoundInfo {
+				initializer: round.initializer,
+				phase: phase_name(&round.phase).into(),
+				mode: mode.into(),
+				matching_pool,
+				ballot: ballot_name(&round.ballot).into(),
+				quorum: round.quorum.deconstruct(),
+				proposal_phase: round.proposal_phase.into(),
+				pre_voting_phase: round.pre_voting_phase.into(),
+				voting_phase: round.voting_phase.into(),
+				reveal_phase: round.reveal_phase.map(Into::into),
+				post_voting_phase: round.post_voting_phase.into(),
+				enactment_phase: round.enactment_phase.into(),
+				finalized_block: round.finalized_block,
+			}
+		}))
+	}
+
+	fn bucket_contents(
+		&self,
+		voting_round_id: VotingRoundId,
+		bucket_id: BucketId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<BucketProposal<AccountId>>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let proposals = self
+			.client
+			.runtime_api()
+			.bucket_proposals(&at, voting_round_id, bucket_id)
+			.map_err(|e| runtime_error("Unable to query the bucket.", e))?;
+
+		Ok(proposals
+			.into_iter()
+			.map(|proposal| BucketProposal {
+				proposal_id: proposal.proposal_id,
+				proposer: proposal.proposer,
+				class: class_name(&proposal.class).into(),
+				votes: proposal.votes,
+			})
+			.collect())
+	}
+
+	fn voter_status(&self, voting_round_id: VotingRoundId, who: AccountId, at: Option<Block::Hash>) -> RpcResult<VoterStatus> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let status = self
+			.client
+			.runtime_api()
+			.voter_status(&at, voting_round_id, who)
+			.map_err(|e| runtime_error("Unable to query the voter.", e))?;
+
+		Ok(VoterStatus {
+			registrations: status
+				.registrations
+				.into_iter()
+				.map(|(bucket_id, bond)| Registration {
+					bucket_id,
+					bonded: bond.total.to_string(),
+					remaining: bond.remaining.to_string(),
+				})
+				.collect(),
+			unclaimed_refund: status.unclaimed_refund.to_string(),
+		})
+	}
+
+	fn projected_tally(&self, voting_round_id: VotingRoundId, at: Option<Block::Hash>) -> RpcResult<Vec<ProjectedResult>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let results = self
+			.client
+			.runtime_api()
+			.projected_tally(&at, voting_round_id)
+			.map_err(|e| runtime_error("Unable to query the tally.", e))?;
+
+		Ok(results
+			.into_iter()
+			.map(|(proposal_id, result)| {
+				let (threshold, threshold_parameter) = threshold_name(&result.threshold);
+				ProjectedResult {
+					proposal_id,
+					ayes: result.ayes.to_string(),
+					nays: result.nays.to_string(),
+					abstains: result.abstains.to_string(),
+					threshold: threshold.into(),
+					threshold_parameter,
+					outcome: outcome_name(&result.outcome).into(),
+				}
+			})
+			.collect())
+	}
+
+	fn vote_cost(&self, votes: u64, at: Option<Block::Hash>) -> RpcResult<String> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let cost = self
+			.client
+			.runtime_api()
+			.vote_cost(&at, votes.into())
+			.map_err(|e| runtime_error("Unable to query the cost of the votes.", e))?;
+
+		match cost {
+			Some(cost) => Ok(cost.to_string()),
+			None => Err(CallError::Custom(ErrorObject::owned(
+				Error::Overflow.into(),
+				"The cost of the votes does not fit in a balance.",
+				None::<()>,
+			))
+			.into()),
+		}
+	}
+}
@@ -0,0 +1,273 @@
+use super::*;
+use quadratic_voting_pallet::{
+	ProposalInfo, ProposalResult, VoterBond, VoterStatus as RuntimeVoterStatus, VotingRoundMetadata,
+};
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::{BlockStatus, Info};
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, NumberFor},
+	OpaqueExtrinsic, Perbill,
+};
+
+type Header = generic::Header<u32, BlakeTwo256>;
+type Block = generic::Block<Header, OpaqueExtrinsic>;
+type Hash = <Block as BlockT>::Hash;
+
+// a chain with a single round, at the start of its voting phase
+struct TestClient;
+
+struct TestRuntimeApi;
+
+impl ProvideRuntimeApi<Block> for TestClient {
+	type Api = TestRuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		TestRuntimeApi.into()
+	}
+}
+
+impl HeaderBackend<Block> for TestClient {
+	fn header(&self, _id: BlockId<Block>) -> sp_blockchain::Result<Option<Header>> {
+		Ok(None)
+	}
+
+	fn info(&self) -> Info<Block> {
+		Info {
+			best_hash: Default::default(),
+			best_number: 22,
+			genesis_hash: Default::default(),
+			finalized_hash: Default::default(),
+			finalized_number: 22,
+			finalized_state: None,
+			number_leaves: 1,
+			block_gap: None,
+		}
+	}
+
+	fn status(&self, _id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+		Ok(BlockStatus::InChain)
+	}
+
+	fn number(&self, _hash: Hash) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+		Ok(Some(22))
+	}
+
+	fn hash(&self, _number: NumberFor<Block>) -> sp_blockchain::Result<Option<Hash>> {
+		Ok(Some(Default::default()))
+	}
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl QuadraticVotingRuntimeApi<Block, u64, u32, u128> for TestRuntimeApi {
+		fn round_info(voting_round_id: VotingRoundId) -> Option<VotingRoundMetadata<u64, u32, u128>> {
+			(voting_round_id == 1).then(|| VotingRoundMetadata {
+				initializer: 1,
+				proposal_phase: VotingPhaseData { start_block: 0, end_block: 10 },
+				previous_round_id: 0,
+				pre_voting_phase: VotingPhaseData { start_block: 11, end_block: 21 },
+				voting_phase: VotingPhaseData { start_block: 22, end_block: 32 },
+				reveal_phase: None,
+				post_voting_phase: VotingPhaseData { start_block: 33, end_block: 43 },
+				enactment_phase: VotingPhaseData { start_block: 44, end_block: 54 },
+				finalized_block: 55,
+				phase: VotingPhases::Voting,
+				quorum: Perbill::from_percent(50),
+				mode: RoundMode::Funding { matching_pool: u128::MAX },
+				ballot: BallotMode::Open,
+			})
+		}
+
+		fn bucket_proposals(_voting_round_id: VotingRoundId, bucket_id: BucketId) -> Vec<ProposalInfo<u64>> {
+			vec![ProposalInfo { proposal_id: bucket_id, proposer: 2, class: ProposalClass::TreasurySpend, votes: 3 }]
+		}
+
+		fn voter_status(_voting_round_id: VotingRoundId, who: u64) -> RuntimeVoterStatus<u128> {
+			RuntimeVoterStatus {
+				registrations: vec![(2, VoterBond { total: who as u128 * 4, remaining: 1 })],
+				unclaimed_refund: 24,
+			}
+		}
+
+		fn projected_tally(_voting_round_id: VotingRoundId) -> Vec<(ProposalCount, ProposalResult<u128>)> {
+			vec![
+				(0, ProposalResult { ayes: 3, nays: 1, abstains: 0, credits: 10, threshold: Threshold::SuperMajority(Perbill::from_percent(75)), outcome: Outcome::Aye }),
+				(1, ProposalResult { ayes: 0, nays: 0, abstains: 0, credits: 0, threshold: Threshold::SimpleMajority, outcome: Outcome::NoQuorum }),
+			]
+		}
+
+		// as a runtime with 64 bit balances would
+		fn vote_cost(votes: u128) -> Option<u128> {
+			(votes as u64).checked_mul(votes as u64).map(Into::into)
+		}
+	}
+}
+
+fn rpc() -> QuadraticVoting<TestClient, Block, u128> {
+	QuadraticVoting::new(Arc::new(TestClient))
+}
+
+#[test]
+fn reports_rounds_by_name() {
+	let round = QuadraticVotingApiServer::<Hash, u64, u32>::round_info(&rpc(), 1, None).unwrap().unwrap();
+	assert_eq!(round.phase, "Voting");
+	assert_eq!(round.mode, "Funding");
+	assert_eq!(round.matching_pool, Some(u128::MAX.to_string()));
+	assert_eq!(round.ballot, "Open");
+	assert_eq!(round.quorum, 500_000_000);
+	assert_eq!(round.voting_phase, BlockRange { start: 22, end: 32 });
+	assert_eq!(round.reveal_phase, None);
+
+	// balances do not lose precision in javascript
+	let json = serde_json::to_value(&round).unwrap();
+	assert_eq!(json["matchingPool"], "340282366920938463463374607431768211455");
+	assert_eq!(json["preVotingPhase"]["start"], 11);
+
+	assert_eq!(QuadraticVotingApiServer::<Hash, u64, u32>::round_info(&rpc(), 2, None).unwrap(), None);
+}
+
+#[test]
+fn reports_buckets_and_voters() {
+	assert_eq!(
+		QuadraticVotingApiServer::<Hash, u64, u32>::bucket_contents(&rpc(), 1, 4, None).unwrap(),
+		vec![BucketProposal { proposal_id: 4, proposer: 2, class: "TreasurySpend".into(), votes: 3 }]
+	);
+
+	assert_eq!(
+		QuadraticVotingApiServer::<Hash, u64, u32>::voter_status(&rpc(), 1, 3, None).unwrap(),
+		VoterStatus {
+			registrations: vec![Registration { bucket_id: 2, bonded: "12".into(), remaining: "1".into() }],
+			unclaimed_refund: "24".into(),
+		}
+	);
+}
+
+#[test]
+fn reports_projected_tallies() {
+	let tally = QuadraticVotingApiServer::<Hash, u64, u32>::projected_tally(&rpc(), 1, None).unwrap();
+	assert_eq!(
+		tally,
+		vec![
+			ProjectedResult {
+				proposal_id: 0,
+				ayes: "3".into(),
+				nays: "1".into(),
+				abstains: "0".into(),
+				threshold: "SuperMajority".into(),
+				threshold_parameter: Some("750000000".into()),
+				outcome: "Aye".into(),
+			},
+			ProjectedResult {
+				proposal_id: 1,
+				ayes: "0".into(),
+				nays: "0".into(),
+				abstains: "0".into(),
+				threshold: "SimpleMajority".into(),
+				threshold_parameter: None,
+				outcome: "NoQuorum".into(),
+			},
+		]
+	);
+}
+
+#[test]
+fn prices_votes_and_rejects_overflows() {
+	assert_eq!(QuadraticVotingApiServer::<Hash, u64, u32>::vote_cost(&rpc(), 12, None).unwrap(), "144");
+	assert!(QuadraticVotingApiServer::<Hash, u64, u32>::vote_cost(&rpc(), u64::MAX, None).is_err());
+}
+
+#[test]
+fn decodes_the_round_events_of_a_block() {
+	use codec::Encode;
+	use events::{round_events, RoundActivity, RoundEvent};
+	use frame_system::{EventRecord, Phase};
+	use parachain_template_runtime::{AccountId, Event as RuntimeEvent, Runtime};
+	use quadratic_voting_pallet::{Event as QuadraticVotingEvent, VoteDirection};
+
+	let voter = AccountId::from([1u8; 32]);
+	let record = |event| EventRecord { phase: Phase::ApplyExtrinsic(0), event, topics: vec![] };
+	let events = vec![
+		record(RuntimeEvent::QuadraticVotingPallet(QuadraticVotingEvent::PhaseTransition {
+			round: 1,
+			phase: VotingPhases::Voting,
+		})),
+		record(RuntimeEvent::System(frame_system::Event::ExtrinsicSuccess { dispatch_info: Default::default() })),
+		record(RuntimeEvent::QuadraticVotingPallet(QuadraticVotingEvent::VoteCast {
+			round: 1,
+			proposal: 2,
+			who: voter.clone(),
+			votes: 3,
+			direction: VoteDirection::Nay,
+		})),
+		record(RuntimeEvent::QuadraticVotingPallet(QuadraticVotingEvent::TallyVerified { round: 1 })),
+		record(RuntimeEvent::QuadraticVotingPallet(QuadraticVotingEvent::ProposalTallied {
+			round: 1,
+			proposal: 2,
+			ayes: 0,
+			nays: 3,
+			abstains: 0,
+			outcome: Outcome::Nay,
+		})),
+	]
+	.encode();
+
+	let block_hash = Hash::repeat_byte(7);
+	let decoded = round_events::<Runtime, _>(block_hash, &events);
+	assert_eq!(
+		decoded,
+		vec![
+			RoundEvent { block_hash, round: 1, activity: RoundActivity::PhaseTransition { phase: "Voting".into() } },
+			RoundEvent {
+				block_hash,
+				round: 1,
+				activity: RoundActivity::VoteCast { proposal_id: 2, who: voter, votes: "3".into(), direction: "Nay".into() },
+			},
+			RoundEvent {
+				block_hash,
+				round: 1,
+				activity: RoundActivity::ProposalTallied {
+					proposal_id: 2,
+					ayes: "0".into(),
+					nays: "3".into(),
+					abstains: "0".into(),
+					outcome: "Nay".into(),
+				},
+			},
+		]
+	);
+
+	let json = serde_json::to_value(&decoded[1]).unwrap();
+	assert_eq!(json["activity"]["type"], "VoteCast");
+	assert_eq!(json["activity"]["proposalId"], 2);
+	assert_eq!(json["activity"]["votes"], "3");
+
+	// the events of another runtime are skipped, rather than misread
+	assert!(round_events::<Runtime, _>(block_hash, &[1, 2, 3]).is_empty());
+}
+
+#[test]
+fn streams_cancelled_rounds_to_phase_subscribers() {
+	use codec::Encode;
+	use events::{is_phase_event, round_events, RoundActivity, RoundEvent};
+	use frame_system::{EventRecord, Phase};
+	use parachain_template_runtime::{Event as RuntimeEvent, Runtime};
+	use quadratic_voting_pallet::Event as QuadraticVotingEvent;
+
+	let events = vec![EventRecord {
+		phase: Phase::Initialization,
+		event: RuntimeEvent::QuadraticVotingPallet(QuadraticVotingEvent::RoundCancelled { round: 2 }),
+		topics: vec![],
+	}]
+	.encode();
+
+	let block_hash = Hash::repeat_byte(7);
+	let decoded = round_events::<Runtime, _>(block_hash, &events);
+	assert_eq!(
+		decoded,
+		vec![RoundEvent { block_hash, round: 2, activity: RoundActivity::PhaseTransition { phase: "Cancelled".into() } }]
+	);
+	assert!(is_phase_event(&decoded[0]));
+
+	let json = serde_json::to_value(&decoded[0]).unwrap();
+	assert_eq!(json["activity"], serde_json::json!({ "type": "PhaseTransition", "phase": "Cancelled" }));
+}
@@ -0,0 +1,35 @@
+//! Runtime API of the quadratic voting pallet, so that clients can query rounds without decoding its storage.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use quadratic_voting_pallet::{
+	BucketId, ProposalCount, ProposalInfo, ProposalResult, VoterStatus, VotingRoundId, VotingRoundMetadata,
+};
+
+sp_api::decl_runtime_apis! {
+	pub trait QuadraticVotingApi<AccountId, BlockNumber, Balance>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// The phase of a round and the blocks of each of its phases, unless the round does not exist or was reaped.
+		fn round_info(voting_round_id: VotingRoundId) -> Option<VotingRoundMetadata<AccountId, BlockNumber, Balance>>;
+
+		/// The proposals in a bucket of a round, in the order of their ids.
+		fn bucket_proposals(voting_round_id: VotingRoundId, bucket_id: BucketId) -> Vec<ProposalInfo<AccountId>>;
+
+		/// The buckets an account registered in, its remaining credits in each, and the bonds held for it.
+		fn voter_status(voting_round_id: VotingRoundId, who: AccountId) -> VoterStatus<Balance>;
+
+		/// The result of every proposal of a round, as recorded once it is tallied, or as projected from the votes
+		/// cast so far.
+		fn projected_tally(voting_round_id: VotingRoundId) -> Vec<(ProposalCount, ProposalResult<Balance>)>;
+
+		/// The credits it takes to cast `votes` votes on a proposal, or `None` if it overflows.
+		fn vote_cost(votes: Balance) -> Option<Balance>;
+	}
+}
@@ -0,0 +1,681 @@
+//! Benchmarking setup for quadratic-voting-pallet
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as QuadraticVoting;
+use crate::{
+	fixture::{self, TallyCircuit},
+	matching::MatchingFormula,
+	threshold, BenchmarkHelper,
+};
+use codec::Encode;
+use curve25519_dalek::scalar::Scalar;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, Hooks, ReservableCurrency},
+	BoundedVec,
+};
+use frame_system::{ensure_signed, RawOrigin};
+use merlin::Transcript;
+use pallet_identity::{Data, IdentityInfo};
+use schnorrkel::{context::attach_rng, SecretKey};
+use schnorrkel_rng::{rand_core::SeedableRng, ChaChaRng};
+use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+use sp_runtime::traits::{Bounded, Hash};
+use sp_std::{boxed::Box, vec, vec::Vec};
+
+type ProposalOf<T> = Proposal<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as Config>::MaxVotes>;
+type RoundOf<T> =
+	VotingRoundMetadata<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber, BalanceOf<T>>;
+
+const SEED: u32 = 0;
+// every benchmark runs in the first round, and puts its proposals in the same bucket
+const ROUND: VotingRoundId = 1;
+const BUCKET: BucketId = 0;
+
+fn funded<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Token::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 1_000_000u32.into());
+	who
+}
+
+fn credits<T: Config>() -> BalanceOf<T> {
+	100u32.into()
+}
+
+fn vote<T: Config>() -> BalanceOf<T> {
+	4u32.into()
+}
+
+fn with_identity<T: Config>(who: &T::AccountId) {
+	let info = IdentityInfo {
+		additional: Default::default(),
+		display: Data::None,
+		legal: Data::None,
+		web: Data::None,
+		riot: Data::None,
+		email: Data::None,
+		pgp_fingerprint: None,
+		image: Data::None,
+		twitter: Data::None,
+	};
+	pallet_identity::Pallet::<T>::set_identity(RawOrigin::Signed(who.clone()).into(), Box::new(info)).unwrap();
+}
+
+fn register<T: Config>(who: &T::AccountId, bucket_id: BucketId) {
+	T::Token::reserve(who, credits::<T>()).unwrap();
+	VotersForBucket::<T>::insert((ROUND, bucket_id, who), VoterBond { total: credits::<T>(), remaining: credits::<T>() });
+	UnclaimedRefunds::<T>::mutate((ROUND, who), |refund| *refund += credits::<T>());
+}
+
+// puts the round straight into `phase`. Rounds are set up in storage, so that the benchmarks do not depend on
+// the manager origin being a signed one
+fn setup_round<T: Config>(mode: RoundMode<BalanceOf<T>>, ballot: BallotMode, phase: VotingPhases) -> RoundOf<T> {
+	let initializer = funded::<T>("initializer", 0);
+	T::Token::reserve(&initializer, T::BondForVotingRound::get()).unwrap();
+	// forfeited bonds are paid to the pallet account, which the genesis of the pallet keeps alive
+	T::Token::make_free_balance_be(&QuadraticVoting::<T>::account_id(), T::Token::minimum_balance());
+	UnclaimedRefunds::<T>::insert((ROUND, &initializer), T::BondForVotingRound::get());
+
+	let mut round =
+		make_voting_round_metadata::<T>(initializer, frame_system::Pallet::<T>::block_number(), 0, mode, ballot)
+			.unwrap();
+	round.phase = phase;
+	VotingRounds::<T>::insert(ROUND, round.clone());
+	LatestVotingRound::<T>::put(ROUND);
+	RoundRandomness::<T>::insert(ROUND, T::Hashing::hash_of(&ROUND));
+	round
+}
+
+// `count` proposals, each backed by every one of `voters` voters registered in the bucket
+fn setup_proposals<T: Config>(count: u32, voters: u32) -> Vec<T::AccountId> {
+	let voters: Vec<T::AccountId> = (0..voters).map(|i| funded::<T>("voter", i)).collect();
+	for voter in &voters {
+		register::<T>(voter, BUCKET);
+	}
+	BucketCredits::<T>::insert((ROUND, BUCKET), credits::<T>() * (voters.len() as u32).into());
+
+	let ayes = BoundedVec::truncate_from(vec![vote::<T>(); voters.len()]);
+	let backers = BoundedVec::<T::AccountId, T::MaxVotes>::truncate_from(voters.clone());
+	let proposals: Vec<ProposalOf<T>> = (0..count)
+		.map(|i| {
+			let initializer = funded::<T>("proposer", i);
+			T::Token::reserve(&initializer, T::BondForProposal::get()).unwrap();
+			UnclaimedRefunds::<T>::mutate((ROUND, &initializer), |refund| *refund += T::BondForProposal::get());
+			if !voters.is_empty() {
+				VotersVoted::<T>::insert((ROUND, i), backers.clone());
+				for voter in &voters {
+					VotersVotedOnProposal::<T>::insert((ROUND, i, voter), ());
+				}
+			}
+			Proposal {
+				initializer,
+				class: ProposalClass::General,
+				ayes: ayes.clone(),
+				nays: Default::default(),
+				abstains: Default::default(),
+				bucket_id: Some(BUCKET),
+			}
+		})
+		.collect();
+	ProposalsForVotingRound::<T>::insert(ROUND, BoundedVec::truncate_from(proposals));
+	voters
+}
+
+// a committee of `size` bonded members for the bucket
+fn setup_committee<T: Config>(size: u32) -> Vec<T::AccountId> {
+	let members: Vec<T::AccountId> = (0..size)
+		.map(|i| {
+			let member = funded::<T>("member", i);
+			T::Token::reserve(&member, T::BondForCommittee::get()).unwrap();
+			member
+		})
+		.collect();
+	Committees::<T>::insert((ROUND, BUCKET), BoundedVec::truncate_from(members.clone()));
+	members
+}
+
+// a `threshold`-of-`members` key for the round. Scalars only need to be distinct here, not random
+fn setup_threshold_key<T: Config>(threshold: u32, members: &[T::AccountId]) -> threshold::Dealing {
+	let mut next = 0u64;
+	let dealing = threshold::deal(threshold, members.len() as u32, || {
+		next += 1;
+		Scalar::from(next)
+	});
+	let key = ThresholdKeySet {
+		public_key: dealing.public_key,
+		threshold,
+		members: BoundedVec::truncate_from(
+			members.iter().cloned().zip(dealing.shares.iter().map(|(_, _, verification_key)| *verification_key)).collect(),
+		),
+	};
+	RoundThresholdKey::<T>::insert(ROUND, key);
+	dealing
+}
+
+// a ballot for the first proposal, encrypted with the nonce `r`
+fn threshold_ballot<T: Config>(public_key: &[u8; 32], r: u64) -> EncryptedMessage<T::MaxMessageLength> {
+	let plaintext = (0 as ProposalCount, vote::<T>(), VoteDirection::Aye).encode();
+	let (ephemeral_key, data) = threshold::encrypt(public_key, &plaintext, Scalar::from(r)).unwrap();
+	EncryptedMessage { ephemeral_key, data: BoundedVec::truncate_from(data) }
+}
+
+fn assert_phase<T: Config>(phase: VotingPhases) {
+	assert_eq!(VotingRounds::<T>::get(ROUND).unwrap().phase, phase);
+}
+
+benchmarks! {
+	start_voting_round {
+		let origin = T::ManagerOrigin::successful_origin();
+		let caller = ensure_signed(origin.clone()).map_err(|_| "the manager origin must be a signed one")?;
+		T::Token::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 1_000_000u32.into());
+		// the previous round must be over
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Finalized);
+		let matching_pool = T::Token::minimum_balance() * 10u32.into();
+	}: _<T::Origin>(origin, RoundMode::Funding { matching_pool }, BallotMode::Open)
+	verify {
+		assert_eq!(LatestVotingRound::<T>::get(), Some(ROUND + 1));
+	}
+
+	submit_proposal {
+		let p in 0 .. T::MaxProposals::get() - 1;
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Proposal);
+		setup_proposals::<T>(p, 0);
+		let caller = funded::<T>("proposer", p);
+		with_identity::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), ProposalClass::General)
+	verify {
+		assert_eq!(ProposalsForVotingRound::<T>::get(ROUND).unwrap().len() as u32, p + 1);
+	}
+
+	register_to_vote {
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::PreVoting);
+		setup_proposals::<T>(T::MaxProposals::get(), 0);
+		let caller = funded::<T>("caller", 0);
+		with_identity::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), BUCKET, credits::<T>())
+	verify {
+		assert!(VotersForBucket::<T>::contains_key((ROUND, BUCKET, &caller)));
+	}
+
+	// funding round votes also move the contribution to the proposer
+	vote {
+		let p in 1 .. T::MaxProposals::get();
+		let v in 0 .. T::MaxVotes::get() - 1;
+		setup_round::<T>(RoundMode::Funding { matching_pool: 0u32.into() }, BallotMode::Open, VotingPhases::Voting);
+		setup_proposals::<T>(p, v);
+		let caller = funded::<T>("caller", 0);
+		register::<T>(&caller, BUCKET);
+	}: _(RawOrigin::Signed(caller.clone()), p - 1, vote::<T>(), VoteDirection::Aye)
+	verify {
+		assert!(VotersVotedOnProposal::<T>::contains_key((ROUND, p - 1, &caller)));
+	}
+
+	commit_vote {
+		let p in 1 .. T::MaxProposals::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::CommitReveal, VotingPhases::Voting);
+		setup_proposals::<T>(p, 0);
+		let caller = funded::<T>("caller", 0);
+		register::<T>(&caller, BUCKET);
+		let commitment = QuadraticVoting::<T>::vote_commitment(&caller, p - 1, vote::<T>(), &VoteDirection::Aye, &[0u8; 32]);
+	}: _(RawOrigin::Signed(caller.clone()), p - 1, commitment, vote::<T>())
+	verify {
+		assert!(VoteCommitments::<T>::contains_key((ROUND, p - 1, &caller)));
+	}
+
+	reveal_vote {
+		let p in 1 .. T::MaxProposals::get();
+		let v in 0 .. T::MaxVotes::get() - 1;
+		setup_round::<T>(RoundMode::Governance, BallotMode::CommitReveal, VotingPhases::Reveal);
+		setup_proposals::<T>(p, v);
+		let caller = funded::<T>("caller", 0);
+		register::<T>(&caller, BUCKET);
+		let salt = [0u8; 32];
+		let commitment = QuadraticVoting::<T>::vote_commitment(&caller, p - 1, vote::<T>(), &VoteDirection::Aye, &salt);
+		VotersForBucket::<T>::insert((ROUND, BUCKET, &caller), VoterBond { total: credits::<T>(), remaining: credits::<T>() - vote::<T>() });
+		VoteCommitments::<T>::insert((ROUND, p - 1, &caller), VoteCommitment { commitment, credits: vote::<T>() });
+	}: _(RawOrigin::Signed(caller.clone()), p - 1, vote::<T>(), VoteDirection::Aye, salt)
+	verify {
+		assert!(VotersVotedOnProposal::<T>::contains_key((ROUND, p - 1, &caller)));
+	}
+
+	set_coordinator {
+		let origin = T::ManagerOrigin::successful_origin();
+		let coordinator: T::AccountId = account("coordinator", 0, SEED);
+	}: _<T::Origin>(origin, coordinator, [0u8; 32])
+	verify {
+		assert!(Coordinator::<T>::get().is_some());
+	}
+
+	sign_up {
+		setup_round::<T>(RoundMode::Governance, BallotMode::Encrypted, VotingPhases::PreVoting);
+		let caller = funded::<T>("caller", 0);
+		register::<T>(&caller, BUCKET);
+	}: _(RawOrigin::Signed(caller.clone()), [1u8; 32])
+	verify {
+		assert!(VotingKeys::<T>::contains_key((ROUND, &caller)));
+	}
+
+	publish_message {
+		setup_round::<T>(RoundMode::Governance, BallotMode::Encrypted, VotingPhases::Voting);
+		let caller = funded::<T>("caller", 0);
+		VotingKeys::<T>::insert((ROUND, &caller), [1u8; 32]);
+		let message = EncryptedMessage {
+			ephemeral_key: [0u8; 32],
+			data: BoundedVec::truncate_from(vec![0u8; T::MaxMessageLength::get() as usize]),
+		};
+	}: _(RawOrigin::Signed(caller), message)
+	verify {
+		assert_eq!(MessageCount::<T>::get(ROUND), 1);
+	}
+
+	// the tally of `p` proposals comes with a proof of the fixture circuit, which the verifier accepts
+	submit_tally {
+		let p in 1 .. T::MaxProposals::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::Encrypted, VotingPhases::Reveal);
+		setup_proposals::<T>(p, 0);
+		let coordinator = funded::<T>("coordinator", 0);
+		RoundCoordinator::<T>::insert(ROUND, CoordinatorInfo { account: coordinator.clone(), public_key: [0u8; 32] });
+		let tally = vec![TallyResult::default(); p as usize];
+
+		// every total of the tally is the sum of a single vote
+		let public_inputs = QuadraticVoting::<T>::tally_public_inputs(ROUND, &tally);
+		let entries = public_inputs.len() - 2;
+		let (params, key) = fixture::setup(entries, 1);
+		T::BenchmarkHelper::set_tally_verifying_key(key);
+		let proof: BoundedVec<u8, T::MaxProofLength> =
+			fixture::prove(&params, TallyCircuit { public_inputs, votes: vec![vec![0]; entries] })
+				.try_into()
+				.map_err(|_| "the proof does not fit in MaxProofLength")?;
+		let tally: BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals> = BoundedVec::truncate_from(tally);
+	}: _(RawOrigin::Signed(coordinator), tally, proof)
+	verify {
+		assert!(CoordinatorTally::<T>::contains_key(ROUND));
+	}
+
+	set_threshold_key {
+		let m in 1 .. T::MaxCommitteeMembers::get();
+		let origin = T::ManagerOrigin::successful_origin();
+		let members: BoundedVec<(T::AccountId, [u8; 32]), T::MaxCommitteeMembers> =
+			BoundedVec::truncate_from((0..m).map(|i| (account("member", i, SEED), [0u8; 32])).collect());
+	}: _<T::Origin>(origin, [0u8; 32], m, members)
+	verify {
+		assert!(ThresholdKey::<T>::get().is_some());
+	}
+
+	// the voter is registered in the last bucket that is looked up
+	cast_threshold_ballot {
+		setup_round::<T>(RoundMode::Governance, BallotMode::ThresholdEncrypted, VotingPhases::Voting);
+		let caller = funded::<T>("caller", 0);
+		register::<T>(&caller, T::BucketSize::get());
+		let ballot = EncryptedMessage {
+			ephemeral_key: [0u8; 32],
+			data: BoundedVec::truncate_from(vec![0u8; T::MaxMessageLength::get() as usize]),
+		};
+	}: _(RawOrigin::Signed(caller), ballot)
+	verify {
+		assert_eq!(MessageCount::<T>::get(ROUND), 1);
+	}
+
+	// the last batch of the last member of a full committee, which joins the submitters after every other member
+	submit_decryption_shares {
+		let b in 1 .. T::MaxSharesPerCall::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::ThresholdEncrypted, VotingPhases::Reveal);
+		let origin = T::DecryptionOrigin::successful_origin();
+		let member = T::DecryptionOrigin::ensure_origin(origin.clone()).map_err(|_| "no decryption origin")?;
+		let size = T::MaxCommitteeMembers::get();
+		let mut members: Vec<T::AccountId> = (1..size).map(|i| account("member", i, SEED)).collect();
+		members.push(member.clone());
+		let dealing = setup_threshold_key::<T>(size, &members);
+		let (_, secret_share, _) = dealing.shares[size as usize - 1];
+		let others: Vec<(u32, T::AccountId)> = members[..size as usize - 1]
+			.iter()
+			.zip(dealing.shares.iter())
+			.map(|(other, (index, _, _))| (*index, other.clone()))
+			.collect();
+		ShareSubmitters::<T>::insert(ROUND, BoundedVec::truncate_from(others));
+
+		let voter: T::AccountId = account("voter", 0, SEED);
+		let mut shares = Vec::new();
+		for i in 0..b {
+			let ballot = threshold_ballot::<T>(&dealing.public_key, i as u64 + 1);
+			shares.push(threshold::decryption_share(&secret_share, &ballot.ephemeral_key, Scalar::from(i as u64 + 1)).unwrap());
+			ThresholdBallots::<T>::insert((ROUND, i), (&voter, ballot));
+		}
+		MessageCount::<T>::insert(ROUND, b);
+		let shares: BoundedVec<threshold::DecryptionShare, T::MaxSharesPerCall> = BoundedVec::truncate_from(shares);
+	}: _<T::Origin>(origin, 0, shares)
+	verify {
+		assert_eq!(SharesSubmitted::<T>::get((ROUND, &member)), b);
+		assert_eq!(ShareSubmitters::<T>::get(ROUND).len() as u32, size);
+	}
+
+	// the applicant fills the committee with a valid VRF output. Randomness is drawn for the round until the
+	// output is below the committee threshold
+	apply_for_committee {
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::PreVoting);
+		let applicant = T::BenchmarkHelper::vrf_account();
+		T::Token::make_free_balance_be(&applicant, BalanceOf::<T>::max_value() / 1_000_000u32.into());
+		register::<T>(&applicant, BUCKET);
+		setup_committee::<T>(T::MaxBucketCommitteeSize::get() - 1);
+
+		let keypair = SecretKey::from_bytes(&T::BenchmarkHelper::vrf_secret_key())
+			.map_err(|_| "invalid VRF secret key")?
+			.to_keypair();
+		let mut draw = 0u32;
+		let (output, proof) = loop {
+			if draw == 1_000 {
+				return Err("no randomness makes the applicant eligible".into())
+			}
+			let randomness = T::Hashing::hash_of(&(ROUND, draw));
+			let transcript = QuadraticVoting::<T>::committee_transcript(ROUND, BUCKET, &randomness);
+			// the extra transcript `vrf_sign` uses, with a generator that does not need system randomness
+			let extra = attach_rng(Transcript::new(b"VRF"), ChaChaRng::from_seed([0u8; 32]));
+			let (inout, proof, _) = keypair.vrf_sign_extra(transcript, extra);
+			let score = u128::from_le_bytes(inout.make_bytes::<[u8; 16]>(b"quadratic-voting-committee-score"));
+			if score < QuadraticVoting::<T>::committee_threshold() {
+				RoundRandomness::<T>::insert(ROUND, randomness);
+				break (VRFOutput(inout.to_output()), VRFProof(proof))
+			}
+			draw += 1;
+		};
+	}: _(RawOrigin::Signed(applicant.clone()), BUCKET, output, proof)
+	verify {
+		let committee = Committees::<T>::get((ROUND, BUCKET));
+		assert_eq!(committee.len() as u32, T::MaxBucketCommitteeSize::get());
+		assert!(committee.contains(&applicant));
+	}
+
+	// the caller's tally is the one that reaches a majority
+	submit_bucket_tally {
+		let p in 1 .. T::MaxProposals::get();
+		let c in 1 .. T::MaxBucketCommitteeSize::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::PostVoting);
+		setup_proposals::<T>(p, 0);
+		let members = setup_committee::<T>(c);
+		let results: BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals> =
+			BoundedVec::truncate_from(vec![TallyResult::default(); p as usize]);
+		let hash = T::Hashing::hash_of(&results);
+		for member in &members[1..] {
+			BucketTallySubmissions::<T>::insert((ROUND, BUCKET, member), hash);
+		}
+	}: _(RawOrigin::Signed(members[0].clone()), BUCKET, results)
+	verify {
+		assert!(BucketTallies::<T>::contains_key((ROUND, BUCKET)));
+	}
+
+	// the whole committee agreed on a wrong tally, and is slashed
+	challenge_tally {
+		let p in 1 .. T::MaxProposals::get();
+		let v in 0 .. T::MaxVotes::get();
+		let c in 1 .. T::MaxBucketCommitteeSize::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::PostVoting);
+		setup_proposals::<T>(p, v);
+		let members = setup_committee::<T>(c);
+		let wrong = TallyResult { ayes: 0u32.into(), nays: 1u32.into(), abstains: 0u32.into(), credits: 1u32.into() };
+		let results: BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals> =
+			BoundedVec::truncate_from(vec![wrong; p as usize]);
+		for member in &members {
+			BucketTallySubmissions::<T>::insert((ROUND, BUCKET, member), T::Hashing::hash_of(&results));
+		}
+		BucketTallies::<T>::insert(
+			(ROUND, BUCKET),
+			BucketTally { results, accepted_at: frame_system::Pallet::<T>::block_number(), challenged: false },
+		);
+
+		let challenger = funded::<T>("challenger", 0);
+		register::<T>(&challenger, T::BucketSize::get());
+	}: _(RawOrigin::Signed(challenger), BUCKET)
+	verify {
+		assert!(BucketTallies::<T>::get((ROUND, BUCKET)).unwrap().challenged);
+		assert!(Committees::<T>::get((ROUND, BUCKET)).is_empty());
+	}
+
+	// the caller voted on the last of `p` proposals of its bucket, so every proposal is looked at
+	claim_refund {
+		let p in 1 .. T::MaxProposals::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Enactment);
+		setup_proposals::<T>(p, 0);
+		let caller = funded::<T>("voter", 0);
+		register::<T>(&caller, BUCKET);
+		VotersVotedOnProposal::<T>::insert((ROUND, p - 1, &caller), ());
+	}: _(RawOrigin::Signed(caller.clone()), ROUND)
+	verify {
+		assert!(!UnclaimedRefunds::<T>::contains_key((ROUND, &caller)));
+		assert_eq!(T::Token::reserved_balance(&caller), 0u32.into());
+	}
+
+	refund_others {
+		let p in 1 .. T::MaxProposals::get();
+		setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Finalized);
+		setup_proposals::<T>(p, 0);
+		let voter = funded::<T>("voter", 0);
+		register::<T>(&voter, BUCKET);
+		VotersVotedOnProposal::<T>::insert((ROUND, p - 1, &voter), ());
+		let caller = funded::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller), ROUND, voter.clone())
+	verify {
+		assert!(!UnclaimedRefunds::<T>::contains_key((ROUND, &voter)));
+		assert_eq!(T::Token::reserved_balance(&voter), 0u32.into());
+	}
+
+	// a threshold round waiting for its decryption shares reads the most without changing phase
+	on_initialize_idle {
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::ThresholdEncrypted, VotingPhases::Reveal);
+		let member: T::AccountId = account("member", 0, SEED);
+		setup_threshold_key::<T>(1, &[member]);
+		let block = round.reveal_phase.unwrap().start_block;
+	}: {
+		QuadraticVoting::<T>::on_initialize(block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Reveal);
+	}
+
+	start_pre_voting {
+		let p in 0 .. T::MaxProposals::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Proposal);
+		setup_proposals::<T>(p, 0);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.proposal_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::PreVoting);
+	}
+
+	start_voting {
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::PreVoting);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.pre_voting_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Voting);
+	}
+
+	start_reveal {
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::CommitReveal, VotingPhases::Voting);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.voting_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Reveal);
+	}
+
+	// `p` proposals are tallied in one batch, which is the last one
+	tally {
+		let p in 1 .. T::MaxTalliesPerBlock::get().min(T::MaxProposals::get());
+		let v in 0 .. T::MaxVotes::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Tallying);
+		setup_proposals::<T>(p, v);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.voting_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::PostVoting);
+	}
+
+	// `c` commitments were never revealed. The proposals are already tallied, so only the commitments are measured
+	settle_commitments {
+		let c in 1 .. T::MaxRefundsPerBlock::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::CommitReveal, VotingPhases::Tallying);
+		setup_proposals::<T>(1, 0);
+		TallyCursor::<T>::insert(ROUND, 1);
+		for i in 0..c {
+			let committer = funded::<T>("committer", i);
+			register::<T>(&committer, BUCKET);
+			let commitment = VoteCommitment { commitment: Default::default(), credits: vote::<T>() };
+			VoteCommitments::<T>::insert((ROUND, 0, &committer), commitment);
+		}
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.reveal_phase.unwrap().end_block);
+	}
+	verify {
+		assert_eq!(VoteCommitments::<T>::iter_prefix((ROUND,)).count(), 0);
+	}
+
+	// `b` ballots are opened with the shares of `t` members. The batch is the last one, so the round is also tallied
+	decrypt_ballots {
+		let b in 1 .. T::MaxDecryptionsPerBlock::get();
+		let t in 1 .. T::MaxCommitteeMembers::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::ThresholdEncrypted, VotingPhases::Reveal);
+		setup_proposals::<T>(1, 0);
+		let members: Vec<T::AccountId> = (0..t).map(|i| account("member", i, SEED)).collect();
+		let dealing = setup_threshold_key::<T>(t, &members);
+
+		for i in 0..b {
+			let voter = funded::<T>("voter", i);
+			register::<T>(&voter, BUCKET);
+			let ballot = threshold_ballot::<T>(&dealing.public_key, i as u64 + 1);
+			for (member, (_, secret_share, _)) in members.iter().zip(dealing.shares.iter()) {
+				let share = threshold::decryption_share(secret_share, &ballot.ephemeral_key, Scalar::from(i as u64 + 1)).unwrap();
+				DecryptionShares::<T>::insert((ROUND, member, i), share);
+			}
+			ThresholdBallots::<T>::insert((ROUND, i), (&voter, ballot));
+		}
+		MessageCount::<T>::insert(ROUND, b);
+
+		let mut submitters = Vec::new();
+		for (member, (index, _, _)) in members.iter().zip(dealing.shares.iter()) {
+			SharesSubmitted::<T>::insert((ROUND, member), b);
+			submitters.push((*index, member.clone()));
+		}
+		ShareSubmitters::<T>::insert(ROUND, BoundedVec::truncate_from(submitters));
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.reveal_phase.unwrap().start_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::PostVoting);
+	}
+
+	// the decryption shares never arrived, so the `r` bonds of the committee are returned. Everyone else claims
+	// their bonds back
+	cancel_round {
+		let r in 0 .. T::MaxBucketCommitteeSize::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::ThresholdEncrypted, VotingPhases::Reveal);
+		setup_proposals::<T>(1, 0);
+		setup_committee::<T>(r);
+		let member: T::AccountId = account("member", 0, SEED);
+		setup_threshold_key::<T>(1, &[member]);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.reveal_phase.unwrap().end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Cancelled);
+	}
+
+	// a funding round, where every one of `p` proposals passed and is matched, and `r` committee bonds are returned
+	finish_settling {
+		let p in 1 .. T::MaxProposals::get();
+		let r in 0 .. T::MaxBucketCommitteeSize::get();
+		let round = setup_round::<T>(
+			RoundMode::Funding { matching_pool: T::Token::minimum_balance() * 10u32.into() },
+			BallotMode::Open,
+			VotingPhases::Settling,
+		);
+		setup_proposals::<T>(p, 0);
+		setup_committee::<T>(r);
+		SettlementProgress::<T>::insert(ROUND, Settlement::Finishing);
+		T::Token::make_free_balance_be(
+			&QuadraticVoting::<T>::matching_pool_account(ROUND),
+			T::Token::minimum_balance() * 10u32.into(),
+		);
+
+		let contributions: Vec<(T::AccountId, BalanceOf<T>)> = (0..T::MaxContributorsPerProposal::get())
+			.map(|i| (account("contributor", i, SEED), vote::<T>()))
+			.collect();
+		for i in 0..p {
+			Contributions::<T>::insert((ROUND, i), BoundedVec::truncate_from(contributions.clone()));
+			let result = ProposalResult {
+				ayes: vote::<T>(),
+				nays: 0u32.into(),
+				abstains: 0u32.into(),
+				credits: vote::<T>() * vote::<T>(),
+				threshold: Threshold::SimpleMajority,
+				outcome: Outcome::Aye,
+			};
+			ProposalOutcome::<T>::insert((ROUND, i), result);
+		}
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.post_voting_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Enactment);
+	}
+
+	finalize {
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Enactment);
+	}: {
+		QuadraticVoting::<T>::on_initialize(round.enactment_phase.end_block);
+	}
+	verify {
+		assert_phase::<T>(VotingPhases::Finalized);
+	}
+
+	// every contributor backs every proposal, which is the worst case for pairwise matching
+	// a finalized round of `p` proposals with `v` votes each is summarized
+	summarize_round {
+		let p in 1 .. T::MaxProposals::get();
+		let v in 0 .. T::MaxVotes::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Finalized);
+		setup_proposals::<T>(p, v);
+		LatestVotingRound::<T>::put(ROUND + 1);
+		let now = round.finalized_block + T::RetentionPeriod::get();
+	}: {
+		QuadraticVoting::<T>::on_idle(now, u64::MAX);
+	}
+	verify {
+		assert!(RoundSummaries::<T>::contains_key(ROUND));
+	}
+
+	// `r` bonds were left unclaimed: the initializer's, the proposer's and those of `r - 2` voters who did not vote,
+	// which are the most expensive to release
+	reap_round {
+		let r in 2 .. T::MaxRemovalsPerReap::get();
+		let round = setup_round::<T>(RoundMode::Governance, BallotMode::Open, VotingPhases::Finalized);
+		setup_proposals::<T>(1, 0);
+		for i in 0..r - 2 {
+			register::<T>(&funded::<T>("voter", i), BUCKET);
+		}
+		LatestVotingRound::<T>::put(ROUND + 1);
+		let now = round.finalized_block + T::RetentionPeriod::get();
+		QuadraticVoting::<T>::on_idle(now, u64::MAX);
+	}: {
+		QuadraticVoting::<T>::on_idle(now, u64::MAX);
+	}
+	verify {
+		assert_eq!(UnclaimedRefunds::<T>::iter_prefix((ROUND,)).count(), 0);
+	}
+
+	matching_formula {
+		let p in 1 .. T::MaxProposals::get();
+		let c in 1 .. T::MaxContributorsPerProposal::get();
+		let contributions: Vec<Vec<(T::AccountId, BalanceOf<T>)>> = (0..p)
+			.map(|_| (0..c).map(|i| (account("contributor", i, 0), (1_000u32 + i).into())).collect())
+			.collect();
+	}: {
+		T::MatchingFormula::scores(&contributions);
+	}
+
+	impl_benchmark_test_suite!(QuadraticVoting, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,68 @@
+// A stand-in for the tally circuit of encrypted rounds. It only proves that the public tally is the sum
+// of the decrypted votes, which is enough to exercise `submit_tally` and the Groth16 verifier offline, and
+// to benchmark it with a proof that is accepted.
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{create_random_proof, generate_random_parameters, ProvingKey};
+use ark_relations::{
+	lc,
+	r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_serialize::CanonicalSerialize;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use sp_std::{vec, vec::Vec};
+
+// the parameters and proofs only need to be reproducible, not secret
+fn rng() -> ChaChaRng {
+	ChaChaRng::from_seed([0u8; 32])
+}
+
+#[derive(Clone)]
+pub struct TallyCircuit {
+	pub public_inputs: Vec<[u8; 32]>,
+	// the decrypted votes of every tally entry, in the order of the public inputs after the two roots.
+	// Every entry must have as many votes as the circuit was set up with
+	pub votes: Vec<Vec<u128>>,
+}
+
+impl ConstraintSynthesizer<Fr> for TallyCircuit {
+	fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+		let inputs: Vec<Fr> = self.public_inputs.iter().map(|input| Fr::from_le_bytes_mod_order(input)).collect();
+
+		// the roots are only bound to the proof
+		for root in &inputs[..2] {
+			cs.new_input_variable(|| Ok(*root))?;
+		}
+
+		for (total, votes) in inputs[2..].iter().zip(self.votes) {
+			let total = cs.new_input_variable(|| Ok(*total))?;
+			let mut sum = lc!();
+			for vote in votes {
+				sum = sum + cs.new_witness_variable(|| Ok(Fr::from(vote)))?;
+			}
+			cs.enforce_constraint(sum, lc!() + Variable::One, lc!() + total)?;
+		}
+
+		Ok(())
+	}
+}
+
+// sets up the circuit for `entries` totals of `voters` votes each, returning the proving key and the
+// serialized verifying key
+pub fn setup(entries: usize, voters: usize) -> (ProvingKey<Bn254>, Vec<u8>) {
+	let circuit = TallyCircuit { public_inputs: vec![[0u8; 32]; 2 + entries], votes: vec![vec![0; voters]; entries] };
+	let params = generate_random_parameters::<Bn254, _, _>(circuit, &mut rng()).unwrap();
+
+	let mut vk = Vec::new();
+	params.vk.serialize(&mut vk).unwrap();
+	(params, vk)
+}
+
+pub fn prove(params: &ProvingKey<Bn254>, circuit: TallyCircuit) -> Vec<u8> {
+	let proof = create_random_proof(circuit, params, &mut rng()).unwrap();
+
+	let mut bytes = Vec::new();
+	proof.serialize(&mut bytes).unwrap();
+	bytes
+}
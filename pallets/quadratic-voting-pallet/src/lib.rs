@@ -5,26 +5,73 @@
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
+
+// drives the mock through random sequences of calls, for the property tests and the fuzz target
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod model;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod fixture;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod matching;
+pub mod merkle;
+pub mod migrations;
+pub mod threshold;
+pub mod verifier;
+pub mod weights;
+pub use weights::WeightInfo;
+
+// what the benchmarks need from the runtime, and cannot set up on their own
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId> {
+	// an account whose `VrfKey` is a valid sr25519 public key
+	fn vrf_account() -> AccountId;
+
+	// the sr25519 secret key of `vrf_account`, in the 64 byte encoding of `schnorrkel::SecretKey`
+	fn vrf_secret_key() -> [u8; 64];
+
+	// makes `TallyVerifier` check proofs against the given Groth16 verifying key
+	fn set_tally_verifying_key(key: sp_std::vec::Vec<u8>);
+}
+
 
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
 		BoundedVec,
 		pallet_prelude::*,
-		traits::{Currency, EnsureOrigin, Randomness, ReservableCurrency},
+		traits::{
+			BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, PalletInfoAccess, Randomness,
+			ReservableCurrency,
+		},
+		PalletId, StorageHasher, Twox128,
 	};
-	use frame_system::pallet_prelude::*;
+	use frame_system::{pallet_prelude::*, RawOrigin};
+	use pallet_identity::{Data, IdentityInfo, Judgement};
 	use rand::{seq::SliceRandom, SeedableRng}; // 0.6.5
 	use rand_chacha::ChaChaRng;
 	use scale_info::TypeInfo;
-	use sp_runtime::traits::IntegerSquareRoot; // 0.1.1
-	use sp_std::vec::Vec;
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
+	use sp_runtime::{traits::{AccountIdConversion, AtLeast32BitUnsigned, CheckedMul, Convert, Hash, IntegerSquareRoot, One, Saturating, StaticLookup, Zero}, PerThing, Perbill}; // 0.1.1
+	use schnorrkel::{context::SigningTranscript, PublicKey};
+	use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+	use sp_std::{boxed::Box, vec::Vec};
+	use crate::{
+		matching::MatchingFormula,
+		merkle,
+		threshold::{self, DecryptionShare},
+		verifier::TallyVerifier,
+		WeightInfo,
+	};
 
 
 	// Ideally, these would be in a primitives directory
@@ -44,6 +91,7 @@ pub mod pallet {
 		type BlocksForPreVotingPhase: Get<BlockNumberFor<Self>>;
 		type BlocksForProposalPhase: Get<BlockNumberFor<Self>>;
 		type BlocksForEnactmentPhase: Get<BlockNumberFor<Self>>;
+		type BlocksForRevealPhase: Get<BlockNumberFor<Self>>;
 		#[pallet::constant]
 		type MaxProposals: Get<ProposalCount>;
 		type Token: ReservableCurrency<Self::AccountId>;
@@ -55,27 +103,267 @@ pub mod pallet {
 		type MaxVotes: Get<MaxVotes>;
 		type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
 		type BucketSize: Get<BucketId>;
+		// share of the credits registered in a bucket that must be spent on a proposal for it to be decided
+		#[pallet::constant]
+		type Quorum: Get<Perbill>;
+		// approval threshold applied to the proposals of each class
+		type ClassThreshold: Convert<ProposalClass, Threshold<BalanceOf<Self>>>;
+		// the matching pools of funding rounds are held by sub-accounts of this id
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		// largest share of a matching pool that a single proposal can receive
+		#[pallet::constant]
+		type MaxMatchingShare: Get<Perbill>;
+		// splits the matching pool of funding rounds between the approved proposals
+		type MatchingFormula: MatchingFormula<Self::AccountId, BalanceOf<Self>>;
+		// bounds the input of the matching formula, which can be quadratic in the number of contributors
+		#[pallet::constant]
+		type MaxContributorsPerProposal: Get<u32>;
+		// what happens to the credits of commitments that are not revealed
+		#[pallet::constant]
+		type UnrevealedCommits: Get<UnrevealedPolicy>;
+		// checks the tallies that coordinators post for encrypted rounds
+		type TallyVerifier: TallyVerifier;
+		#[pallet::constant]
+		type MaxMessageLength: Get<u32>;
+		#[pallet::constant]
+		type MaxMessages: Get<u32>;
+		// messages, or threshold ballots, a voter can publish in a round, so that no voter can use up
+		// `MaxMessages`, nor make the decryption committee open more ballots than others can cast
+		#[pallet::constant]
+		type MaxMessagesPerVoter: Get<u32>;
+		#[pallet::constant]
+		type MaxProofLength: Get<u32>;
+		// members of the decryption committee submit their decryption shares through this origin
+		type DecryptionOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+		#[pallet::constant]
+		type MaxCommitteeMembers: Get<u32>;
+		// decryption shares are submitted in batches of at most this many
+		#[pallet::constant]
+		type MaxSharesPerCall: Get<u32>;
+		// threshold-encrypted ballots are decrypted across several blocks, this many at a time
+		#[pallet::constant]
+		type MaxDecryptionsPerBlock: Get<u32>;
+		// rounds are tallied across several blocks, this many proposals at a time
+		#[pallet::constant]
+		type MaxTalliesPerBlock: Get<u32>;
+		// unrevealed commitments are settled across several blocks, this many at a time
+		#[pallet::constant]
+		type MaxRefundsPerBlock: Get<u32>;
+		// share of a refund paid to whoever releases it with `refund_others`
+		#[pallet::constant]
+		type RefundTip: Get<Perbill>;
+		// a round that is over is reaped once this many rounds were started after it, or this many blocks went by
+		// since it was finalized, whichever comes first. Only its summary is kept
+		#[pallet::constant]
+		type RoundsToKeep: Get<VotingRoundId>;
+		#[pallet::constant]
+		type RetentionPeriod: Get<BlockNumberFor<Self>>;
+		// rounds are reaped across several blocks, this many storage entries at a time
+		#[pallet::constant]
+		type MaxRemovalsPerReap: Get<u32>;
+		// the sr25519 key an account proves its committee eligibility with, if it has one
+		type VrfKey: Convert<Self::AccountId, Option<[u8; 32]>>;
+		// share of the registered voters of a bucket that are eligible for its committee
+		type CommitteeDifficulty: Get<Perbill>;
+		type BondForCommittee: Get<BalanceOf<Self>>;
+		#[pallet::constant]
+		type MaxBucketCommitteeSize: Get<u32>;
+		// blocks after a committee tally is accepted during which it can be challenged
+		type ChallengePeriod: Get<BlockNumberFor<Self>>;
+		type BondForChallenge: Get<BalanceOf<Self>>;
+		type WeightInfo: WeightInfo;
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: crate::BenchmarkHelper<Self::AccountId>;
 	}
 
 	type BlockNumberFor<T> = <T as frame_system::Config>::BlockNumber;
 	type AccountIdFor<T> = <T as frame_system::Config>::AccountId;
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 	<<T as Config>::Token as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	type VotingRoundMetadataOf<T> = VotingRoundMetadata<AccountIdFor<T>, BlockNumberFor<T>, BalanceOf<T>>;
 
 	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	pub enum VotingPhases {
 		Proposal,
 		PreVoting,
 		Voting,
+		// commit-reveal rounds reveal their votes, encrypted rounds wait for the coordinator's tally
+		Reveal,
+		// the proposals are tallied, `MaxTalliesPerBlock` at a time
+		Tallying,
 		PostVoting,
+		// the buckets left undecided by their committee are counted, and the matching pool is paid out. Bonds can be
+		// claimed from here on
+		Settling,
 		Enactment,
 		Finalized,
+		// the decryption committee did not open the ballots in time. Every bond can be claimed back
+		Cancelled,
 	}
 
 	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	pub enum VoteDirection {
 		Aye,
-		Nay
+		Nay,
+		// counts towards turnout, but not towards either side
+		Abstain,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Outcome {
+		Aye,
+		Nay,
+		// turnout on the proposal did not reach the quorum of the round
+		NoQuorum,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum RoundMode<Balance> {
+		// proposals are approved or rejected
+		Governance,
+		// votes are contributions to the proposer, matched from a pool in proportion to (Σ√contribution)²
+		Funding { matching_pool: Balance },
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum BallotMode {
+		// votes are public as soon as they are cast
+		Open,
+		// voters commit to a vote during the Voting phase, and reveal it during the Reveal phase
+		CommitReveal,
+		// voters publish messages encrypted to the coordinator, who posts a proven tally during the Reveal phase
+		Encrypted,
+		// ballots are encrypted to the threshold key of the decryption committee, which opens them during the Reveal phase
+		ThresholdEncrypted,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum UnrevealedPolicy {
+		// the committed credits are simply not counted
+		Ignore,
+		// the committed credits are slashed
+		Slash,
+	}
+
+	// progress of a round through its Settling phase
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Settlement {
+		// the proposals that their committee left undecided are counted, from the given proposal
+		Tallying(ProposalCount),
+		// the committee bonds are returned, and the matching pool is paid out
+		Finishing,
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct VoterBond<Balance> {
+		// bonded when registering, less what has been slashed
+		pub total: Balance,
+		// what is left to vote with
+		pub remaining: Balance,
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct VoteCommitment<Hash, Balance> {
+		pub commitment: Hash,
+		// worst-case credit cost, held until the vote is revealed
+		pub credits: Balance,
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct CoordinatorInfo<AccountId> {
+		pub account: AccountId,
+		// messages of encrypted rounds are encrypted to this key
+		pub public_key: [u8; 32],
+	}
+
+	// a vote, or a change of voting key, encrypted to the coordinator. Both look the same on chain,
+	// so a voter cannot prove how they voted
+	#[derive(Encode, Decode, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(MaxLength))]
+	#[codec(mel_bound())]
+	pub struct EncryptedMessage<MaxLength: Get<u32>> {
+		// ephemeral key used for the shared secret with the coordinator
+		pub ephemeral_key: [u8; 32],
+		pub data: BoundedVec<u8, MaxLength>,
+	}
+
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(MaxMembers))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen))]
+	pub struct ThresholdKeySet<AccountId, MaxMembers>
+	where
+		MaxMembers: Get<u32>,
+	{
+		pub public_key: [u8; 32],
+		// number of decryption shares needed to open a ballot
+		pub threshold: u32,
+		// every member with their verification key. Shares are indexed by position, from 1
+		pub members: BoundedVec<(AccountId, [u8; 32]), MaxMembers>,
+	}
+
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(MaxProposals))]
+	#[codec(mel_bound(BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen))]
+	pub struct BucketTally<BlockNumber, Balance, MaxProposals>
+	where
+		MaxProposals: Get<u32>,
+	{
+		// one result per proposal of the bucket, in the order of the proposal ids
+		pub results: BoundedVec<TallyResult<Balance>, MaxProposals>,
+		pub accepted_at: BlockNumber,
+		// a challenged tally has been recounted, and is final
+		pub challenged: bool,
+	}
+
+	#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct TallyResult<Balance> {
+		pub ayes: Balance,
+		pub nays: Balance,
+		pub abstains: Balance,
+		// what the votes on the proposal cost, in credits. Turnout is measured in these
+		pub credits: Balance,
+	}
+
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ProposalClass {
+		General,
+		TreasurySpend,
+		RuntimeParameter,
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Threshold<Balance> {
+		// more ayes than nays
+		SimpleMajority,
+		// ayes make up at least the given share of the ayes and nays
+		SuperMajority(Perbill),
+		// more ayes than nays, and at least the given aye weight
+		AbsoluteMinimum(Balance),
+	}
+
+	impl<Balance: AtLeast32BitUnsigned + Copy> Threshold<Balance> {
+		pub fn approves(&self, ayes: Balance, nays: Balance) -> bool {
+			match self {
+				Threshold::SimpleMajority => ayes > nays,
+				Threshold::SuperMajority(share) =>
+					!ayes.is_zero() && ayes >= share.mul_ceil(ayes.saturating_add(nays)),
+				Threshold::AbsoluteMinimum(minimum) => ayes > nays && ayes >= *minimum,
+			}
+		}
+	}
+
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ProposalResult<Balance> {
+		pub ayes: Balance,
+		pub nays: Balance,
+		pub abstains: Balance,
+		pub credits: Balance,
+		pub threshold: Threshold<Balance>,
+		pub outcome: Outcome,
 	}
 
 	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -85,16 +373,21 @@ pub mod pallet {
 	}
 
 	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-	pub struct VotingRoundMetadata<AccountId, BlockNumber> {
+	pub struct VotingRoundMetadata<AccountId, BlockNumber, Balance> {
 		pub initializer: AccountId,
 		pub proposal_phase: VotingPhaseData<BlockNumber>,
 		pub previous_round_id: VotingRoundId,
 		pub pre_voting_phase: VotingPhaseData<BlockNumber>,
 		pub voting_phase: VotingPhaseData<BlockNumber>,
+		pub reveal_phase: Option<VotingPhaseData<BlockNumber>>,
 		pub post_voting_phase: VotingPhaseData<BlockNumber>,
 		pub enactment_phase: VotingPhaseData<BlockNumber>,
 		pub finalized_block: BlockNumber,
 		pub phase: VotingPhases,
+		// stored so that a runtime upgrade does not change the quorum of an ongoing round
+		pub quorum: Perbill,
+		pub mode: RoundMode<Balance>,
+		pub ballot: BallotMode,
 	}
 
 	#[derive(
@@ -107,13 +400,83 @@ pub mod pallet {
 		MaxVotes: Get<u32>,
 	{
 		pub initializer: AccountId,
+		pub class: ProposalClass,
 		pub ayes: BoundedVec<Balance, MaxVotes>,
 		pub nays: BoundedVec<Balance, MaxVotes>,
+		pub abstains: BoundedVec<Balance, MaxVotes>,
 		pub bucket_id: Option<BucketId>,
 	}
 
+	// what is kept of a round once it is reaped
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(MaxProposals))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen, Hash: MaxEncodedLen))]
+	pub struct RoundSummary<AccountId, BlockNumber, Balance, Hash, MaxProposals>
+	where
+		MaxProposals: Get<u32>,
+	{
+		pub mode: RoundMode<Balance>,
+		pub ballot: BallotMode,
+		// Finalized, or Cancelled
+		pub phase: VotingPhases,
+		pub finalized_block: BlockNumber,
+		// proposer, class and result of every proposal, in the order of the proposal ids
+		pub proposals: BoundedVec<(AccountId, ProposalClass, Option<ProposalResult<Balance>>), MaxProposals>,
+		// credits registered across the buckets of the round
+		pub credits: Balance,
+		pub votes: u32,
+		// root of the merkle tree of the votes, see `merkle::vote_leaves`
+		pub votes_root: Hash,
+	}
+
+	// a proposal of a bucket, as the runtime API reports it
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+	pub struct ProposalInfo<AccountId> {
+		pub proposal_id: ProposalCount,
+		pub proposer: AccountId,
+		pub class: ProposalClass,
+		// ayes, nays and abstentions cast so far
+		pub votes: u32,
+	}
+
+	// what an account has at stake in a round, as the runtime API reports it
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+	pub struct VoterStatus<Balance> {
+		// the buckets the account registered in, with its bond in each
+		pub registrations: Vec<(BucketId, VoterBond<Balance>)>,
+		// held for the account until it claims it back, see `claim_refund`
+		pub unclaimed_refund: Balance,
+	}
+
+	// everything the pallet holds on a round, as `export_round` reads it
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct RoundExport<AccountId, BlockNumber, Balance, Hash, MaxVotes>
+	where
+		MaxVotes: Get<u32>,
+	{
+		pub round: VotingRoundMetadata<AccountId, BlockNumber, Balance>,
+		// drawn when the proposals are shuffled into buckets
+		pub randomness: Option<Hash>,
+		// in the order of the proposal ids
+		pub proposals: Vec<Proposal<AccountId, Balance, MaxVotes>>,
+		// voter, bucket and bond of every registration
+		pub registrations: Vec<(AccountId, BucketId, VoterBond<Balance>)>,
+		pub bucket_credits: Vec<(BucketId, Balance)>,
+		// the accounts that voted on each proposal, in the order they voted. Votes are not linked to their voter
+		pub voters: Vec<(ProposalCount, Vec<AccountId>)>,
+		// contributor and amount of every contribution to each proposal of a funding round
+		pub contributions: Vec<(ProposalCount, Vec<(AccountId, Balance)>)>,
+		// the recorded result of every tallied proposal
+		pub outcomes: Vec<(ProposalCount, ProposalResult<Balance>)>,
+		pub unclaimed_refunds: Vec<(AccountId, Balance)>,
+	}
+
+	// the version of the pallet's storage, see the `migrations` module
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
@@ -122,7 +485,7 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		VotingRoundId,
-		VotingRoundMetadata<AccountIdFor<T>, BlockNumberFor<T>>,
+		VotingRoundMetadataOf<T>,
 		OptionQuery,
 	>;
 
@@ -149,11 +512,23 @@ pub mod pallet {
 			NMapKey<Blake2_128Concat, BucketId>,
 			NMapKey<Blake2_128Concat, T::AccountId>,
 		),
-		// total bond, remaining bond
-		(BalanceOf<T>, BalanceOf<T>),
+		VoterBond<BalanceOf<T>>,
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn bucket_credits)]
+	pub(super) type BucketCredits<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, BucketId>,
+		),
+		// sum of the credits registered by all voters of the bucket
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn voters_voted_on_proposal)]
 	pub(super) type VotersVotedOnProposal<T: Config> = StorageNMap<
@@ -167,6 +542,236 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn vote_commitments)]
+	pub(super) type VoteCommitments<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, ProposalCount>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+		),
+		VoteCommitment<T::Hash, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	// voters who committed a vote in a bucket, whether they revealed it or not. Unrevealed commitments are
+	// settled by `UnrevealedCommits`, so the rest of the bond is not forfeited
+	#[pallet::storage]
+	#[pallet::getter(fn committed_voters)]
+	pub(super) type CommittedVoters<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, BucketId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+		),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn coordinator)]
+	pub(super) type Coordinator<T: Config> = StorageValue<_, CoordinatorInfo<T::AccountId>, OptionQuery>;
+
+	// the coordinator of an encrypted round is fixed when the round starts
+	#[pallet::storage]
+	#[pallet::getter(fn round_coordinator)]
+	pub(super) type RoundCoordinator<T: Config> =
+		StorageMap<_, Blake2_128Concat, VotingRoundId, CoordinatorInfo<T::AccountId>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn voting_keys)]
+	pub(super) type VotingKeys<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, T::AccountId>),
+		[u8; 32],
+		OptionQuery,
+	>;
+
+	// hash chain over the sign ups of an encrypted round
+	#[pallet::storage]
+	#[pallet::getter(fn sign_up_root)]
+	pub(super) type SignUpRoot<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, T::Hash, ValueQuery>;
+
+	// hash chain over the messages of an encrypted round. The messages themselves are only in the events
+	#[pallet::storage]
+	#[pallet::getter(fn message_root)]
+	pub(super) type MessageRoot<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, T::Hash, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn message_count)]
+	pub(super) type MessageCount<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, u32, ValueQuery>;
+
+	// messages, or threshold ballots, each voter has published in a round
+	#[pallet::storage]
+	#[pallet::getter(fn voter_message_count)]
+	pub(super) type VoterMessageCount<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, T::AccountId>),
+		u32,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn coordinator_tally)]
+	pub(super) type CoordinatorTally<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		VotingRoundId,
+		BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn threshold_key)]
+	pub(super) type ThresholdKey<T: Config> =
+		StorageValue<_, ThresholdKeySet<T::AccountId, T::MaxCommitteeMembers>, OptionQuery>;
+
+	// the threshold key of a round is fixed when the round starts
+	#[pallet::storage]
+	#[pallet::getter(fn round_threshold_key)]
+	pub(super) type RoundThresholdKey<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		VotingRoundId,
+		ThresholdKeySet<T::AccountId, T::MaxCommitteeMembers>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn threshold_ballots)]
+	pub(super) type ThresholdBallots<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, u32>),
+		(T::AccountId, EncryptedMessage<T::MaxMessageLength>),
+		OptionQuery,
+	>;
+
+	// the share of a member for a ballot, by ballot index
+	#[pallet::storage]
+	#[pallet::getter(fn decryption_shares)]
+	pub(super) type DecryptionShares<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, u32>,
+		),
+		DecryptionShare,
+		OptionQuery,
+	>;
+
+	// how many shares a member has submitted so far, from the first ballot on
+	#[pallet::storage]
+	#[pallet::getter(fn shares_submitted)]
+	pub(super) type SharesSubmitted<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, T::AccountId>),
+		u32,
+		ValueQuery,
+	>;
+
+	// member index and account, in the order the shares were submitted
+	#[pallet::storage]
+	#[pallet::getter(fn share_submitters)]
+	pub(super) type ShareSubmitters<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		VotingRoundId,
+		BoundedVec<(u32, T::AccountId), T::MaxCommitteeMembers>,
+		ValueQuery,
+	>;
+
+	// next ballot to decrypt
+	#[pallet::storage]
+	#[pallet::getter(fn decryption_cursor)]
+	pub(super) type DecryptionCursor<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, u32, ValueQuery>;
+
+	// next proposal to tally
+	#[pallet::storage]
+	#[pallet::getter(fn tally_cursor)]
+	pub(super) type TallyCursor<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, ProposalCount, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn settlement)]
+	pub(super) type SettlementProgress<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, Settlement, OptionQuery>;
+
+	// the bonds an account holds in a round, until it claims them back
+	#[pallet::storage]
+	#[pallet::getter(fn unclaimed_refunds)]
+	pub(super) type UnclaimedRefunds<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, T::AccountId>),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn round_summary)]
+	pub(super) type RoundSummaries<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		VotingRoundId,
+		RoundSummary<T::AccountId, BlockNumberFor<T>, BalanceOf<T>, T::Hash, T::MaxProposals>,
+		OptionQuery,
+	>;
+
+	// the oldest round that `on_idle` has not reaped yet
+	#[pallet::storage]
+	#[pallet::getter(fn next_round_to_reap)]
+	pub(super) type NextRoundToReap<T: Config> = StorageValue<_, VotingRoundId, ValueQuery>;
+
+	// randomness the bucket committees of a round are drawn with, fixed when the proposals are bucketed
+	#[pallet::storage]
+	#[pallet::getter(fn round_randomness)]
+	pub(super) type RoundRandomness<T: Config> = StorageMap<_, Blake2_128Concat, VotingRoundId, T::Hash, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn committees)]
+	pub(super) type Committees<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, BucketId>),
+		BoundedVec<T::AccountId, T::MaxBucketCommitteeSize>,
+		ValueQuery,
+	>;
+
+	// the hash of the tally each committee member submitted for its bucket
+	#[pallet::storage]
+	#[pallet::getter(fn bucket_tally_submissions)]
+	pub(super) type BucketTallySubmissions<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, BucketId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+		),
+		T::Hash,
+		OptionQuery,
+	>;
+
+	// the tallies submitted for a bucket, by hash, until one of them is accepted
+	#[pallet::storage]
+	pub(super) type ProposedBucketTallies<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, BucketId>,
+			NMapKey<Identity, T::Hash>,
+		),
+		BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn bucket_tallies)]
+	pub(super) type BucketTallies<T: Config> = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, VotingRoundId>, NMapKey<Blake2_128Concat, BucketId>),
+		BucketTally<BlockNumberFor<T>, BalanceOf<T>, T::MaxProposals>,
+		OptionQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn voters_voted)]
 	pub(super) type VotersVoted<T: Config> = StorageNMap<
@@ -179,6 +784,19 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn contributions)]
+	pub(super) type Contributions<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, VotingRoundId>,
+			NMapKey<Blake2_128Concat, ProposalCount>,
+		),
+		// contributor, amount
+		BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxContributorsPerProposal>,
+		OptionQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn proposal_outcome)]
 	pub(super) type ProposalOutcome<T: Config> = StorageNMap<
@@ -187,7 +805,7 @@ pub mod pallet {
 			NMapKey<Blake2_128Concat, VotingRoundId>,
 			NMapKey<Blake2_128Concat, ProposalCount>,
 		),
-		(BalanceOf<T>, VoteDirection),
+		ProposalResult<BalanceOf<T>>,
 		OptionQuery,
 	>;
 
@@ -196,9 +814,104 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		// Emits the voting round id
-		PhaseTransition((VotingPhases, VotingRoundId)),
-		ProposalSubmitted(ProposalCount),
+		// a round was started, and entered its proposal phase
+		RoundStarted {
+			round: VotingRoundId,
+			initializer: T::AccountId,
+			mode: RoundMode<BalanceOf<T>>,
+			ballot: BallotMode,
+		},
+		PhaseTransition { round: VotingRoundId, phase: VotingPhases },
+		ProposalSubmitted { round: VotingRoundId, proposal: ProposalCount, proposer: T::AccountId, class: ProposalClass },
+		// the proposal submitted as `submitted` was shuffled into the bucket, under a new id, when the round entered
+		// its pre-voting phase
+		BucketAssigned { round: VotingRoundId, submitted: ProposalCount, proposal: ProposalCount, bucket: BucketId },
+		VoterRegistered { round: VotingRoundId, bucket: BucketId, who: T::AccountId, credits: BalanceOf<T> },
+		// the credits are locked until the commitment is revealed or settled
+		VoteCommitted { round: VotingRoundId, proposal: ProposalCount, who: T::AccountId, credits: BalanceOf<T> },
+		VoteCast {
+			round: VotingRoundId,
+			proposal: ProposalCount,
+			who: T::AccountId,
+			votes: BalanceOf<T>,
+			direction: VoteDirection,
+		},
+		// the result recorded for the proposal, which a later tally of the same proposal replaces
+		ProposalTallied {
+			round: VotingRoundId,
+			proposal: ProposalCount,
+			ayes: BalanceOf<T>,
+			nays: BalanceOf<T>,
+			abstains: BalanceOf<T>,
+			outcome: Outcome,
+		},
+		MatchingPayout { round: VotingRoundId, proposal: ProposalCount, amount: BalanceOf<T> },
+		// the credits of the commitment went back to the voter's bond
+		UnrevealedCommitReleased { round: VotingRoundId, proposal: ProposalCount, who: T::AccountId, credits: BalanceOf<T> },
+		UnrevealedCommitSlashed { round: VotingRoundId, proposal: ProposalCount, who: T::AccountId, amount: BalanceOf<T> },
+		CoordinatorSet { coordinator: T::AccountId },
+		SignedUp { round: VotingRoundId, who: T::AccountId, voting_key: [u8; 32] },
+		MessagePublished { round: VotingRoundId, index: u32, message: EncryptedMessage<T::MaxMessageLength> },
+		TallyVerified { round: VotingRoundId },
+		ThresholdKeySet { public_key: [u8; 32], threshold: u32 },
+		ThresholdBallotCast { round: VotingRoundId, who: T::AccountId, index: u32 },
+		DecryptionSharesSubmitted { round: VotingRoundId, who: T::AccountId },
+		// the ballot could not be decrypted, or was not a valid vote
+		BallotRejected { round: VotingRoundId, index: u32 },
+		RoundCancelled { round: VotingRoundId },
+		CommitteeMemberJoined { round: VotingRoundId, bucket: BucketId, who: T::AccountId },
+		BucketTallySubmitted { round: VotingRoundId, bucket: BucketId, who: T::AccountId },
+		// a majority of the committee agreed on the tally
+		BucketTallyAccepted { round: VotingRoundId, bucket: BucketId },
+		// the recount disagreed with the tally
+		BucketTallyOverturned { round: VotingRoundId, bucket: BucketId, challenger: T::AccountId },
+		// the recount agreed with the tally, and the challenger's bond was slashed
+		ChallengeRejected { round: VotingRoundId, bucket: BucketId, challenger: T::AccountId },
+		// the amount was paid to the challenger
+		CommitteeMemberSlashed { round: VotingRoundId, bucket: BucketId, who: T::AccountId, amount: BalanceOf<T> },
+		// a bond held by the round was returned, less what was forfeited for not voting
+		BondRefunded { round: VotingRoundId, who: T::AccountId, amount: BalanceOf<T>, forfeited: BalanceOf<T> },
+		// a share of the refund was paid to whoever released it
+		RefundTipPaid { round: VotingRoundId, who: T::AccountId, to: T::AccountId, amount: BalanceOf<T> },
+		// only the summary of the round is left
+		RoundReaped { round: VotingRoundId },
+	}
+
+	impl<T: Config> Event<T> {
+		// the round of the event, unless it is about the pallet's settings
+		pub fn round(&self) -> Option<VotingRoundId> {
+			match self {
+				Event::RoundStarted { round, .. } |
+				Event::PhaseTransition { round, .. } |
+				Event::ProposalSubmitted { round, .. } |
+				Event::BucketAssigned { round, .. } |
+				Event::VoterRegistered { round, .. } |
+				Event::VoteCommitted { round, .. } |
+				Event::VoteCast { round, .. } |
+				Event::ProposalTallied { round, .. } |
+				Event::MatchingPayout { round, .. } |
+				Event::UnrevealedCommitReleased { round, .. } |
+				Event::UnrevealedCommitSlashed { round, .. } |
+				Event::SignedUp { round, .. } |
+				Event::MessagePublished { round, .. } |
+				Event::TallyVerified { round } |
+				Event::ThresholdBallotCast { round, .. } |
+				Event::DecryptionSharesSubmitted { round, .. } |
+				Event::BallotRejected { round, .. } |
+				Event::RoundCancelled { round } |
+				Event::CommitteeMemberJoined { round, .. } |
+				Event::BucketTallySubmitted { round, .. } |
+				Event::BucketTallyAccepted { round, .. } |
+				Event::BucketTallyOverturned { round, .. } |
+				Event::ChallengeRejected { round, .. } |
+				Event::CommitteeMemberSlashed { round, .. } |
+				Event::BondRefunded { round, .. } |
+				Event::RefundTipPaid { round, .. } |
+				Event::RoundReaped { round } => Some(*round),
+				// CoordinatorSet and ThresholdKeySet
+				_ => None,
+			}
+		}
 	}
 
 	// Errors inform users that something went wrong.
@@ -234,37 +947,193 @@ pub mod pallet {
 		CouldNotComputeSqrt,
 		// voter has voted on the given proposal
 		VoterHasVotedForThisProposal,
+		// funding rounds only accept contributions
+		OnlyAyeInFundingRound,
+		// proposal has reached the maximum number of contributors
+		TooManyContributors,
+		// votes of commit-reveal rounds must be committed
+		MustCommitVote,
+		// only commit-reveal rounds accept commitments
+		NotACommitRevealRound,
+		// only allowed in reveal phase
+		CanCallOnlyDuringRevealPhase,
+		// no commitment to reveal
+		CommitmentNotFound,
+		// revealed vote does not match the commitment
+		InvalidReveal,
+		// votes of encrypted rounds must be published as messages
+		MustPublishMessage,
+		// only encrypted rounds accept sign ups, messages and tallies
+		NotAnEncryptedRound,
+		// funding rounds pay contributions as they are cast, so their votes cannot be encrypted
+		UnsupportedBallotMode,
+		// encrypted rounds need a coordinator
+		NoCoordinator,
+		// encrypted rounds need a tally verifier with a verifying key
+		NoTallyVerifier,
+		NotTheCoordinator,
+		AlreadySignedUp,
+		NotSignedUp,
+		TooManyMessages,
+		// the voter has published `MaxMessagesPerVoter` messages or ballots
+		TooManyMessagesFromVoter,
+		// the tally must have an entry for every proposal
+		TallyLengthMismatch,
+		TallyAlreadySubmitted,
+		InvalidTallyProof,
+		// votes of threshold-encrypted rounds must be cast as ballots
+		MustCastThresholdBallot,
+		NotAThresholdRound,
+		// threshold-encrypted rounds need a threshold key
+		NoThresholdKey,
+		// the threshold must be between 1 and the number of members
+		InvalidThreshold,
+		// only voters registered in a bucket can cast ballots
+		NotRegistered,
+		NotACommitteeMember,
+		SharesAlreadySubmitted,
+		// there must be a share for every ballot, and none past the last one
+		SharesLengthMismatch,
+		// a batch of shares must start at the first ballot the member has no share for
+		SharesOutOfOrder,
+		InvalidDecryptionShare,
+		BallotNotFound,
+		// the account has no sr25519 key to check the VRF output against
+		NoVrfKey,
+		InvalidVrfProof,
+		// the VRF output is above the committee threshold
+		NotEligibleForCommittee,
+		AlreadyInCommittee,
+		CommitteeFull,
+		// only allowed in post voting phase
+		CanCallOnlyDuringPostVotingPhase,
+		NotInCommittee,
+		// the tally must have a result for every proposal of the bucket
+		BucketTallyLengthMismatch,
+		BucketTallyAlreadySubmitted,
+		BucketTallyAlreadyAccepted,
+		BucketTallyNotAccepted,
+		// a tally is only accepted while it can still be challenged for the whole challenge period, before
+		// the post voting phase ends
+		TooLateForBucketTally,
+		ChallengePeriodOver,
+		// the tally has already been recounted
+		AlreadyChallenged,
+		// bonds can only be claimed once the round is past the post voting phase
+		CanCallOnlyAfterPostVotingPhase,
+		// the bonds of others can only be released once the round is over
+		CanCallOnlyAfterEnactmentPhase,
+		// the account holds no bonds in the round, or already claimed them
+		NothingToClaim,
+		// the round is not over, or still within its retention period
+		RoundNotReapable,
+	}
+
+	// a round started at genesis by its initializer, with the proposals submitted to it in order. If `open_voting` is
+	// set, the proposals are put into buckets in that order, the registrations are made, and the round starts in its
+	// voting phase
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+	pub struct GenesisRound<AccountId, Balance> {
+		pub initializer: AccountId,
+		pub mode: RoundMode<Balance>,
+		// only open and commit-reveal ballots, which need no keys
+		pub ballot: BallotMode,
+		// proposer, class
+		pub proposals: Vec<(AccountId, ProposalClass)>,
+		// voter, bucket, credits
+		pub registrations: Vec<(AccountId, BucketId, Balance)>,
+		pub open_voting: bool,
 	}
 
-	#[derive(Default)]
 	#[pallet::genesis_config]
-	pub struct GenesisConfig {
+	pub struct GenesisConfig<T: Config> {
 		pub voting_round_id: VotingRoundId,
+		// endowed with the given amount, to bond and vote with
+		pub voters: Vec<(T::AccountId, BalanceOf<T>)>,
+		// judges the identities below as `Reasonable`, if set
+		pub registrar: Option<T::AccountId>,
+		// account, display name. The deposit of the identity is reserved from the account
+		pub identities: Vec<(T::AccountId, Vec<u8>)>,
+		pub initial_round: Option<GenesisRound<T::AccountId, BalanceOf<T>>>,
 	}
 
-	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig {
-		fn build(&self) {
-			let voting_round_id = &self.voting_round_id;
-			<LatestVotingRound<T>>::put(*voting_round_id);
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				voting_round_id: 0,
+				voters: Vec::new(),
+				registrar: None,
+				identities: Vec::new(),
+				initial_round: None,
+			}
 		}
 	}
 
-	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
-			let mut weight: Weight = 0;
-			let voting_round_id = match LatestVotingRound::<T>::get() {
-				Some(id) => id,
-				// this will happen only when the pallet is initialized for the first time
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			<LatestVotingRound<T>>::put(self.voting_round_id);
+
+			// the pallet account is kept alive, so that forfeited bonds can be paid to it
+			let _ = T::Token::make_free_balance_be(&Pallet::<T>::account_id(), T::Token::minimum_balance());
+
+			for (who, amount) in &self.voters {
+				T::Token::deposit_creating(who, *amount);
+			}
+
+			if let Some(registrar) = &self.registrar {
+				pallet_identity::Pallet::<T>::add_registrar(RawOrigin::Root.into(), registrar.clone())
+					.expect("the registrar is added by root");
+			}
+			let registrar_index = pallet_identity::Pallet::<T>::registrars().len() as u32;
+			for (who, display) in &self.identities {
+				let info = IdentityInfo {
+					legal: Data::None,
+					display: Data::Raw(display.clone().try_into().expect("display names are at most 32 bytes")),
+					email: Data::None,
+					image: Data::None,
+					twitter: Data::None,
+					riot: Data::None,
+					web: Data::None,
+					additional: Default::default(),
+					pgp_fingerprint: None,
+				};
+				pallet_identity::Pallet::<T>::set_identity(RawOrigin::Signed(who.clone()).into(), Box::new(info))
+					.expect("accounts with an identity can pay its deposit");
+				if let Some(registrar) = &self.registrar {
+					pallet_identity::Pallet::<T>::provide_judgement(
+						RawOrigin::Signed(registrar.clone()).into(),
+						registrar_index - 1,
+						T::Lookup::unlookup(who.clone()),
+						Judgement::Reasonable,
+					)
+					.expect("the registrar judges identities that were just set");
+				}
+			}
+
+			if let Some(round) = &self.initial_round {
+				Pallet::<T>::start_genesis_round(round).expect("the initial round can be started");
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+			// every transition below is benchmarked from the start of the hook, so it replaces this weight
+			let mut weight = T::WeightInfo::on_initialize_idle();
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				// this will happen only when the pallet is initialized for the first time
 				None => 0,
 			};
-			weight += 1;
 			if voting_round_id == 0 {
 				return weight
 			}
 
-			weight += 1;
 			let mut voting_round =
 				VotingRounds::<T>::get(voting_round_id).expect("Past voting round must exist");
 
@@ -274,13 +1143,13 @@ pub mod pallet {
 					if block_number == voting_round.proposal_phase.end_block {
 						// group proposals into buckets of k size + transition state
 						// group proposals
-						weight += 1;
 						let random = T::Randomness::random(&block_number.encode());
+						RoundRandomness::<T>::insert(voting_round_id, random.0);
 
 						// shuffle with random. Not sure if its possible to shuffle in place, so fetching all and shuffling by hand
 						// usage of sort_by was explored
-						weight += T::MaxProposals::get() as u64;
 						let proposals = ProposalsForVotingRound::<T>::get(voting_round_id);
+						let proposal_count = proposals.as_ref().map_or(0, |proposals| proposals.len() as u32);
 
 						// we let the state change regardless of proposals being empty
 						if proposals.is_some() {
@@ -291,124 +1160,178 @@ pub mod pallet {
 							}
 							let mut rng = ChaChaRng::from_seed(z); // Vec<u8> => [u8; 32]
 							let mut unbounded = Vec::with_capacity(T::MaxProposals::get() as usize);
-							for ele in proposals.expect("qed") {
-								unbounded.push(ele);
+							// the submission order is kept along, for indexers to follow the proposals to their new ids
+							for (submitted, ele) in proposals.expect("qed").into_iter().enumerate() {
+								unbounded.push((submitted as ProposalCount, ele));
 							}
 							unbounded.shuffle(&mut rng);
-							for i in 0..unbounded.len() {
+							let mut randomized = Vec::with_capacity(unbounded.len());
+							for (i, (submitted, proposal)) in unbounded.into_iter().enumerate() {
 								let bucket_id = T::BucketSize::get() % ((i as BucketId) + 1);
-								let who = unbounded[i].initializer.clone();
-								unbounded[i] = Proposal::<T::AccountId, BalanceOf<T>, T::MaxVotes> {
-									initializer: who,
+								randomized.push(Proposal::<T::AccountId, BalanceOf<T>, T::MaxVotes> {
+									initializer: proposal.initializer,
+									class: proposal.class,
 									ayes: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
 									nays: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
+									abstains: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
 									bucket_id: Some(bucket_id as BucketId),
-								};
+								});
+								Self::deposit_event(Event::BucketAssigned {
+									round: voting_round_id,
+									submitted,
+									proposal: i as ProposalCount,
+									bucket: bucket_id as BucketId,
+								});
 							}
 							let randomized = BoundedVec::<
 								Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>,
 								T::MaxProposals,
-							>::truncate_from(unbounded);
+							>::truncate_from(randomized);
 							ProposalsForVotingRound::<T>::set(voting_round_id, Some(randomized));
 						}
 
 						// transition state
-						weight += 1;
+						weight = T::WeightInfo::start_pre_voting(proposal_count);
 						voting_round.phase = VotingPhases::PreVoting;
 						VotingRounds::<T>::set(voting_round_id, Some(voting_round));
-						Self::deposit_event(Event::PhaseTransition((VotingPhases::PreVoting, voting_round_id)));
+						Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::PreVoting });
 
 					}
 				},
 				VotingPhases::PreVoting => {
 					if block_number == voting_round.pre_voting_phase.end_block {
 						// transition state
-						weight += 1;
+						weight = T::WeightInfo::start_voting();
 						voting_round.phase = VotingPhases::Voting;
 						VotingRounds::<T>::set(voting_round_id, Some(voting_round));
-						Self::deposit_event(Event::PhaseTransition((VotingPhases::Voting, voting_round_id)));
+						Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Voting });
 
 					}
 				},
 				VotingPhases::Voting => {
 					if block_number == voting_round.voting_phase.end_block {
-						// tally votes + transition state
-						weight += T::MaxProposals::get() as u64;
-						let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).expect("qed");
-
-						for i in 0..proposals.len() {
-							let mut ayes: BalanceOf<T> = 0u32.into();
-
-							// this should be replaced with an .iter().sum() :/
-							for aye in &proposals[i].ayes {
-								ayes += *aye;
-							}
-							let mut nays:  BalanceOf<T> = 0u32.into();
-
-							for nay in &proposals[i].nays {
-								nays += *nay;
-							}
-
-							let key = (voting_round_id, i as ProposalCount);
-							if ayes > nays {
-								ProposalOutcome::<T>::set(key, Some((ayes, VoteDirection::Aye)));
-							} else {
-								ProposalOutcome::<T>::set(key, Some((nays, VoteDirection::Nay)));
+						match voting_round.ballot {
+							// votes are only known once they are revealed, or tallied by the coordinator
+							BallotMode::CommitReveal | BallotMode::Encrypted | BallotMode::ThresholdEncrypted => {
+								// transition state
+								weight = T::WeightInfo::start_reveal();
+								voting_round.phase = VotingPhases::Reveal;
+								VotingRounds::<T>::set(voting_round_id, Some(voting_round));
+								Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Reveal });
+							},
+							BallotMode::Open => {
+								// transition state, and tally the first batch straight away
+								voting_round.phase = VotingPhases::Tallying;
+								Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Tallying });
+								weight = Self::tally_step(voting_round_id, &mut voting_round);
+								VotingRounds::<T>::set(voting_round_id, Some(voting_round));
+							},
+						}
+					}
+				},
+				VotingPhases::Reveal => {
+					let reveal_end = voting_round.reveal_phase.as_ref().map(|phase| phase.end_block);
+					if voting_round.ballot == BallotMode::ThresholdEncrypted {
+						let threshold = RoundThresholdKey::<T>::get(voting_round_id).map(|key| key.threshold).unwrap_or_default();
+						let submitters = ShareSubmitters::<T>::get(voting_round_id);
+						if threshold > 0 && submitters.len() as u32 >= threshold {
+							// decrypt a batch of ballots, and tally once they are all open
+							let (ballots, done) =
+								Self::decrypt_ballots(voting_round_id, &voting_round, &submitters[..threshold as usize]);
+							weight = T::WeightInfo::decrypt_ballots(ballots, threshold);
+							if done {
+								// transition state
+								voting_round.phase = VotingPhases::Tallying;
+								Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Tallying });
+								weight = weight.saturating_add(Self::tally_step(voting_round_id, &mut voting_round));
+								VotingRounds::<T>::set(voting_round_id, Some(voting_round));
 							}
+						} else if reveal_end.map_or(false, |end| block_number >= end) {
+							// the shares never arrived. Everyone but the committees claims their bonds back
+							let refunds = Self::refund_committees(voting_round_id);
+							weight = T::WeightInfo::cancel_round(refunds);
+
+							// transition state
+							voting_round.phase = VotingPhases::Cancelled;
+							VotingRounds::<T>::set(voting_round_id, Some(voting_round));
+							Self::deposit_event(Event::RoundCancelled { round: voting_round_id });
 						}
-
-						// transition state
-						weight += 1;
-						voting_round.phase = VotingPhases::PostVoting;
+					} else if reveal_end == Some(block_number) {
+						// only revealed votes, or the verified tally of the coordinator, are counted
+						voting_round.phase = VotingPhases::Tallying;
+						Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Tallying });
+						weight = Self::tally_step(voting_round_id, &mut voting_round);
 						VotingRounds::<T>::set(voting_round_id, Some(voting_round));
-						Self::deposit_event(Event::PhaseTransition((VotingPhases::PostVoting, voting_round_id)));
 					}
 				},
+				VotingPhases::Tallying => {
+					weight = Self::tally_step(voting_round_id, &mut voting_round);
+					VotingRounds::<T>::set(voting_round_id, Some(voting_round));
+				},
 				VotingPhases::PostVoting => {
-					if block_number == voting_round.post_voting_phase.end_block {
-						// return voting round bond, proposal bound, and vote bond + transition state
-						weight += 1;
-						T::Token::unreserve(&voting_round.initializer, T::BondForVotingRound::get());
-						weight += T::MaxProposals::get() as u64;
-						let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).expect("qed");
-						for i in 0..proposals.len() {
-							T::Token::unreserve(&proposals[i].initializer, T::BondForProposal::get());
-							weight += T::MaxVotes::get() as u64;
-							let voters = match VotersVoted::<T>::get((voting_round_id, i as ProposalCount)) {
-								Some(voters) => voters,
-								None => BoundedVec::<AccountIdFor<T>, T::MaxVotes>::default(),
-							};
-
-							let bucket_id = &proposals[i].bucket_id.expect("qed");
-
-							for voter in voters {
-								// get their bond
-								let bond = VotersForBucket::<T>::get((voting_round_id, bucket_id, voter.clone())).expect("qed");
-								// we only return the bond of the voters who voted. In the event that the voter has votes left (upon voting), those are returned as well because
-								// it is possible that the voter did not intend to behave maliciously
-								T::Token::unreserve(&voter, bond.0);
-							}
-						}
-						// transition state
-						weight += 1;
-						voting_round.phase = VotingPhases::Enactment;
+					// decryption and tallying may run past the end of the Reveal phase, and with them, past the end of this one
+					if block_number >= voting_round.post_voting_phase.end_block {
+						// transition state, and settle what fits in this block straight away
+						voting_round.phase = VotingPhases::Settling;
+						Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Settling });
+						weight = Self::settle_step(voting_round_id, &mut voting_round);
 						VotingRounds::<T>::set(voting_round_id, Some(voting_round));
-						Self::deposit_event(Event::PhaseTransition((VotingPhases::Enactment, voting_round_id)));
 					}
 				},
+				VotingPhases::Settling => {
+					weight = Self::settle_step(voting_round_id, &mut voting_round);
+					VotingRounds::<T>::set(voting_round_id, Some(voting_round));
+				},
 				VotingPhases::Enactment => {
-					if block_number == voting_round.enactment_phase.end_block {
+					// settling may run past the end of this phase
+					if block_number >= voting_round.enactment_phase.end_block {
 						// transition state
-						weight += 1;
+						weight = T::WeightInfo::finalize();
 						voting_round.phase = VotingPhases::Finalized;
 						VotingRounds::<T>::set(voting_round_id, Some(voting_round));
-						Self::deposit_event(Event::PhaseTransition((VotingPhases::Finalized, voting_round_id)));
+						Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Finalized });
 					}
 				},
-				VotingPhases::Finalized => (),
+				VotingPhases::Finalized | VotingPhases::Cancelled => (),
 			};
 			weight
 		}
+
+		// reaps the oldest round that is past its retention period, one step per block
+		fn on_idle(block_number: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let latest_voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => 0,
+			};
+			let voting_round_id = NextRoundToReap::<T>::get().max(1);
+			if voting_round_id >= latest_voting_round_id || remaining_weight < Self::reap_step_weight() {
+				return T::DbWeight::get().reads(2)
+			}
+
+			match VotingRounds::<T>::get(voting_round_id) {
+				Some(voting_round) => {
+					if !Self::is_reapable(voting_round_id, &voting_round, block_number, latest_voting_round_id) {
+						return T::DbWeight::get().reads(3)
+					}
+					let (weight, done) = Self::reap_step(voting_round_id, &voting_round);
+					if done {
+						NextRoundToReap::<T>::put(voting_round_id + 1);
+					}
+					weight
+				},
+				// already reaped with `reap_round`
+				None => {
+					NextRoundToReap::<T>::put(voting_round_id + 1);
+					T::DbWeight::get().reads_writes(3, 1)
+				},
+			}
+		}
+
+		// the migrations of the runtime ran before this, so it checks the storage they leave
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
@@ -416,8 +1339,12 @@ pub mod pallet {
 		// The following function starts a new proposal round, provided the origin
 		// belongs to the technical committee,
 		// and the previous voting round has "finalized"
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-		pub fn start_voting_round(origin: OriginFor<T>) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::start_voting_round())]
+		pub fn start_voting_round(
+			origin: OriginFor<T>,
+			mode: RoundMode<BalanceOf<T>>,
+			ballot: BallotMode,
+		) -> DispatchResult {
 			// check if the user is a member of the technical committee
 			T::ManagerOrigin::ensure_origin(origin.clone())?;
 			let who = ensure_signed(origin)?;
@@ -436,11 +1363,32 @@ pub mod pallet {
 				};
 
 				// check if phase is finalized
-				if past_voting_round.phase != VotingPhases::Finalized {
+				if past_voting_round.phase != VotingPhases::Finalized && past_voting_round.phase != VotingPhases::Cancelled {
 					Err(Error::<T>::ProposalPhaseCannotStart)?
 				}
 			}
 
+			let coordinator = match (&mode, &ballot) {
+				(RoundMode::Funding { .. }, BallotMode::Encrypted | BallotMode::ThresholdEncrypted) =>
+					Err(Error::<T>::UnsupportedBallotMode)?,
+				// without a verifying key, the tally of the round could never be accepted
+				(RoundMode::Governance, BallotMode::Encrypted) if !T::TallyVerifier::can_verify() =>
+					Err(Error::<T>::NoTallyVerifier)?,
+				(RoundMode::Governance, BallotMode::Encrypted) => match Coordinator::<T>::get() {
+					Some(coordinator) => Some(coordinator),
+					None => Err(Error::<T>::NoCoordinator)?,
+				},
+				_ => None,
+			};
+
+			let threshold_key = match ballot {
+				BallotMode::ThresholdEncrypted => match ThresholdKey::<T>::get() {
+					Some(key) => Some(key),
+					None => Err(Error::<T>::NoThresholdKey)?,
+				},
+				_ => None,
+			};
+
 			// bond some tokens to the voting round
 			let bond = T::BondForVotingRound::get();
 
@@ -451,18 +1399,41 @@ pub mod pallet {
 			let next_voting_round_id =
 				latest_voting_round_id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 			let next_voting_round_metadata =
-				make_voting_round_metadata::<T>(who, current_block, latest_voting_round_id)?;
+				make_voting_round_metadata::<T>(who.clone(), current_block, latest_voting_round_id, mode.clone(), ballot)?;
+
+			if let RoundMode::Funding { matching_pool } = mode {
+				T::Token::transfer(
+					&who,
+					&Self::matching_pool_account(next_voting_round_id),
+					matching_pool,
+					ExistenceRequirement::KeepAlive,
+				)?;
+			}
+
+			if let Some(coordinator) = coordinator {
+				RoundCoordinator::<T>::insert(next_voting_round_id, coordinator);
+			}
+			if let Some(key) = threshold_key {
+				RoundThresholdKey::<T>::insert(next_voting_round_id, key);
+			}
 
 			VotingRounds::<T>::insert(next_voting_round_id, next_voting_round_metadata.clone());
 			LatestVotingRound::<T>::put(next_voting_round_id);
+			Self::hold_refund(next_voting_round_id, &who, bond);
 
-			Self::deposit_event(Event::PhaseTransition((VotingPhases::Proposal, next_voting_round_id)));
+			Self::deposit_event(Event::RoundStarted {
+				round: next_voting_round_id,
+				initializer: who,
+				mode,
+				ballot: next_voting_round_metadata.ballot,
+			});
+			Self::deposit_event(Event::PhaseTransition { round: next_voting_round_id, phase: VotingPhases::Proposal });
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-		pub fn submit_proposal(origin: OriginFor<T>) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::submit_proposal(T::MaxProposals::get()))]
+		pub fn submit_proposal(origin: OriginFor<T>, class: ProposalClass) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			// ensure those who create proposals are backed by identities
@@ -487,8 +1458,10 @@ pub mod pallet {
 					let proposals = ProposalsForVotingRound::<T>::get(voting_round_id);
 					let new_proposal = Proposal::<T::AccountId, BalanceOf<T>, T::MaxVotes> {
 						initializer: who.clone(),
+						class,
 						ayes: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
 						nays: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
+						abstains: BoundedVec::<BalanceOf<T>, T::MaxVotes>::default(),
 						bucket_id: None,
 					};
 
@@ -505,27 +1478,42 @@ pub mod pallet {
 							.try_insert(0 as usize, new_proposal)
 							.map_err(|_| Error::<T>::StorageOverflow)?;
 						ProposalsForVotingRound::<T>::set(voting_round_id, Some(new_proposal_list));
-						Self::deposit_event(Event::ProposalSubmitted(0));
+						Self::deposit_event(Event::ProposalSubmitted {
+							round: voting_round_id,
+							proposal: 0,
+							proposer: who.clone(),
+							class,
+						});
 					} else {
 						ProposalsForVotingRound::<T>::try_append(voting_round_id, new_proposal)
 							.map_err(|_| Error::<T>::StorageOverflow)?;
-						Self::deposit_event(Event::ProposalSubmitted(proposals.unwrap().len() as ProposalCount));
+						Self::deposit_event(Event::ProposalSubmitted {
+							round: voting_round_id,
+							proposal: proposals.unwrap().len() as ProposalCount,
+							proposer: who.clone(),
+							class,
+						});
 					}
 				},
 				VotingPhases::PreVoting |
 				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
 				VotingPhases::PostVoting |
+				VotingPhases::Settling |
 				VotingPhases::Enactment |
-				VotingPhases::Finalized => Err(Error::<T>::CanCallOnlyDuringProposalPhase)?,
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringProposalPhase)?,
 			};
 
 			// bond according to proposal cost
 			T::Token::reserve(&who, T::BondForProposal::get())?;
+			Self::hold_refund(voting_round_id, &who, T::BondForProposal::get());
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::register_to_vote())]
 		pub fn register_to_vote(origin: OriginFor<T>, bucket_id: BucketId, votes: BalanceOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -555,21 +1543,35 @@ pub mod pallet {
 						Some(proposals) => proposals,
 						None => Err(Error::<T>::NoProposals)?,
 					};
-					VotersForBucket::<T>::insert((voting_round_id, bucket_id, &who), (votes, votes));
+					// re-registering replaces the previous registration
+					let previous = match VotersForBucket::<T>::get((voting_round_id, bucket_id, &who)) {
+						Some(bond) => bond.total,
+						None => 0u32.into(),
+					};
+					BucketCredits::<T>::mutate((voting_round_id, bucket_id), |credits| {
+						*credits = credits.saturating_sub(previous).saturating_add(votes)
+					});
+					VotersForBucket::<T>::insert((voting_round_id, bucket_id, &who), VoterBond { total: votes, remaining: votes });
 				},
 				VotingPhases::Proposal |
 				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
 				VotingPhases::PostVoting |
+				VotingPhases::Settling |
 				VotingPhases::Enactment |
-				VotingPhases::Finalized => Err(Error::<T>::CanCallOnlyDuringPreVotingPhase)?,
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringPreVotingPhase)?,
 			};
 
 			T::Token::reserve(&who, votes)?;
+			Self::hold_refund(voting_round_id, &who, votes);
+			Self::deposit_event(Event::VoterRegistered { round: voting_round_id, bucket: bucket_id, who, credits: votes });
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::vote(T::MaxProposals::get(), T::MaxVotes::get()))]
 		pub fn vote(origin: OriginFor<T>, proposal_id: ProposalCount, vote: BalanceOf<T>, direction: VoteDirection) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
@@ -585,123 +1587,1943 @@ pub mod pallet {
 
 			match voting_round.phase {
 				VotingPhases::Voting => {
-					let mut proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
+					match voting_round.ballot {
+						BallotMode::Open => {},
+						BallotMode::CommitReveal => Err(Error::<T>::MustCommitVote)?,
+						BallotMode::Encrypted => Err(Error::<T>::MustPublishMessage)?,
+						BallotMode::ThresholdEncrypted => Err(Error::<T>::MustCastThresholdBallot)?,
+					}
+					Self::do_vote(&who, voting_round_id, &voting_round, proposal_id, vote, direction)?;
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// commits to a vote without disclosing it. `credits` are held until the vote is revealed,
+		// and must cover the vote
+		#[pallet::weight(T::WeightInfo::commit_vote(T::MaxProposals::get()))]
+		pub fn commit_vote(origin: OriginFor<T>, proposal_id: ProposalCount, commitment: T::Hash, credits: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			if voting_round.ballot != BallotMode::CommitReveal {
+				Err(Error::<T>::NotACommitRevealRound)?
+			}
+
+			match voting_round.phase {
+				VotingPhases::Voting => {
+					let proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
 						Some(proposals) => proposals,
 						None => Err(Error::<T>::NoProposals)?,
 					};
 
-					let proposal = match proposals.get_mut(proposal_id as usize) {
-						Some(proposal) => proposal,
+					let attached_bucket_id = match proposals.get(proposal_id as usize) {
+						Some(proposal) => proposal.bucket_id.expect("qed"),
 						None => Err(Error::<T>::ProposalNotFound)?,
 					};
 
-					// check if voter has voted for this proposal already
-					match VotersVotedOnProposal::<T>::get((voting_round_id, proposal_id, &who)) {
-						Some(_) => Err(Error::<T>::VoterHasVotedForThisProposal)?,
-						None => {},
+					if VoteCommitments::<T>::contains_key((voting_round_id, proposal_id, &who)) {
+						Err(Error::<T>::VoterHasVotedForThisProposal)?
 					}
 
-					let attached_bucket_id = proposal.bucket_id.expect("qed");
-
-					let mut bonded_tokens = match VotersForBucket::<T>::get((voting_round_id, attached_bucket_id, &who)) {
+					let bonded_tokens = match VotersForBucket::<T>::get((voting_round_id, attached_bucket_id, &who)) {
 						Some(tokens) => tokens,
 						None => Err(Error::<T>::NoTokensBonded)?,
 					};
 
-					// check if vote is greater than the remaining bond
-					if vote > bonded_tokens.1 {
+					if credits > bonded_tokens.remaining {
 						Err(Error::<T>::CannotVoteMoreThanBond)?
 					}
 
-					// we accept the vote now
-					let vote = get_vote_from_bond::<T>(vote)?;
-					let _ = match direction {
-						VoteDirection::Aye => {
-							proposal.ayes.try_push(vote).map_err(|_| Error::<T>::StorageOverflow)?;
-							bonded_tokens = (bonded_tokens.0, bonded_tokens.1 - vote);
-						},
-						VoteDirection::Nay => {
-							proposal.nays.try_push(vote).map_err(|_| Error::<T>::StorageOverflow)?;
-							bonded_tokens = (bonded_tokens.0, bonded_tokens.1 - vote);
-						}
+					VoteCommitments::<T>::insert((voting_round_id, proposal_id, &who), VoteCommitment { commitment, credits });
+					CommittedVoters::<T>::insert((voting_round_id, attached_bucket_id, &who), ());
+					VotersForBucket::<T>::insert(
+						(voting_round_id, attached_bucket_id, &who),
+						VoterBond { total: bonded_tokens.total, remaining: bonded_tokens.remaining - credits },
+					);
+					Self::deposit_event(Event::VoteCommitted {
+						round: voting_round_id,
+						proposal: proposal_id,
+						who: who.clone(),
+						credits,
+					});
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::reveal_vote(T::MaxProposals::get(), T::MaxVotes::get()))]
+		pub fn reveal_vote(
+			origin: OriginFor<T>,
+			proposal_id: ProposalCount,
+			vote: BalanceOf<T>,
+			direction: VoteDirection,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::Reveal => {
+					let commitment = match VoteCommitments::<T>::get((voting_round_id, proposal_id, &who)) {
+						Some(commitment) => commitment,
+						None => Err(Error::<T>::CommitmentNotFound)?,
 					};
 
+					if Self::vote_commitment(&who, proposal_id, vote, &direction, &salt) != commitment.commitment {
+						Err(Error::<T>::InvalidReveal)?
+					}
+
+					if vote > commitment.credits {
+						Err(Error::<T>::CannotVoteMoreThanBond)?
+					}
 
-					let mut past_voters = match VotersVoted::<T>::get((voting_round_id, proposal_id)) {
-						Some(past) => past,
-						None => BoundedVec::<AccountIdFor<T>, T::MaxVotes>::default(),
+					// release the held credits, and spend them like a regular vote
+					let proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
+						Some(proposals) => proposals,
+						None => Err(Error::<T>::NoProposals)?,
 					};
-					past_voters.try_push(who.clone()).map_err(|_| Error::<T>::StorageOverflow)?;
-					VotersVoted::<T>::set((voting_round_id, proposal_id), Some(past_voters));
-					proposals[proposal_id as usize] = Proposal::<T::AccountId, BalanceOf<T>, T::MaxVotes> {
-						initializer: proposal.initializer.clone(),
-						ayes: proposal.ayes.clone(),
-						nays: proposal.nays.clone(),
-						bucket_id: proposal.bucket_id,
+					let attached_bucket_id = match proposals.get(proposal_id as usize) {
+						Some(proposal) => proposal.bucket_id.expect("qed"),
+						None => Err(Error::<T>::ProposalNotFound)?,
 					};
-					VotersVotedOnProposal::<T>::set((voting_round_id, proposal_id, &who), Some(()));
-					ProposalsForVotingRound::<T>::set(voting_round_id, Some(proposals));
-					VotersForBucket::<T>::set((voting_round_id, attached_bucket_id, &who), Some(bonded_tokens));
+					VotersForBucket::<T>::mutate((voting_round_id, attached_bucket_id, &who), |bond| {
+						if let Some(bond) = bond {
+							bond.remaining += commitment.credits;
+						}
+					});
+					VoteCommitments::<T>::remove((voting_round_id, proposal_id, &who));
+
+					Self::do_vote(&who, voting_round_id, &voting_round, proposal_id, vote, direction)?;
 				},
 				VotingPhases::Proposal |
 				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Tallying |
 				VotingPhases::PostVoting |
+				VotingPhases::Settling |
 				VotingPhases::Enactment |
-				VotingPhases::Finalized => Err(Error::<T>::CanCallOnlyDuringVotingPhase)?,
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringRevealPhase)?,
 			};
 
 			Ok(())
 		}
-	}
 
-	pub fn make_voting_round_metadata<T: Config>(
-		initiator: AccountIdFor<T>,
-		start_block: BlockNumberFor<T>,
-		previous_round_id: VotingRoundId,
-	) -> Result<VotingRoundMetadata<AccountIdFor<T>, BlockNumberFor<T>>, Error<T>> {
-		let proposal_start = start_block;
-		let proposal_end = start_block + T::BlocksForProposalPhase::get();
+		// sets the coordinator of the encrypted rounds started from now on
+		#[pallet::weight(T::WeightInfo::set_coordinator())]
+		pub fn set_coordinator(origin: OriginFor<T>, account: T::AccountId, public_key: [u8; 32]) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
 
-		let pre_voting_start = proposal_end + T::OneBlock::get();
-		let pre_voting_end = pre_voting_start + T::BlocksForPreVotingPhase::get();
+			Coordinator::<T>::put(CoordinatorInfo { account: account.clone(), public_key });
+			Self::deposit_event(Event::CoordinatorSet { coordinator: account });
 
-		let voting_start = pre_voting_end + T::OneBlock::get();
-		let voting_end = voting_start + T::BlocksForVotingPhase::get();
+			Ok(())
+		}
 
-		let post_voting_start = voting_end + T::OneBlock::get();
-		let post_voting_end = post_voting_start + T::BlocksForPostVotingPhase::get();
+		// registers the key that signs the voter's messages. It can later be changed by a message,
+		// so that nobody but the coordinator knows which key is current. Only voters who registered
+		// in the round can sign up
+		#[pallet::weight(T::WeightInfo::sign_up())]
+		pub fn sign_up(origin: OriginFor<T>, voting_key: [u8; 32]) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-		let enactment_start = post_voting_end + T::OneBlock::get();
-		let enactment_end = enactment_start + T::BlocksForEnactmentPhase::get();
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
 
-		let finalized = enactment_end + T::OneBlock::get();
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
 
-		return Ok(VotingRoundMetadata::<AccountIdFor<T>, BlockNumberFor<T>> {
-			initializer: initiator,
-			phase: VotingPhases::Proposal,
-			previous_round_id,
-			proposal_phase: VotingPhaseData::<BlockNumberFor<T>> {
-				start_block: proposal_start,
-				end_block: proposal_end,
-			},
-			pre_voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
-				start_block: pre_voting_start,
-				end_block: pre_voting_end,
-			},
-			voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
-				start_block: voting_start,
-				end_block: voting_end,
-			},
-			post_voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
-				start_block: post_voting_start,
-				end_block: post_voting_end,
-			},
-			enactment_phase: VotingPhaseData::<BlockNumberFor<T>> {
-				start_block: enactment_start,
-				end_block: enactment_end,
-			},
-			finalized_block: finalized,
-		})
+			if voting_round.ballot != BallotMode::Encrypted {
+				Err(Error::<T>::NotAnEncryptedRound)?
+			}
+
+			match voting_round.phase {
+				VotingPhases::PreVoting => {
+					if !Self::is_registered(voting_round_id, &who) {
+						Err(Error::<T>::NotRegistered)?
+					}
+					if VotingKeys::<T>::contains_key((voting_round_id, &who)) {
+						Err(Error::<T>::AlreadySignedUp)?
+					}
+
+					VotingKeys::<T>::insert((voting_round_id, &who), voting_key);
+					let root = SignUpRoot::<T>::get(voting_round_id);
+					SignUpRoot::<T>::insert(voting_round_id, T::Hashing::hash_of(&(root, &who, voting_key)));
+					Self::deposit_event(Event::SignedUp { round: voting_round_id, who, voting_key });
+				},
+				VotingPhases::Proposal |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringPreVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::publish_message())]
+		pub fn publish_message(origin: OriginFor<T>, message: EncryptedMessage<T::MaxMessageLength>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			if voting_round.ballot != BallotMode::Encrypted {
+				Err(Error::<T>::NotAnEncryptedRound)?
+			}
+
+			match voting_round.phase {
+				VotingPhases::Voting => {
+					if !VotingKeys::<T>::contains_key((voting_round_id, &who)) {
+						Err(Error::<T>::NotSignedUp)?
+					}
+
+					let index = MessageCount::<T>::get(voting_round_id);
+					if index >= T::MaxMessages::get() {
+						Err(Error::<T>::TooManyMessages)?
+					}
+					let published = VoterMessageCount::<T>::get((voting_round_id, &who));
+					if published >= T::MaxMessagesPerVoter::get() {
+						Err(Error::<T>::TooManyMessagesFromVoter)?
+					}
+
+					let root = MessageRoot::<T>::get(voting_round_id);
+					MessageRoot::<T>::insert(voting_round_id, T::Hashing::hash_of(&(root, &message)));
+					MessageCount::<T>::insert(voting_round_id, index + 1);
+					VoterMessageCount::<T>::insert((voting_round_id, &who), published + 1);
+					Self::deposit_event(Event::MessagePublished { round: voting_round_id, index, message });
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// the coordinator posts the tally of every proposal, in the order of `ProposalsForVotingRound`,
+		// with a proof that it follows from the sign ups and messages of the round
+		#[pallet::weight(T::WeightInfo::submit_tally(tally.len() as u32))]
+		pub fn submit_tally(
+			origin: OriginFor<T>,
+			tally: BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals>,
+			proof: BoundedVec<u8, T::MaxProofLength>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let coordinator = match RoundCoordinator::<T>::get(voting_round_id) {
+				Some(coordinator) => coordinator,
+				None => Err(Error::<T>::NotAnEncryptedRound)?,
+			};
+
+			if coordinator.account != who {
+				Err(Error::<T>::NotTheCoordinator)?
+			}
+
+			match voting_round.phase {
+				VotingPhases::Reveal => {
+					if CoordinatorTally::<T>::contains_key(voting_round_id) {
+						Err(Error::<T>::TallyAlreadySubmitted)?
+					}
+
+					let proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
+						Some(proposals) => proposals,
+						None => Err(Error::<T>::NoProposals)?,
+					};
+
+					if tally.len() != proposals.len() {
+						Err(Error::<T>::TallyLengthMismatch)?
+					}
+
+					let public_inputs = Self::tally_public_inputs(voting_round_id, &tally);
+					if !T::TallyVerifier::verify(&public_inputs, &proof) {
+						Err(Error::<T>::InvalidTallyProof)?
+					}
+
+					CoordinatorTally::<T>::insert(voting_round_id, tally);
+					Self::deposit_event(Event::TallyVerified { round: voting_round_id });
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringRevealPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// sets the threshold key of the rounds started from now on. `members` are the decryption committee,
+		// in the order of their share indices, with their verification keys
+		#[pallet::weight(T::WeightInfo::set_threshold_key(members.len() as u32))]
+		pub fn set_threshold_key(
+			origin: OriginFor<T>,
+			public_key: [u8; 32],
+			threshold: u32,
+			members: BoundedVec<(T::AccountId, [u8; 32]), T::MaxCommitteeMembers>,
+		) -> DispatchResult {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			if threshold == 0 || threshold as usize > members.len() {
+				Err(Error::<T>::InvalidThreshold)?
+			}
+
+			ThresholdKey::<T>::put(ThresholdKeySet { public_key, threshold, members });
+			Self::deposit_event(Event::ThresholdKeySet { public_key, threshold });
+
+			Ok(())
+		}
+
+		// the ballot is the encrypted `(proposal_id, vote, direction)`, and is decrypted after the Voting phase
+		#[pallet::weight(T::WeightInfo::cast_threshold_ballot())]
+		pub fn cast_threshold_ballot(origin: OriginFor<T>, ballot: EncryptedMessage<T::MaxMessageLength>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			if voting_round.ballot != BallotMode::ThresholdEncrypted {
+				Err(Error::<T>::NotAThresholdRound)?
+			}
+
+			match voting_round.phase {
+				VotingPhases::Voting => {
+					// the proposal is only known after decryption, so any registration will do
+					if !Self::is_registered(voting_round_id, &who) {
+						Err(Error::<T>::NotRegistered)?
+					}
+
+					let index = MessageCount::<T>::get(voting_round_id);
+					if index >= T::MaxMessages::get() {
+						Err(Error::<T>::TooManyMessages)?
+					}
+					let cast = VoterMessageCount::<T>::get((voting_round_id, &who));
+					if cast >= T::MaxMessagesPerVoter::get() {
+						Err(Error::<T>::TooManyMessagesFromVoter)?
+					}
+
+					ThresholdBallots::<T>::insert((voting_round_id, index), (&who, ballot));
+					MessageCount::<T>::insert(voting_round_id, index + 1);
+					VoterMessageCount::<T>::insert((voting_round_id, &who), cast + 1);
+					Self::deposit_event(Event::ThresholdBallotCast { round: voting_round_id, who, index });
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// a member of the decryption committee submits its shares of the ballots from `first` on, in the order of
+		// the ballots. The member counts towards the threshold once it has a share for every ballot
+		#[pallet::weight(T::WeightInfo::submit_decryption_shares(shares.len() as u32))]
+		pub fn submit_decryption_shares(
+			origin: OriginFor<T>,
+			first: u32,
+			shares: BoundedVec<DecryptionShare, T::MaxSharesPerCall>,
+		) -> DispatchResult {
+			let who = T::DecryptionOrigin::ensure_origin(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let key = match RoundThresholdKey::<T>::get(voting_round_id) {
+				Some(key) => key,
+				None => Err(Error::<T>::NotAThresholdRound)?,
+			};
+
+			let (index, verification_key) = match key.members.iter().position(|(member, _)| *member == who) {
+				Some(position) => (position as u32 + 1, key.members[position].1),
+				None => Err(Error::<T>::NotACommitteeMember)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::Reveal => {
+					let count = MessageCount::<T>::get(voting_round_id);
+					let submitted = SharesSubmitted::<T>::get((voting_round_id, &who));
+					if ShareSubmitters::<T>::get(voting_round_id).iter().any(|(_, member)| *member == who) {
+						Err(Error::<T>::SharesAlreadySubmitted)?
+					}
+
+					if first != submitted {
+						Err(Error::<T>::SharesOutOfOrder)?
+					}
+
+					let next = first.saturating_add(shares.len() as u32);
+					if shares.is_empty() || next > count {
+						Err(Error::<T>::SharesLengthMismatch)?
+					}
+
+					for (i, share) in (first..next).zip(shares.iter()) {
+						let (_, ballot) = match ThresholdBallots::<T>::get((voting_round_id, i)) {
+							Some(ballot) => ballot,
+							None => Err(Error::<T>::BallotNotFound)?,
+						};
+						if !threshold::verify_share(&verification_key, &ballot.ephemeral_key, share) {
+							Err(Error::<T>::InvalidDecryptionShare)?
+						}
+					}
+
+					if next == count {
+						ShareSubmitters::<T>::try_mutate(voting_round_id, |submitters| {
+							submitters.try_push((index, who.clone()))
+						})
+						.map_err(|_| Error::<T>::StorageOverflow)?;
+					}
+					for (i, share) in (first..next).zip(shares.into_iter()) {
+						DecryptionShares::<T>::insert((voting_round_id, &who, i), share);
+					}
+					SharesSubmitted::<T>::insert((voting_round_id, &who), next);
+					if next == count {
+						Self::deposit_event(Event::DecryptionSharesSubmitted { round: voting_round_id, who });
+					}
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringRevealPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// a voter registered in the bucket joins its committee, if its VRF output over the round randomness
+		// is below the committee threshold
+		#[pallet::weight(T::WeightInfo::apply_for_committee())]
+		pub fn apply_for_committee(
+			origin: OriginFor<T>,
+			bucket_id: BucketId,
+			vrf_output: VRFOutput,
+			vrf_proof: VRFProof,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::PreVoting => {
+					if !VotersForBucket::<T>::contains_key((voting_round_id, bucket_id, &who)) {
+						Err(Error::<T>::NotRegistered)?
+					}
+
+					let mut committee = Committees::<T>::get((voting_round_id, bucket_id));
+					if committee.contains(&who) {
+						Err(Error::<T>::AlreadyInCommittee)?
+					}
+
+					let public_key = match T::VrfKey::convert(who.clone()).and_then(|key| PublicKey::from_bytes(&key).ok()) {
+						Some(key) => key,
+						None => Err(Error::<T>::NoVrfKey)?,
+					};
+
+					let randomness = match RoundRandomness::<T>::get(voting_round_id) {
+						Some(randomness) => randomness,
+						None => Err(Error::<T>::VotingRoundNotFound)?,
+					};
+
+					let transcript = Self::committee_transcript(voting_round_id, bucket_id, &randomness);
+					let (inout, _) = match public_key.vrf_verify(transcript, &vrf_output.0, &vrf_proof.0) {
+						Ok(verified) => verified,
+						Err(_) => Err(Error::<T>::InvalidVrfProof)?,
+					};
+
+					let score = u128::from_le_bytes(inout.make_bytes::<[u8; 16]>(b"quadratic-voting-committee-score"));
+					if score >= Self::committee_threshold() {
+						Err(Error::<T>::NotEligibleForCommittee)?
+					}
+
+					committee.try_push(who.clone()).map_err(|_| Error::<T>::CommitteeFull)?;
+					T::Token::reserve(&who, T::BondForCommittee::get())?;
+					Committees::<T>::insert((voting_round_id, bucket_id), committee);
+					Self::deposit_event(Event::CommitteeMemberJoined { round: voting_round_id, bucket: bucket_id, who });
+				},
+				VotingPhases::Proposal |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringPreVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// a committee member submits the tally of its bucket. It is accepted once a majority of the committee
+		// submitted the same tally
+		#[pallet::weight(T::WeightInfo::submit_bucket_tally(results.len() as u32, T::MaxBucketCommitteeSize::get()))]
+		pub fn submit_bucket_tally(
+			origin: OriginFor<T>,
+			bucket_id: BucketId,
+			results: BoundedVec<TallyResult<BalanceOf<T>>, T::MaxProposals>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::PostVoting => {
+					// encrypted rounds are tallied by their coordinator
+					if voting_round.ballot == BallotMode::Encrypted {
+						Err(Error::<T>::UnsupportedBallotMode)?
+					}
+
+					let committee = Committees::<T>::get((voting_round_id, bucket_id));
+					if !committee.contains(&who) {
+						Err(Error::<T>::NotInCommittee)?
+					}
+
+					if BucketTallies::<T>::contains_key((voting_round_id, bucket_id)) {
+						Err(Error::<T>::BucketTallyAlreadyAccepted)?
+					}
+
+					if BucketTallySubmissions::<T>::contains_key((voting_round_id, bucket_id, &who)) {
+						Err(Error::<T>::BucketTallyAlreadySubmitted)?
+					}
+
+					// the committee bonds are returned when the phase ends, so the challenge period must end before it.
+					// Buckets left without a tally are counted by the chain
+					let now = <frame_system::Pallet<T>>::block_number();
+					if now + T::ChallengePeriod::get() >= voting_round.post_voting_phase.end_block {
+						Err(Error::<T>::TooLateForBucketTally)?
+					}
+
+					let proposals = Self::proposals_in_bucket(voting_round_id, bucket_id);
+					if results.len() != proposals.len() {
+						Err(Error::<T>::BucketTallyLengthMismatch)?
+					}
+
+					let hash = T::Hashing::hash_of(&results);
+					BucketTallySubmissions::<T>::insert((voting_round_id, bucket_id, &who), hash);
+					Self::deposit_event(Event::BucketTallySubmitted { round: voting_round_id, bucket: bucket_id, who });
+
+					let agreeing = BucketTallySubmissions::<T>::iter_prefix_values((voting_round_id, bucket_id))
+						.filter(|submitted| *submitted == hash)
+						.count();
+					if agreeing * 2 > committee.len() {
+						for ((proposal_id, proposal), result) in proposals.iter().zip(results.iter()) {
+							Self::record_result(voting_round_id, &voting_round, *proposal_id, proposal, result);
+						}
+						let _ = ProposedBucketTallies::<T>::clear_prefix((voting_round_id, bucket_id), u32::MAX, None);
+						BucketTallies::<T>::insert(
+							(voting_round_id, bucket_id),
+							BucketTally { results, accepted_at: now, challenged: false },
+						);
+						Self::deposit_event(Event::BucketTallyAccepted { round: voting_round_id, bucket: bucket_id });
+					} else {
+						ProposedBucketTallies::<T>::insert((voting_round_id, bucket_id, hash), results);
+					}
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringPostVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// a registered voter disputes an accepted committee tally, with a bond. The chain recounts the bucket:
+		// if the tally was wrong, the members who submitted a wrong tally lose their bond to the challenger,
+		// otherwise the challenger loses theirs
+		#[pallet::weight(T::WeightInfo::challenge_tally(T::MaxProposals::get(), T::MaxVotes::get(), T::MaxBucketCommitteeSize::get()))]
+		pub fn challenge_tally(origin: OriginFor<T>, bucket_id: BucketId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::PostVoting => {
+					if !Self::is_registered(voting_round_id, &who) {
+						Err(Error::<T>::NotRegistered)?
+					}
+
+					let mut tally = match BucketTallies::<T>::get((voting_round_id, bucket_id)) {
+						Some(tally) => tally,
+						None => Err(Error::<T>::BucketTallyNotAccepted)?,
+					};
+
+					if tally.challenged {
+						Err(Error::<T>::AlreadyChallenged)?
+					}
+
+					if <frame_system::Pallet<T>>::block_number() > tally.accepted_at + T::ChallengePeriod::get() {
+						Err(Error::<T>::ChallengePeriodOver)?
+					}
+
+					let bond = T::BondForChallenge::get();
+					T::Token::reserve(&who, bond)?;
+
+					// recount
+					let proposals = Self::proposals_in_bucket(voting_round_id, bucket_id);
+					let recount: Vec<TallyResult<BalanceOf<T>>> =
+						proposals.iter().map(|(_, proposal)| Self::count(proposal)).collect();
+
+					if recount[..] == tally.results[..] {
+						// forfeited, like every other bond
+						T::Token::repatriate_reserved(&who, &Self::account_id(), bond, BalanceStatus::Free)?;
+						Self::deposit_event(Event::ChallengeRejected { round: voting_round_id, bucket: bucket_id, challenger: who });
+					} else {
+						for ((proposal_id, proposal), result) in proposals.iter().zip(recount.iter()) {
+							Self::record_result(voting_round_id, &voting_round, *proposal_id, proposal, result);
+						}
+
+						// every member who submitted a different tally than the recount is slashed
+						let correct = T::Hashing::hash_of(&recount);
+						let mut committee = Committees::<T>::get((voting_round_id, bucket_id));
+						let mut slashed = Vec::new();
+						for (member, submitted) in BucketTallySubmissions::<T>::iter_prefix((voting_round_id, bucket_id)) {
+							if submitted != correct {
+								let unpaid = T::Token::repatriate_reserved(
+									&member,
+									&who,
+									T::BondForCommittee::get(),
+									BalanceStatus::Free,
+								)?;
+								slashed.push((member, T::BondForCommittee::get() - unpaid));
+							}
+						}
+						// slashed members no longer hold a committee bond to return
+						committee.retain(|member| !slashed.iter().any(|(slashed, _)| slashed == member));
+						Committees::<T>::insert((voting_round_id, bucket_id), committee);
+						for (member, amount) in slashed {
+							Self::deposit_event(Event::CommitteeMemberSlashed {
+								round: voting_round_id,
+								bucket: bucket_id,
+								who: member,
+								amount,
+							});
+						}
+
+						T::Token::unreserve(&who, bond);
+						tally.results = BoundedVec::truncate_from(recount);
+						Self::deposit_event(Event::BucketTallyOverturned { round: voting_round_id, bucket: bucket_id, challenger: who });
+					}
+
+					tally.challenged = true;
+					BucketTallies::<T>::insert((voting_round_id, bucket_id), tally);
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::Settling |
+				VotingPhases::Enactment |
+				VotingPhases::Finalized |
+				VotingPhases::Cancelled => Err(Error::<T>::CanCallOnlyDuringPostVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// returns the bonds the caller holds in a round, once the round is past the post voting phase
+		#[pallet::weight(T::WeightInfo::claim_refund(T::MaxProposals::get()))]
+		pub fn claim_refund(origin: OriginFor<T>, voting_round_id: VotingRoundId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::Settling | VotingPhases::Enactment | VotingPhases::Finalized | VotingPhases::Cancelled => {
+					Self::release_refund(voting_round_id, &voting_round, &who)?;
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting => Err(Error::<T>::CanCallOnlyAfterPostVotingPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// releases the bonds someone else left in a round that is over, for a `RefundTip` share of them
+		#[pallet::weight(T::WeightInfo::refund_others(T::MaxProposals::get()))]
+		pub fn refund_others(origin: OriginFor<T>, voting_round_id: VotingRoundId, who: T::AccountId) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			match voting_round.phase {
+				VotingPhases::Finalized | VotingPhases::Cancelled => {
+					let refund = Self::release_refund(voting_round_id, &voting_round, &who)?;
+					let tip = T::RefundTip::get() * refund;
+					if !tip.is_zero() {
+						T::Token::transfer(&who, &caller, tip, ExistenceRequirement::AllowDeath)?;
+						Self::deposit_event(Event::RefundTipPaid { round: voting_round_id, who, to: caller, amount: tip });
+					}
+				},
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment => Err(Error::<T>::CanCallOnlyAfterEnactmentPhase)?,
+			};
+
+			Ok(())
+		}
+
+		// takes one step of reaping a round that is past its retention period, ahead of `on_idle`
+		#[pallet::weight(Pallet::<T>::reap_step_weight())]
+		pub fn reap_round(origin: OriginFor<T>, voting_round_id: VotingRoundId) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(metadata) => metadata,
+				None => Err(Error::<T>::VotingRoundNotFound)?,
+			};
+
+			let latest_voting_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => 0,
+			};
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			if !Self::is_reapable(voting_round_id, &voting_round, block_number, latest_voting_round_id) {
+				Err(Error::<T>::RoundNotReapable)?
+			}
+
+			Self::reap_step(voting_round_id, &voting_round);
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		// the public inputs of the tally proof: the sign up and message roots, followed by the
+		// ayes, nays, abstains and credits spent of every proposal
+		pub fn tally_public_inputs(
+			voting_round_id: VotingRoundId,
+			tally: &[TallyResult<BalanceOf<T>>],
+		) -> Vec<[u8; 32]> {
+			let mut inputs = Vec::with_capacity(2 + tally.len() * 4);
+			inputs.push(field_element(SignUpRoot::<T>::get(voting_round_id).as_ref()));
+			inputs.push(field_element(MessageRoot::<T>::get(voting_round_id).as_ref()));
+			for result in tally {
+				inputs.push(field_element(&result.ayes.encode()));
+				inputs.push(field_element(&result.nays.encode()));
+				inputs.push(field_element(&result.abstains.encode()));
+				inputs.push(field_element(&result.credits.encode()));
+			}
+			inputs
+		}
+
+		// the commitment a voter submits with `commit_vote`, and opens with `reveal_vote`
+		pub fn vote_commitment(
+			who: &AccountIdFor<T>,
+			proposal_id: ProposalCount,
+			vote: BalanceOf<T>,
+			direction: &VoteDirection,
+			salt: &[u8; 32],
+		) -> T::Hash {
+			T::Hashing::hash_of(&(who, proposal_id, vote, direction, salt))
+		}
+
+		// The queries of the runtime API
+
+		pub fn round_info(voting_round_id: VotingRoundId) -> Option<VotingRoundMetadataOf<T>> {
+			VotingRounds::<T>::get(voting_round_id)
+		}
+
+		pub fn bucket_proposals(voting_round_id: VotingRoundId, bucket_id: BucketId) -> Vec<ProposalInfo<AccountIdFor<T>>> {
+			Self::proposals_in_bucket(voting_round_id, bucket_id)
+				.into_iter()
+				.map(|(proposal_id, proposal)| ProposalInfo {
+					proposal_id,
+					votes: Self::votes_on(&proposal),
+					proposer: proposal.initializer,
+					class: proposal.class,
+				})
+				.collect()
+		}
+
+		pub fn voter_status(voting_round_id: VotingRoundId, who: &AccountIdFor<T>) -> VoterStatus<BalanceOf<T>> {
+			let registrations = (0..=T::BucketSize::get())
+				.filter_map(|bucket_id| {
+					VotersForBucket::<T>::get((voting_round_id, bucket_id, who)).map(|bond| (bucket_id, bond))
+				})
+				.collect();
+			VoterStatus { registrations, unclaimed_refund: UnclaimedRefunds::<T>::get((voting_round_id, who)) }
+		}
+
+		// the result of every proposal of the round: the recorded one once the proposal is tallied, or else the one
+		// that the votes cast so far would give
+		pub fn projected_tally(voting_round_id: VotingRoundId) -> Vec<(ProposalCount, ProposalResult<BalanceOf<T>>)> {
+			let voting_round = match VotingRounds::<T>::get(voting_round_id) {
+				Some(voting_round) => voting_round,
+				None => return Vec::new(),
+			};
+			// the votes of an encrypted round are only known once the coordinator posts its tally
+			let coordinator_tally = match voting_round.ballot {
+				BallotMode::Encrypted => Some(CoordinatorTally::<T>::get(voting_round_id).unwrap_or_default()),
+				BallotMode::Open | BallotMode::CommitReveal | BallotMode::ThresholdEncrypted => None,
+			};
+
+			ProposalsForVotingRound::<T>::get(voting_round_id)
+				.unwrap_or_default()
+				.iter()
+				.enumerate()
+				.map(|(i, proposal)| {
+					let proposal_id = i as ProposalCount;
+					let result = match ProposalOutcome::<T>::get((voting_round_id, proposal_id)) {
+						Some(result) => result,
+						None => {
+							let tally = match &coordinator_tally {
+								Some(tally) => tally.get(i).cloned().unwrap_or_default(),
+								None => Self::count(proposal),
+							};
+							Self::result_of(voting_round_id, &voting_round, proposal, &tally)
+						},
+					};
+					(proposal_id, result)
+				})
+				.collect()
+		}
+
+		// the credits it takes to cast `votes` votes, or None if it overflows
+		pub fn vote_cost(votes: BalanceOf<T>) -> Option<BalanceOf<T>> {
+			votes.checked_mul(&votes)
+		}
+
+		// the prefix of every storage item of the pallet
+		pub fn storage_prefix() -> [u8; 16] {
+			Twox128::hash(<Self as PalletInfoAccess>::name().as_bytes())
+		}
+
+		// every storage item of a round, or None if there is no such round, or it has been reaped
+		pub fn export_round(
+			voting_round_id: VotingRoundId,
+		) -> Option<RoundExport<AccountIdFor<T>, BlockNumberFor<T>, BalanceOf<T>, T::Hash, T::MaxVotes>> {
+			let round = VotingRounds::<T>::get(voting_round_id)?;
+			let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default().into_inner();
+
+			let mut registrations: Vec<_> = VotersForBucket::<T>::iter_prefix((voting_round_id,))
+				.map(|((bucket_id, who), bond)| (who, bucket_id, bond))
+				.collect();
+			// storage order is that of the hashed keys
+			registrations.sort_by_key(|(_, bucket_id, _)| *bucket_id);
+			let mut bucket_credits: Vec<_> = BucketCredits::<T>::iter_prefix((voting_round_id,)).collect();
+			bucket_credits.sort_by_key(|(bucket_id, _)| *bucket_id);
+
+			let ids = 0..proposals.len() as ProposalCount;
+			let voters = ids
+				.clone()
+				.filter_map(|proposal_id| {
+					VotersVoted::<T>::get((voting_round_id, proposal_id)).map(|voters| (proposal_id, voters.into_inner()))
+				})
+				.collect();
+			let contributions = ids
+				.clone()
+				.filter_map(|proposal_id| {
+					Contributions::<T>::get((voting_round_id, proposal_id))
+						.map(|contributions| (proposal_id, contributions.into_inner()))
+				})
+				.collect();
+			let outcomes = ids
+				.filter_map(|proposal_id| {
+					ProposalOutcome::<T>::get((voting_round_id, proposal_id)).map(|result| (proposal_id, result))
+				})
+				.collect();
+
+			Some(RoundExport {
+				round,
+				randomness: RoundRandomness::<T>::get(voting_round_id),
+				proposals,
+				registrations,
+				bucket_credits,
+				voters,
+				contributions,
+				outcomes,
+				unclaimed_refunds: UnclaimedRefunds::<T>::iter_prefix((voting_round_id,)).collect(),
+			})
+		}
+
+		// checks the invariants of the pallet's storage. Rounds being reaped lose their data over several blocks, so
+		// the votes of those are left out
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let reaping = |voting_round_id: VotingRoundId| RoundSummaries::<T>::contains_key(voting_round_id);
+
+			let latest_voting_round_id = LatestVotingRound::<T>::get().unwrap_or(0);
+			if latest_voting_round_id > 0 && !VotingRounds::<T>::contains_key(latest_voting_round_id) {
+				return Err("LatestVotingRound points to a round that does not exist")
+			}
+
+			for (voting_round_id, voting_round) in VotingRounds::<T>::iter() {
+				if voting_round.phase == VotingPhases::Proposal {
+					continue
+				}
+				let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+				if proposals.iter().any(|proposal| proposal.bucket_id.is_none()) {
+					return Err("a proposal of a round past its Proposal phase has no bucket")
+				}
+			}
+
+			// the credits registered in a bucket are those of the bonds in it
+			let mut registered = sp_std::collections::btree_map::BTreeMap::<(VotingRoundId, BucketId), BalanceOf<T>>::new();
+			for ((voting_round_id, bucket_id, _), bond) in VotersForBucket::<T>::iter() {
+				if bond.remaining > bond.total {
+					return Err("a voter bond has more remaining than bonded")
+				}
+				let total = registered.entry((voting_round_id, bucket_id)).or_default();
+				*total = total.saturating_add(bond.total);
+			}
+			for ((voting_round_id, bucket_id), credits) in BucketCredits::<T>::iter() {
+				if reaping(voting_round_id) {
+					continue
+				}
+				if registered.remove(&(voting_round_id, bucket_id)).unwrap_or_default() != credits {
+					return Err("BucketCredits is not the sum of the bonds in the bucket")
+				}
+			}
+			if registered.into_iter().any(|((voting_round_id, _), total)| !reaping(voting_round_id) && !total.is_zero()) {
+				return Err("a bucket with bonds has no BucketCredits")
+			}
+
+			for ((voting_round_id, proposal_id), voters) in VotersVoted::<T>::iter() {
+				if reaping(voting_round_id) {
+					continue
+				}
+				for (i, who) in voters.iter().enumerate() {
+					if voters[..i].contains(who) {
+						return Err("an account voted twice on a proposal")
+					}
+					if !VotersVotedOnProposal::<T>::contains_key((voting_round_id, proposal_id, who)) {
+						return Err("a voter in VotersVoted is missing from VotersVotedOnProposal")
+					}
+				}
+			}
+			for (voting_round_id, proposal_id, who) in VotersVotedOnProposal::<T>::iter_keys() {
+				if reaping(voting_round_id) {
+					continue
+				}
+				if !VotersVoted::<T>::get((voting_round_id, proposal_id)).map_or(false, |voters| voters.contains(&who)) {
+					return Err("a voter in VotersVotedOnProposal is missing from VotersVoted")
+				}
+			}
+
+			// every bond the pallet holds is reserved, on top of the identity deposits. The refunds still include
+			// what will be forfeited when they are claimed, and challenger bonds are forfeited or returned in the call
+			// that reserves them
+			let mut held = sp_std::collections::btree_map::BTreeMap::<AccountIdFor<T>, BalanceOf<T>>::new();
+			for ((_, who), refund) in UnclaimedRefunds::<T>::iter() {
+				let total = held.entry(who).or_default();
+				*total = total.saturating_add(refund);
+			}
+			// committee bonds are returned when the round is settled or cancelled
+			for ((voting_round_id, _), members) in Committees::<T>::iter() {
+				let bonded = match VotingRounds::<T>::get(voting_round_id).map(|voting_round| voting_round.phase) {
+					Some(VotingPhases::PreVoting) |
+					Some(VotingPhases::Voting) |
+					Some(VotingPhases::Reveal) |
+					Some(VotingPhases::Tallying) |
+					Some(VotingPhases::PostVoting) |
+					Some(VotingPhases::Settling) => true,
+					Some(VotingPhases::Proposal) |
+					Some(VotingPhases::Enactment) |
+					Some(VotingPhases::Finalized) |
+					Some(VotingPhases::Cancelled) |
+					None => false,
+				};
+				if !bonded {
+					continue
+				}
+				for member in members {
+					let total = held.entry(member).or_default();
+					*total = total.saturating_add(T::BondForCommittee::get());
+				}
+			}
+			for (who, total) in held {
+				if T::Token::reserved_balance(&who) < total {
+					return Err("the reserved balance of an account does not cover its bonds")
+				}
+			}
+
+			// forfeited bonds cannot be paid to an account that does not exist
+			if T::Token::free_balance(&Self::account_id()) < T::Token::minimum_balance() {
+				return Err("the pallet account forfeited bonds are paid to is not alive")
+			}
+
+			Ok(())
+		}
+
+		fn do_vote(
+			who: &AccountIdFor<T>,
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			proposal_id: ProposalCount,
+			vote: BalanceOf<T>,
+			direction: VoteDirection,
+		) -> DispatchResult {
+			let mut proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
+				Some(proposals) => proposals,
+				None => Err(Error::<T>::NoProposals)?,
+			};
+
+			let proposal = match proposals.get_mut(proposal_id as usize) {
+				Some(proposal) => proposal,
+				None => Err(Error::<T>::ProposalNotFound)?,
+			};
+
+			// check if voter has voted for this proposal already
+			match VotersVotedOnProposal::<T>::get((voting_round_id, proposal_id, who)) {
+				Some(_) => Err(Error::<T>::VoterHasVotedForThisProposal)?,
+				None => {},
+			}
+
+			let attached_bucket_id = proposal.bucket_id.expect("qed");
+
+			let mut bonded_tokens = match VotersForBucket::<T>::get((voting_round_id, attached_bucket_id, who)) {
+				Some(tokens) => tokens,
+				None => Err(Error::<T>::NoTokensBonded)?,
+			};
+
+			// check if vote is greater than the remaining bond
+			if vote > bonded_tokens.remaining {
+				Err(Error::<T>::CannotVoteMoreThanBond)?
+			}
+
+			let contribution = vote;
+			if let RoundMode::Funding { .. } = voting_round.mode {
+				if direction != VoteDirection::Aye {
+					Err(Error::<T>::OnlyAyeInFundingRound)?
+				}
+			}
+
+			// we accept the vote now
+			let vote = get_vote_from_bond::<T>(vote)?;
+			let _ = match direction {
+				VoteDirection::Aye => {
+					proposal.ayes.try_push(vote).map_err(|_| Error::<T>::StorageOverflow)?;
+					match voting_round.mode {
+						RoundMode::Funding { .. } => {
+							// the contribution goes straight to the proposer, so it is spent in full
+							Contributions::<T>::try_append((voting_round_id, proposal_id), (who.clone(), contribution))
+								.map_err(|_| Error::<T>::TooManyContributors)?;
+							T::Token::repatriate_reserved(who, &proposal.initializer, contribution, BalanceStatus::Free)?;
+							UnclaimedRefunds::<T>::mutate((voting_round_id, who), |refund| *refund = refund.saturating_sub(contribution));
+							bonded_tokens.remaining -= contribution;
+						},
+						RoundMode::Governance => {
+							bonded_tokens.remaining -= vote;
+						},
+					}
+				},
+				VoteDirection::Nay => {
+					proposal.nays.try_push(vote).map_err(|_| Error::<T>::StorageOverflow)?;
+					bonded_tokens.remaining -= vote;
+				},
+				VoteDirection::Abstain => {
+					proposal.abstains.try_push(vote).map_err(|_| Error::<T>::StorageOverflow)?;
+					bonded_tokens.remaining -= vote;
+				},
+			};
+
+			let mut past_voters = match VotersVoted::<T>::get((voting_round_id, proposal_id)) {
+				Some(past) => past,
+				None => BoundedVec::<AccountIdFor<T>, T::MaxVotes>::default(),
+			};
+			past_voters.try_push(who.clone()).map_err(|_| Error::<T>::StorageOverflow)?;
+			VotersVoted::<T>::set((voting_round_id, proposal_id), Some(past_voters));
+			proposals[proposal_id as usize] = Proposal::<T::AccountId, BalanceOf<T>, T::MaxVotes> {
+				initializer: proposal.initializer.clone(),
+				class: proposal.class,
+				ayes: proposal.ayes.clone(),
+				nays: proposal.nays.clone(),
+				abstains: proposal.abstains.clone(),
+				bucket_id: proposal.bucket_id,
+			};
+			VotersVotedOnProposal::<T>::set((voting_round_id, proposal_id, who), Some(()));
+			ProposalsForVotingRound::<T>::set(voting_round_id, Some(proposals));
+			VotersForBucket::<T>::set((voting_round_id, attached_bucket_id, who), Some(bonded_tokens));
+			Self::deposit_event(Event::VoteCast {
+				round: voting_round_id,
+				proposal: proposal_id,
+				who: who.clone(),
+				votes: vote,
+				direction,
+			});
+
+			Ok(())
+		}
+
+		// moves the tally of the round forward, once its unrevealed commitments are settled, and the round to
+		// PostVoting once every proposal is tallied
+		fn tally_step(voting_round_id: VotingRoundId, voting_round: &mut VotingRoundMetadataOf<T>) -> Weight {
+			let mut weight = 0;
+			if voting_round.ballot == BallotMode::CommitReveal {
+				let settled = Self::settle_commitments(voting_round_id, T::MaxRefundsPerBlock::get());
+				if settled > 0 {
+					weight = T::WeightInfo::settle_commitments(settled);
+				}
+				// there may be more left
+				if settled >= T::MaxRefundsPerBlock::get() {
+					return weight
+				}
+			}
+
+			let (proposal_count, votes, done) = Self::tally(voting_round_id, voting_round);
+			weight = weight.saturating_add(T::WeightInfo::tally(proposal_count, votes));
+			if done {
+				// transition state
+				voting_round.phase = VotingPhases::PostVoting;
+				Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::PostVoting });
+			}
+			weight
+		}
+
+		// tallies the next `MaxTalliesPerBlock` proposals. Returns how many were tallied, the most votes cast
+		// on one of them, and whether every proposal has been tallied
+		fn tally(voting_round_id: VotingRoundId, voting_round: &VotingRoundMetadataOf<T>) -> (u32, u32, bool) {
+			let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).expect("qed");
+			let start = TallyCursor::<T>::get(voting_round_id);
+			let end = (proposals.len() as ProposalCount).min(start.saturating_add(T::MaxTalliesPerBlock::get()));
+
+			// without a verified tally, the proposals of an encrypted round get no votes
+			let coordinator_tally = match voting_round.ballot {
+				BallotMode::Encrypted => Some(CoordinatorTally::<T>::get(voting_round_id).unwrap_or_default()),
+				BallotMode::Open | BallotMode::CommitReveal | BallotMode::ThresholdEncrypted => None,
+			};
+
+			let mut most_votes = 0;
+			for i in start as usize..end as usize {
+				most_votes = most_votes.max(Self::votes_on(&proposals[i]));
+				let result = match &coordinator_tally {
+					Some(tally) => tally.get(i).cloned().unwrap_or_default(),
+					None => {
+						// buckets with a committee are tallied by it, during PostVoting
+						let bucket_id = proposals[i].bucket_id.expect("qed");
+						if !Committees::<T>::get((voting_round_id, bucket_id)).is_empty() {
+							continue
+						}
+						Self::count(&proposals[i])
+					},
+				};
+				Self::record_result(voting_round_id, voting_round, i as ProposalCount, &proposals[i], &result);
+			}
+
+			TallyCursor::<T>::insert(voting_round_id, end);
+			(end - start, most_votes, end as usize >= proposals.len())
+		}
+
+		// counts up to `MaxTalliesPerBlock` of the proposals that their committee left undecided, from `from`.
+		// Returns where to resume, unless every proposal is decided, how many were counted, and the most votes
+		// cast on one of them
+		fn tally_remaining(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			from: ProposalCount,
+		) -> (Option<ProposalCount>, u32, u32) {
+			let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+			let (mut remaining, mut most_votes) = (0, 0);
+			for i in from as usize..proposals.len() {
+				if remaining >= T::MaxTalliesPerBlock::get() {
+					return (Some(i as ProposalCount), remaining, most_votes)
+				}
+				if !ProposalOutcome::<T>::contains_key((voting_round_id, i as ProposalCount)) {
+					remaining += 1;
+					most_votes = most_votes.max(Self::votes_on(&proposals[i]));
+					let result = Self::count(&proposals[i]);
+					Self::record_result(voting_round_id, voting_round, i as ProposalCount, &proposals[i], &result);
+				}
+			}
+
+			(None, remaining, most_votes)
+		}
+
+		fn votes_on(proposal: &Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>) -> u32 {
+			(proposal.ayes.len() + proposal.nays.len() + proposal.abstains.len()) as u32
+		}
+
+		pub(crate) fn count(proposal: &Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>) -> TallyResult<BalanceOf<T>> {
+			let mut ayes: BalanceOf<T> = 0u32.into();
+
+			// this should be replaced with an .iter().sum() :/
+			for aye in &proposal.ayes {
+				ayes += *aye;
+			}
+			let mut nays:  BalanceOf<T> = 0u32.into();
+
+			for nay in &proposal.nays {
+				nays += *nay;
+			}
+			let mut abstains: BalanceOf<T> = 0u32.into();
+
+			for abstain in &proposal.abstains {
+				abstains += *abstain;
+			}
+
+			// every vote cost its square in credits
+			let mut credits: BalanceOf<T> = 0u32.into();
+			for vote in proposal.ayes.iter().chain(proposal.nays.iter()).chain(proposal.abstains.iter()) {
+				credits = credits.saturating_add(vote.saturating_mul(*vote));
+			}
+			TallyResult { ayes, nays, abstains, credits }
+		}
+
+		fn record_result(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			proposal_id: ProposalCount,
+			proposal: &Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>,
+			result: &TallyResult<BalanceOf<T>>,
+		) {
+			let key = (voting_round_id, proposal_id);
+			let result = Self::result_of(voting_round_id, voting_round, proposal, result);
+			Self::deposit_event(Event::ProposalTallied {
+				round: voting_round_id,
+				proposal: proposal_id,
+				ayes: result.ayes,
+				nays: result.nays,
+				abstains: result.abstains,
+				outcome: result.outcome.clone(),
+			});
+			ProposalOutcome::<T>::set(key, Some(result));
+		}
+
+		fn result_of(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			proposal: &Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>,
+			result: &TallyResult<BalanceOf<T>>,
+		) -> ProposalResult<BalanceOf<T>> {
+			let TallyResult { ayes, nays, abstains, credits } = result.clone();
+
+			// turnout is measured in credits, like the registrations of the bucket, and abstentions count towards it
+			let bucket_id = proposal.bucket_id.expect("qed");
+			let registered = BucketCredits::<T>::get((voting_round_id, bucket_id));
+
+			let threshold = T::ClassThreshold::convert(proposal.class);
+			let outcome = if credits.is_zero() || credits < voting_round.quorum * registered {
+				Outcome::NoQuorum
+			} else if threshold.approves(ayes, nays) {
+				Outcome::Aye
+			} else {
+				Outcome::Nay
+			};
+
+			ProposalResult { ayes, nays, abstains, credits, threshold, outcome }
+		}
+
+		// the proposals of a bucket, with their ids, in the order of the ids
+		fn proposals_in_bucket(
+			voting_round_id: VotingRoundId,
+			bucket_id: BucketId,
+		) -> Vec<(ProposalCount, Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>)> {
+			ProposalsForVotingRound::<T>::get(voting_round_id)
+				.unwrap_or_default()
+				.into_iter()
+				.enumerate()
+				.filter(|(_, proposal)| proposal.bucket_id == Some(bucket_id))
+				.map(|(i, proposal)| (i as ProposalCount, proposal))
+				.collect()
+		}
+
+		// whether the account registered in any bucket of the round
+		fn is_registered(voting_round_id: VotingRoundId, who: &AccountIdFor<T>) -> bool {
+			(0..=T::BucketSize::get()).any(|bucket_id| VotersForBucket::<T>::contains_key((voting_round_id, bucket_id, who)))
+		}
+
+		// drops up to `limit` of the commitments that were not revealed, slashing them if so configured. Returns
+		// how many there were
+		fn settle_commitments(voting_round_id: VotingRoundId, limit: u32) -> u32 {
+			let mut settled = 0;
+			let policy = T::UnrevealedCommits::get();
+			let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+			let unrevealed = VoteCommitments::<T>::drain_prefix((voting_round_id,)).take(limit as usize);
+			for ((proposal_id, who), commitment) in unrevealed {
+				settled += 1;
+				let bucket_id = match proposals.get(proposal_id as usize).and_then(|proposal| proposal.bucket_id) {
+					Some(bucket_id) => bucket_id,
+					None => continue,
+				};
+				match policy {
+					UnrevealedPolicy::Ignore => {
+						// the held credits go back to the voter's bond
+						VotersForBucket::<T>::mutate((voting_round_id, bucket_id, &who), |bond| {
+							if let Some(bond) = bond {
+								bond.remaining += commitment.credits;
+							}
+						});
+						Self::deposit_event(Event::UnrevealedCommitReleased {
+							round: voting_round_id,
+							proposal: proposal_id,
+							who,
+							credits: commitment.credits,
+						});
+					},
+					UnrevealedPolicy::Slash => {
+						let (_, unslashed) = T::Token::slash_reserved(&who, commitment.credits);
+						let slashed = commitment.credits - unslashed;
+						UnclaimedRefunds::<T>::mutate((voting_round_id, &who), |refund| *refund = refund.saturating_sub(slashed));
+
+						// the slashed credits are no longer part of the bond, nor of the credits registered in the bucket
+						VotersForBucket::<T>::mutate((voting_round_id, bucket_id, &who), |bond| {
+							if let Some(bond) = bond {
+								bond.total = bond.total.saturating_sub(slashed);
+							}
+						});
+						BucketCredits::<T>::mutate((voting_round_id, bucket_id), |credits| {
+							*credits = credits.saturating_sub(slashed)
+						});
+						Self::deposit_event(Event::UnrevealedCommitSlashed {
+							round: voting_round_id,
+							proposal: proposal_id,
+							who,
+							amount: slashed,
+						});
+					},
+				}
+			}
+			settled
+		}
+
+		// opens the next batch of threshold-encrypted ballots with the shares of `submitters`, and casts them.
+		// Returns the size of the batch, and whether every ballot has been opened
+		fn decrypt_ballots(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			submitters: &[(u32, AccountIdFor<T>)],
+		) -> (u32, bool) {
+			let count = MessageCount::<T>::get(voting_round_id);
+			let mut cursor = DecryptionCursor::<T>::get(voting_round_id);
+			let start = cursor;
+			let end = count.min(cursor.saturating_add(T::MaxDecryptionsPerBlock::get()));
+
+			while cursor < end {
+				let vote = ThresholdBallots::<T>::get((voting_round_id, cursor)).and_then(|(voter, ballot)| {
+					let points: Vec<(u32, [u8; 32])> = submitters
+						.iter()
+						.filter_map(|(index, member)| {
+							DecryptionShares::<T>::get((voting_round_id, member, cursor)).map(|share| (*index, share.share))
+						})
+						.collect();
+					let plaintext = threshold::decrypt(&threshold::combine(&points)?, &ballot.data);
+					let (proposal_id, vote, direction) =
+						<(ProposalCount, BalanceOf<T>, VoteDirection)>::decode(&mut &plaintext[..]).ok()?;
+					Some((voter, proposal_id, vote, direction))
+				});
+
+				let accepted = match vote {
+					Some((voter, proposal_id, vote, direction)) =>
+						Self::do_vote(&voter, voting_round_id, voting_round, proposal_id, vote, direction).is_ok(),
+					None => false,
+				};
+				if !accepted {
+					Self::deposit_event(Event::BallotRejected { round: voting_round_id, index: cursor });
+				}
+				cursor += 1;
+			}
+
+			DecryptionCursor::<T>::insert(voting_round_id, cursor);
+			(cursor - start, cursor >= count)
+		}
+
+		// moves the settlement of the round forward, and the round to Enactment once it is done. The remaining
+		// buckets are counted `MaxTalliesPerBlock` proposals at a time, and the round is finished in the block
+		// the last of them is counted
+		fn settle_step(voting_round_id: VotingRoundId, voting_round: &mut VotingRoundMetadataOf<T>) -> Weight {
+			let mut weight = 0;
+			let mut settlement = match SettlementProgress::<T>::get(voting_round_id) {
+				Some(settlement) => settlement,
+				None => Settlement::Tallying(0),
+			};
+
+			// the chain counts the buckets whose committee did not agree on a tally
+			if let Settlement::Tallying(from) = settlement {
+				let (next, remaining, votes) = Self::tally_remaining(voting_round_id, voting_round, from);
+				weight = weight.saturating_add(T::WeightInfo::tally(remaining, votes));
+				settlement = match next {
+					Some(next) => Settlement::Tallying(next),
+					None => Settlement::Finishing,
+				};
+			}
+
+			if settlement == Settlement::Finishing {
+				let committee_refunds = Self::refund_committees(voting_round_id);
+
+				let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+				if let RoundMode::Funding { .. } = voting_round.mode {
+					Self::distribute_matching_pool(voting_round_id, &voting_round.initializer, &proposals);
+				}
+				weight = weight.saturating_add(T::WeightInfo::finish_settling(proposals.len() as u32, committee_refunds));
+
+				// transition state
+				voting_round.phase = VotingPhases::Enactment;
+				Self::deposit_event(Event::PhaseTransition { round: voting_round_id, phase: VotingPhases::Enactment });
+			}
+
+			SettlementProgress::<T>::insert(voting_round_id, settlement);
+			weight
+		}
+
+		fn hold_refund(voting_round_id: VotingRoundId, who: &AccountIdFor<T>, bond: BalanceOf<T>) {
+			UnclaimedRefunds::<T>::mutate((voting_round_id, who), |refund| *refund = refund.saturating_add(bond));
+		}
+
+		// returns the bonds `who` holds in the round. Voters only get back the bond of the buckets they voted or
+		// committed a vote in, unless the round was encrypted or cancelled. The rest is forfeited to the pallet account
+		fn release_refund(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			who: &AccountIdFor<T>,
+		) -> Result<BalanceOf<T>, Error<T>> {
+			if !UnclaimedRefunds::<T>::contains_key((voting_round_id, who)) {
+				Err(Error::<T>::NothingToClaim)?
+			}
+			let held = UnclaimedRefunds::<T>::take((voting_round_id, who));
+
+			let mut forfeited: BalanceOf<T> = 0u32.into();
+			if voting_round.phase != VotingPhases::Cancelled && voting_round.ballot != BallotMode::Encrypted {
+				let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+				for bucket_id in 0..=T::BucketSize::get() {
+					let bond = match VotersForBucket::<T>::get((voting_round_id, bucket_id, who)) {
+						Some(bond) => bond,
+						None => continue,
+					};
+					let voted = CommittedVoters::<T>::contains_key((voting_round_id, bucket_id, who)) ||
+						proposals.iter().enumerate().any(|(i, proposal)| {
+							proposal.bucket_id == Some(bucket_id) &&
+								VotersVotedOnProposal::<T>::contains_key((voting_round_id, i as ProposalCount, who))
+						});
+					if !voted {
+						forfeited = forfeited.saturating_add(match voting_round.mode {
+							// contributions have already left the reserve
+							RoundMode::Funding { .. } => bond.remaining,
+							RoundMode::Governance => bond.total,
+						});
+					}
+				}
+			}
+
+			// what cannot be moved to the pallet account is returned, so that nothing stays reserved
+			let forfeited = forfeited.min(held);
+			let unpaid = if forfeited.is_zero() {
+				forfeited
+			} else {
+				T::Token::repatriate_reserved(who, &Self::account_id(), forfeited, BalanceStatus::Free).unwrap_or(forfeited)
+			};
+			let forfeited = forfeited - unpaid;
+			let refund = held - forfeited;
+			T::Token::unreserve(who, refund);
+			Self::deposit_event(Event::BondRefunded { round: voting_round_id, who: who.clone(), amount: refund, forfeited });
+			Ok(refund)
+		}
+
+		// a round can be reaped once it is over, and either `RoundsToKeep` rounds were started after it, or
+		// `RetentionPeriod` blocks went by since it was finalized. The latest round is always kept
+		fn is_reapable(
+			voting_round_id: VotingRoundId,
+			voting_round: &VotingRoundMetadataOf<T>,
+			block_number: BlockNumberFor<T>,
+			latest_voting_round_id: VotingRoundId,
+		) -> bool {
+			let over = match voting_round.phase {
+				VotingPhases::Finalized | VotingPhases::Cancelled => true,
+				VotingPhases::Proposal |
+				VotingPhases::PreVoting |
+				VotingPhases::Voting |
+				VotingPhases::Reveal |
+				VotingPhases::Tallying |
+				VotingPhases::PostVoting |
+				VotingPhases::Settling |
+				VotingPhases::Enactment => false,
+			};
+			over && voting_round_id < latest_voting_round_id &&
+				(latest_voting_round_id - voting_round_id >= T::RoundsToKeep::get() ||
+					block_number >= voting_round.finalized_block.saturating_add(T::RetentionPeriod::get()))
+		}
+
+		fn reap_step_weight() -> Weight {
+			T::WeightInfo::summarize_round(T::MaxProposals::get(), T::MaxVotes::get())
+				.max(T::WeightInfo::reap_round(T::MaxRemovalsPerReap::get()))
+		}
+
+		// one step of reaping a round. The summary of the round is written first. Then the bonds that were never
+		// claimed are released, and the data of the round is removed, `MaxRemovalsPerReap` entries at a time.
+		// Returns the weight of the step, and whether the round is gone
+		fn reap_step(voting_round_id: VotingRoundId, voting_round: &VotingRoundMetadataOf<T>) -> (Weight, bool) {
+			if !RoundSummaries::<T>::contains_key(voting_round_id) {
+				let (proposal_count, votes) = Self::summarize(voting_round_id, voting_round);
+				return (T::WeightInfo::summarize_round(proposal_count, votes), false)
+			}
+
+			let limit = T::MaxRemovalsPerReap::get();
+			// the refunds are computed from the data below, so they go first
+			let unclaimed: Vec<AccountIdFor<T>> =
+				UnclaimedRefunds::<T>::iter_key_prefix((voting_round_id,)).take(limit as usize).collect();
+			for who in &unclaimed {
+				let _ = Self::release_refund(voting_round_id, voting_round, who);
+			}
+			let mut budget = limit - unclaimed.len() as u32;
+
+			let prefix = (voting_round_id,);
+			Self::drain(VotersForBucket::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(BucketCredits::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(VotersVotedOnProposal::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(VoteCommitments::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(CommittedVoters::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(VotersVoted::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(Contributions::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(ProposalOutcome::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(VotingKeys::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(VoterMessageCount::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(ThresholdBallots::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(DecryptionShares::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(SharesSubmitted::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(Committees::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(BucketTallySubmissions::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(ProposedBucketTallies::<T>::drain_prefix(prefix), &mut budget);
+			Self::drain(BucketTallies::<T>::drain_prefix(prefix), &mut budget);
+
+			// everything above is gone once a step does not use up its budget
+			let done = budget > 0;
+			if done {
+				ProposalsForVotingRound::<T>::remove(voting_round_id);
+				RoundCoordinator::<T>::remove(voting_round_id);
+				SignUpRoot::<T>::remove(voting_round_id);
+				MessageRoot::<T>::remove(voting_round_id);
+				MessageCount::<T>::remove(voting_round_id);
+				CoordinatorTally::<T>::remove(voting_round_id);
+				RoundThresholdKey::<T>::remove(voting_round_id);
+				ShareSubmitters::<T>::remove(voting_round_id);
+				DecryptionCursor::<T>::remove(voting_round_id);
+				TallyCursor::<T>::remove(voting_round_id);
+				SettlementProgress::<T>::remove(voting_round_id);
+				RoundRandomness::<T>::remove(voting_round_id);
+				VotingRounds::<T>::remove(voting_round_id);
+				Self::deposit_event(Event::RoundReaped { round: voting_round_id });
+			}
+			(T::WeightInfo::reap_round(limit - budget), done)
+		}
+
+		// removes up to `budget` entries, and takes them off the budget
+		fn drain<I: Iterator>(entries: I, budget: &mut u32) {
+			*budget -= entries.take(*budget as usize).count() as u32;
+		}
+
+		// writes the summary of the round. Returns the number of proposals and of votes
+		fn summarize(voting_round_id: VotingRoundId, voting_round: &VotingRoundMetadataOf<T>) -> (u32, u32) {
+			let proposals = ProposalsForVotingRound::<T>::get(voting_round_id).unwrap_or_default();
+			let leaves = merkle::vote_leaves::<T::Hashing, _, _, _>(&proposals);
+			let votes = leaves.len() as u32;
+
+			let mut credits: BalanceOf<T> = 0u32.into();
+			for bucket_id in 0..=T::BucketSize::get() {
+				credits = credits.saturating_add(BucketCredits::<T>::get((voting_round_id, bucket_id)));
+			}
+
+			let results = proposals
+				.iter()
+				.enumerate()
+				.map(|(i, proposal)| {
+					let result = ProposalOutcome::<T>::get((voting_round_id, i as ProposalCount));
+					(proposal.initializer.clone(), proposal.class, result)
+				})
+				.collect::<Vec<_>>();
+
+			RoundSummaries::<T>::insert(
+				voting_round_id,
+				RoundSummary {
+					mode: voting_round.mode.clone(),
+					ballot: voting_round.ballot.clone(),
+					phase: voting_round.phase.clone(),
+					finalized_block: voting_round.finalized_block,
+					proposals: BoundedVec::truncate_from(results),
+					credits,
+					votes,
+					votes_root: merkle::root::<T::Hashing>(leaves),
+				},
+			);
+			(proposals.len() as u32, votes)
+		}
+
+		// returns the number of bonds that were returned
+		fn refund_committees(voting_round_id: VotingRoundId) -> u32 {
+			let mut refunds = 0;
+			for (_, members) in Committees::<T>::iter_prefix((voting_round_id,)) {
+				for member in members {
+					refunds += 1;
+					T::Token::unreserve(&member, T::BondForCommittee::get());
+					Self::deposit_event(Event::BondRefunded {
+						round: voting_round_id,
+						who: member,
+						amount: T::BondForCommittee::get(),
+						forfeited: 0u32.into(),
+					});
+				}
+			}
+			refunds
+		}
+
+		// the transcript a committee applicant signs with its VRF key
+		pub fn committee_transcript(
+			voting_round_id: VotingRoundId,
+			bucket_id: BucketId,
+			randomness: &T::Hash,
+		) -> impl SigningTranscript {
+			schnorrkel::signing_context(b"quadratic-voting-committee").bytes(&(voting_round_id, bucket_id, randomness).encode())
+		}
+
+		// an applicant is eligible if the score of its VRF output, read as a fraction of u128::MAX, is below the difficulty
+		pub fn committee_threshold() -> u128 {
+			T::CommitteeDifficulty::get() * u128::MAX
+		}
+
+		// starts the round of the genesis config, and takes it to its voting phase if it opens voting
+		fn start_genesis_round(round: &GenesisRound<AccountIdFor<T>, BalanceOf<T>>) -> DispatchResult {
+			match round.ballot {
+				BallotMode::Open | BallotMode::CommitReveal => {},
+				BallotMode::Encrypted | BallotMode::ThresholdEncrypted => Err(Error::<T>::UnsupportedBallotMode)?,
+			};
+
+			let previous_round_id = match LatestVotingRound::<T>::get() {
+				Some(id) => id,
+				None => 0,
+			};
+			let voting_round_id = previous_round_id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+
+			let bond = T::BondForVotingRound::get();
+			T::Token::reserve(&round.initializer, bond)?;
+			let mut voting_round = make_voting_round_metadata::<T>(
+				round.initializer.clone(),
+				Zero::zero(),
+				previous_round_id,
+				round.mode.clone(),
+				round.ballot.clone(),
+			)?;
+			if let RoundMode::Funding { matching_pool } = round.mode {
+				T::Token::transfer(
+					&round.initializer,
+					&Self::matching_pool_account(voting_round_id),
+					matching_pool,
+					ExistenceRequirement::KeepAlive,
+				)?;
+			}
+			VotingRounds::<T>::insert(voting_round_id, voting_round.clone());
+			LatestVotingRound::<T>::put(voting_round_id);
+			Self::hold_refund(voting_round_id, &round.initializer, bond);
+
+			for (proposer, class) in &round.proposals {
+				Self::submit_proposal(RawOrigin::Signed(proposer.clone()).into(), *class)?;
+			}
+			if !round.open_voting {
+				return Ok(())
+			}
+
+			// there is no randomness to shuffle the proposals with yet, so they keep their order
+			let mut proposals = match ProposalsForVotingRound::<T>::get(voting_round_id) {
+				Some(proposals) => proposals,
+				None => Err(Error::<T>::NoProposals)?,
+			};
+			for (i, proposal) in proposals.iter_mut().enumerate() {
+				proposal.bucket_id = Some(T::BucketSize::get() % ((i as BucketId) + 1));
+			}
+			ProposalsForVotingRound::<T>::insert(voting_round_id, proposals);
+
+			voting_round.phase = VotingPhases::PreVoting;
+			VotingRounds::<T>::insert(voting_round_id, voting_round.clone());
+			for (voter, bucket_id, credits) in &round.registrations {
+				Self::register_to_vote(RawOrigin::Signed(voter.clone()).into(), *bucket_id, *credits)?;
+			}
+
+			// the phases before voting are over, and voting starts with the first block
+			let offset = voting_round.voting_phase.start_block.saturating_sub(One::one());
+			let shift = |phase: &mut VotingPhaseData<BlockNumberFor<T>>| {
+				phase.start_block = phase.start_block.saturating_sub(offset);
+				phase.end_block = phase.end_block.saturating_sub(offset);
+			};
+			shift(&mut voting_round.proposal_phase);
+			shift(&mut voting_round.pre_voting_phase);
+			shift(&mut voting_round.voting_phase);
+			if let Some(reveal_phase) = voting_round.reveal_phase.as_mut() {
+				shift(reveal_phase);
+			}
+			shift(&mut voting_round.post_voting_phase);
+			shift(&mut voting_round.enactment_phase);
+			voting_round.finalized_block = voting_round.finalized_block.saturating_sub(offset);
+			voting_round.phase = VotingPhases::Voting;
+			VotingRounds::<T>::insert(voting_round_id, voting_round);
+
+			Ok(())
+		}
+
+		// the account forfeited bonds are paid to
+		pub fn account_id() -> AccountIdFor<T> {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		pub fn matching_pool_account(voting_round_id: VotingRoundId) -> AccountIdFor<T> {
+			T::PalletId::get().into_sub_account_truncating(voting_round_id)
+		}
+
+		// pays every approved proposal its share of the pool, in proportion to its matching score
+		fn distribute_matching_pool(
+			voting_round_id: VotingRoundId,
+			initializer: &AccountIdFor<T>,
+			proposals: &[Proposal<T::AccountId, BalanceOf<T>, T::MaxVotes>],
+		) {
+			let pool_account = Self::matching_pool_account(voting_round_id);
+			let pool = T::Token::free_balance(&pool_account);
+			let cap = T::MaxMatchingShare::get() * pool;
+
+			let mut contributions = Vec::with_capacity(proposals.len());
+			for i in 0..proposals.len() {
+				let key = (voting_round_id, i as ProposalCount);
+				let approved = match ProposalOutcome::<T>::get(key) {
+					Some(result) => result.outcome == Outcome::Aye,
+					None => false,
+				};
+				contributions.push(match Contributions::<T>::get(key) {
+					Some(contributors) if approved => contributors.into_inner(),
+					_ => Vec::new(),
+				});
+			}
+
+			let scores = T::MatchingFormula::scores(&contributions);
+			let mut total: BalanceOf<T> = 0u32.into();
+			for score in &scores {
+				total = total.saturating_add(*score);
+			}
+
+			if !total.is_zero() {
+				for (i, score) in scores.into_iter().enumerate().take(proposals.len()) {
+					if score.is_zero() {
+						continue
+					}
+					let payout = (Perbill::from_rational(score, total) * pool).min(cap);
+					if T::Token::transfer(
+						&pool_account,
+						&proposals[i].initializer,
+						payout,
+						ExistenceRequirement::AllowDeath,
+					)
+					.is_ok()
+					{
+						Self::deposit_event(Event::MatchingPayout { round: voting_round_id, proposal: i as ProposalCount, amount: payout });
+					}
+				}
+			}
+
+			// whatever was not matched goes back to the initializer of the round
+			let leftover = T::Token::free_balance(&pool_account);
+			let _ = T::Token::transfer(&pool_account, initializer, leftover, ExistenceRequirement::AllowDeath);
+		}
+	}
+
+	pub fn make_voting_round_metadata<T: Config>(
+		initiator: AccountIdFor<T>,
+		start_block: BlockNumberFor<T>,
+		previous_round_id: VotingRoundId,
+		mode: RoundMode<BalanceOf<T>>,
+		ballot: BallotMode,
+	) -> Result<VotingRoundMetadataOf<T>, Error<T>> {
+		let proposal_start = start_block;
+		let proposal_end = start_block + T::BlocksForProposalPhase::get();
+
+		let pre_voting_start = proposal_end + T::OneBlock::get();
+		let pre_voting_end = pre_voting_start + T::BlocksForPreVotingPhase::get();
+
+		let voting_start = pre_voting_end + T::OneBlock::get();
+		let voting_end = voting_start + T::BlocksForVotingPhase::get();
+
+		// commit-reveal and encrypted rounds open their votes before they are tallied
+		let (reveal_phase, tally_block) = match ballot {
+			BallotMode::CommitReveal | BallotMode::Encrypted | BallotMode::ThresholdEncrypted => {
+				let reveal_start = voting_end + T::OneBlock::get();
+				let reveal_end = reveal_start + T::BlocksForRevealPhase::get();
+				(Some(VotingPhaseData::<BlockNumberFor<T>> { start_block: reveal_start, end_block: reveal_end }), reveal_end)
+			},
+			BallotMode::Open => (None, voting_end),
+		};
+
+		let post_voting_start = tally_block + T::OneBlock::get();
+		let post_voting_end = post_voting_start + T::BlocksForPostVotingPhase::get();
+
+		let enactment_start = post_voting_end + T::OneBlock::get();
+		let enactment_end = enactment_start + T::BlocksForEnactmentPhase::get();
+
+		let finalized = enactment_end + T::OneBlock::get();
+
+		return Ok(VotingRoundMetadataOf::<T> {
+			initializer: initiator,
+			phase: VotingPhases::Proposal,
+			previous_round_id,
+			proposal_phase: VotingPhaseData::<BlockNumberFor<T>> {
+				start_block: proposal_start,
+				end_block: proposal_end,
+			},
+			pre_voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
+				start_block: pre_voting_start,
+				end_block: pre_voting_end,
+			},
+			voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
+				start_block: voting_start,
+				end_block: voting_end,
+			},
+			reveal_phase,
+			post_voting_phase: VotingPhaseData::<BlockNumberFor<T>> {
+				start_block: post_voting_start,
+				end_block: post_voting_end,
+			},
+			enactment_phase: VotingPhaseData::<BlockNumberFor<T>> {
+				start_block: enactment_start,
+				end_block: enactment_end,
+			},
+			finalized_block: finalized,
+			quorum: T::Quorum::get(),
+			mode,
+			ballot,
+		})
+	}
+
+	// little-endian bytes, zero padded or truncated to 32 bytes
+	pub fn field_element(bytes: &[u8]) -> [u8; 32] {
+		let mut element = [0u8; 32];
+		let len = bytes.len().min(32);
+		element[..len].copy_from_slice(&bytes[..len]);
+		element
 	}
 
 	pub fn get_vote_from_bond<T: Config>(bond: BalanceOf<T>)-> Result<BalanceOf<T>, Error<T>>{
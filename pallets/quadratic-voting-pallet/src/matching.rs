@@ -0,0 +1,90 @@
+// Formulas that split the matching pool of a funding round between its proposals.
+use frame_support::traits::Get;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, IntegerSquareRoot, Saturating, Zero},
+	Perbill,
+};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, vec::Vec};
+
+pub trait MatchingFormula<AccountId, Balance> {
+	// the score of every proposal, given its `(contributor, amount)` pairs. Proposals are matched in
+	// proportion to their score
+	fn scores(contributions: &[Vec<(AccountId, Balance)>]) -> Vec<Balance>;
+}
+
+// plain quadratic funding, where the score of a proposal is `(Σ√c)²`
+pub struct Quadratic;
+
+impl<AccountId, Balance> MatchingFormula<AccountId, Balance> for Quadratic
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+{
+	fn scores(contributions: &[Vec<(AccountId, Balance)>]) -> Vec<Balance> {
+		contributions
+			.iter()
+			.map(|proposal| {
+				let root_sum = proposal
+					.iter()
+					.fold(Balance::zero(), |sum, (_, amount)| sum.saturating_add(amount.integer_sqrt()));
+				root_sum.saturating_mul(root_sum)
+			})
+			.collect()
+	}
+}
+
+// pairwise-bounded quadratic funding. Every pair of contributors `i, j` of a proposal adds `2√(cᵢcⱼ)` to
+// its score, scaled down by `M / (M + kᵢⱼ)`, where `kᵢⱼ` is the sum of `√(cᵢcⱼ)` over all proposals both
+// of them backed, so a pair that keeps backing the same proposals is matched less and less. `M` is the
+// bound, usually in the order of a single contribution. This is `O(proposals × contributors²)`, so its
+// input must be bounded
+pub struct Pairwise<M>(PhantomData<M>);
+
+impl<AccountId, Balance, M> MatchingFormula<AccountId, Balance> for Pairwise<M>
+where
+	AccountId: Ord + Clone,
+	Balance: AtLeast32BitUnsigned + Copy,
+	M: Get<Balance>,
+{
+	fn scores(contributions: &[Vec<(AccountId, Balance)>]) -> Vec<Balance> {
+		let bound = M::get();
+
+		// how much every pair of contributors has backed the same proposals
+		let mut pairs = BTreeMap::<(AccountId, AccountId), Balance>::new();
+		for_each_pair(contributions, |pair, root_product| {
+			let k = pairs.entry(pair).or_insert_with(Zero::zero);
+			*k = k.saturating_add(root_product);
+		});
+
+		let mut scores = Vec::with_capacity(contributions.len());
+		for proposal in contributions {
+			let mut score = Balance::zero();
+			for_each_pair(sp_std::slice::from_ref(proposal), |pair, root_product| {
+				let k = pairs.get(&pair).copied().unwrap_or_else(Zero::zero);
+				let term = root_product.saturating_add(root_product);
+				score = score.saturating_add(Perbill::from_rational(bound, bound.saturating_add(k)) * term);
+			});
+			scores.push(score);
+		}
+		scores
+	}
+}
+
+// calls `f` with every (ordered) pair of distinct contributors of each proposal, and `√cᵢ × √cⱼ`
+fn for_each_pair<AccountId, Balance, F>(contributions: &[Vec<(AccountId, Balance)>], mut f: F)
+where
+	AccountId: Ord + Clone,
+	Balance: AtLeast32BitUnsigned + Copy,
+	F: FnMut((AccountId, AccountId), Balance),
+{
+	for proposal in contributions {
+		for (i, (a, a_amount)) in proposal.iter().enumerate() {
+			for (b, b_amount) in proposal.iter().skip(i + 1) {
+				if a == b {
+					continue
+				}
+				let pair = if a < b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
+				f(pair, a_amount.integer_sqrt().saturating_mul(b_amount.integer_sqrt()));
+			}
+		}
+	}
+}
@@ -0,0 +1,105 @@
+// A binary merkle tree over the votes of a round. Its root is kept in the summary of the round once the
+// votes themselves are reaped, so that any of them can still be proven.
+//
+// Nodes are hashed in pairs, and the last node of a layer with an odd number of nodes is carried up as is.
+use crate::{Proposal, ProposalCount, VoteDirection};
+use codec::Encode;
+use frame_support::traits::Get;
+use sp_runtime::traits::Hash;
+use sp_std::vec::Vec;
+
+// the leaf of the `index`-th vote in the given direction on a proposal
+pub fn vote_leaf<H: Hash, Balance: Encode>(
+	proposal_id: ProposalCount,
+	direction: &VoteDirection,
+	index: u32,
+	vote: &Balance,
+) -> H::Output {
+	H::hash_of(&(proposal_id, direction, index, vote))
+}
+
+// the leaves of every vote of a round, proposal by proposal, with ayes first, then nays, then abstentions
+pub fn vote_leaves<H, AccountId, Balance, MaxVotes>(proposals: &[Proposal<AccountId, Balance, MaxVotes>]) -> Vec<H::Output>
+where
+	H: Hash,
+	Balance: Encode,
+	MaxVotes: Get<u32>,
+{
+	let mut leaves = Vec::new();
+	for (i, proposal) in proposals.iter().enumerate() {
+		for (direction, votes) in
+			[(VoteDirection::Aye, &proposal.ayes), (VoteDirection::Nay, &proposal.nays), (VoteDirection::Abstain, &proposal.abstains)]
+		{
+			for (index, vote) in votes.iter().enumerate() {
+				leaves.push(vote_leaf::<H, _>(i as ProposalCount, &direction, index as u32, vote));
+			}
+		}
+	}
+	leaves
+}
+
+fn parent<H: Hash>(left: &H::Output, right: &H::Output) -> H::Output {
+	H::hash_of(&(left, right))
+}
+
+fn next_layer<H: Hash>(layer: &[H::Output]) -> Vec<H::Output> {
+	layer
+		.chunks(2)
+		.map(|pair| match pair {
+			[left, right] => parent::<H>(left, right),
+			_ => pair[0],
+		})
+		.collect()
+}
+
+// the root of the tree, or the hash of nothing if there are no leaves
+pub fn root<H: Hash>(leaves: Vec<H::Output>) -> H::Output {
+	let mut layer = leaves;
+	if layer.is_empty() {
+		return H::hash(&[])
+	}
+	while layer.len() > 1 {
+		layer = next_layer::<H>(&layer);
+	}
+	layer[0]
+}
+
+// the siblings of the leaf at `index`, from the bottom of the tree up
+pub fn proof<H: Hash>(leaves: Vec<H::Output>, mut index: usize) -> Vec<H::Output> {
+	let mut proof = Vec::new();
+	let mut layer = leaves;
+	while layer.len() > 1 {
+		let sibling = index ^ 1;
+		if sibling < layer.len() {
+			proof.push(layer[sibling]);
+		}
+		layer = next_layer::<H>(&layer);
+		index /= 2;
+	}
+	proof
+}
+
+// whether `leaf` is the leaf at `index`, in a tree of `count` leaves with the given root
+pub fn verify<H: Hash>(root: &H::Output, leaf: H::Output, mut index: usize, mut count: usize, proof: &[H::Output]) -> bool {
+	if index >= count {
+		return false
+	}
+	let mut node = leaf;
+	let mut siblings = proof.iter();
+	while count > 1 {
+		if index % 2 == 1 {
+			node = match siblings.next() {
+				Some(sibling) => parent::<H>(sibling, &node),
+				None => return false,
+			};
+		} else if index + 1 < count {
+			node = match siblings.next() {
+				Some(sibling) => parent::<H>(&node, sibling),
+				None => return false,
+			};
+		}
+		index /= 2;
+		count = (count + 1) / 2;
+	}
+	siblings.next().is_none() && node == *root
+}
@@ -0,0 +1,260 @@
+//! Storage migrations of the pallet, one module per storage version. A runtime runs them in its `Executive`,
+//! oldest first, and each one only acts on the version it migrates from.
+
+pub mod v1 {
+	use crate::{
+		BallotMode, BalanceOf, BucketCredits, Config, Outcome, Pallet, Proposal, ProposalClass, ProposalOutcome,
+		ProposalResult, ProposalsForVotingRound, RoundMode, TallyResult, Threshold, UnclaimedRefunds, VoterBond,
+		VotersForBucket, VotingPhases, VotingRoundId, VotingRoundMetadata, VotingRounds,
+	};
+	use frame_support::{
+		log,
+		traits::{Currency, Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+		BoundedVec,
+	};
+	#[cfg(feature = "try-runtime")]
+	use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+	use sp_runtime::{traits::Saturating, Perbill};
+	use sp_std::{collections::btree_set::BTreeSet, marker::PhantomData, vec::Vec};
+
+	/// The types of the pallet before storage was versioned, as they were encoded.
+	pub mod v0 {
+		use crate::{BucketId, VotingPhaseData, VotingRoundId};
+		use codec::{Decode, Encode};
+		use sp_std::vec::Vec;
+
+		#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+		pub enum VotingPhases {
+			Proposal,
+			PreVoting,
+			Voting,
+			PostVoting,
+			Enactment,
+			Finalized,
+		}
+
+		#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+		pub enum VoteDirection {
+			Aye,
+			Nay,
+		}
+
+		#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+		pub struct VotingRoundMetadata<AccountId, BlockNumber> {
+			pub initializer: AccountId,
+			pub proposal_phase: VotingPhaseData<BlockNumber>,
+			pub previous_round_id: VotingRoundId,
+			pub pre_voting_phase: VotingPhaseData<BlockNumber>,
+			pub voting_phase: VotingPhaseData<BlockNumber>,
+			pub post_voting_phase: VotingPhaseData<BlockNumber>,
+			pub enactment_phase: VotingPhaseData<BlockNumber>,
+			pub finalized_block: BlockNumber,
+			pub phase: VotingPhases,
+		}
+
+		/// The votes were bounded by `MaxVotes`, which encodes the same as a `Vec`.
+		#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+		pub struct Proposal<AccountId, Balance> {
+			pub initializer: AccountId,
+			pub ayes: Vec<Balance>,
+			pub nays: Vec<Balance>,
+			pub bucket_id: Option<BucketId>,
+		}
+	}
+
+	/// Migrates every storage item whose encoding changed since v0:
+	///
+	/// - rounds gain a reveal phase, a quorum, a mode and a ballot mode, and their phase moves to the new
+	///   [`VotingPhases`]. v0 rounds were open governance rounds without a quorum.
+	/// - proposals gain a class and abstentions. v0 proposals are [`ProposalClass::General`].
+	/// - outcomes were `(winning votes, direction)`, and are now [`ProposalResult`]s, recounted from the proposal.
+	/// - bonds in `VotersForBucket` were `(total, remaining)` tuples, and are now [`VoterBond`]s.
+	///
+	/// v0 returned the bonds of a round when its post voting phase ended. The bonds of rounds that had not got
+	/// there yet are recorded in `UnclaimedRefunds`, so that they can be claimed, and `BucketCredits` is filled
+	/// in from the bonds of every round.
+	///
+	/// v1 pays forfeited bonds to the pallet account, which its genesis endows with the existential deposit.
+	/// Chains that started on v0 never did, so the migration endows it.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain = Pallet::<T>::on_chain_storage_version();
+			if on_chain != 0 {
+				log::info!(target: "runtime::quadratic-voting", "skipping v1 migration, storage is at {:?}", on_chain);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let (mut reads, mut writes) = (1u64, 1u64);
+
+			let pallet_account = Pallet::<T>::account_id();
+			reads += 1;
+			if T::Token::free_balance(&pallet_account) < T::Token::minimum_balance() {
+				let _ = T::Token::make_free_balance_be(&pallet_account, T::Token::minimum_balance());
+				writes += 1;
+			}
+
+			// rounds whose bonds v0 had not returned yet
+			let mut bonded = BTreeSet::new();
+			VotingRounds::<T>::translate::<v0::VotingRoundMetadata<T::AccountId, T::BlockNumber>, _>(|id, old| {
+				reads += 1;
+				writes += 1;
+				let phase = match old.phase {
+					v0::VotingPhases::Proposal => VotingPhases::Proposal,
+					v0::VotingPhases::PreVoting => VotingPhases::PreVoting,
+					v0::VotingPhases::Voting => VotingPhases::Voting,
+					v0::VotingPhases::PostVoting => VotingPhases::PostVoting,
+					v0::VotingPhases::Enactment => VotingPhases::Enactment,
+					v0::VotingPhases::Finalized => VotingPhases::Finalized,
+				};
+				match phase {
+					VotingPhases::Proposal | VotingPhases::PreVoting | VotingPhases::Voting | VotingPhases::PostVoting => {
+						bonded.insert(id);
+						Self::hold(id, &old.initializer, T::BondForVotingRound::get());
+						writes += 1;
+					},
+					VotingPhases::Reveal |
+					VotingPhases::Tallying |
+					VotingPhases::Settling |
+					VotingPhases::Enactment |
+					VotingPhases::Finalized |
+					VotingPhases::Cancelled => (),
+				}
+				Some(VotingRoundMetadata {
+					initializer: old.initializer,
+					proposal_phase: old.proposal_phase,
+					previous_round_id: old.previous_round_id,
+					pre_voting_phase: old.pre_voting_phase,
+					voting_phase: old.voting_phase,
+					reveal_phase: None,
+					post_voting_phase: old.post_voting_phase,
+					enactment_phase: old.enactment_phase,
+					finalized_block: old.finalized_block,
+					phase,
+					quorum: Perbill::zero(),
+					mode: RoundMode::Governance,
+					ballot: BallotMode::Open,
+				})
+			});
+
+			ProposalsForVotingRound::<T>::translate::<Vec<v0::Proposal<T::AccountId, BalanceOf<T>>>, _>(|id, old| {
+				reads += 1;
+				writes += 1;
+				let proposals = old
+					.into_iter()
+					.map(|proposal| {
+						if bonded.contains(&id) {
+							Self::hold(id, &proposal.initializer, T::BondForProposal::get());
+							writes += 1;
+						}
+						Proposal {
+							initializer: proposal.initializer,
+							class: ProposalClass::General,
+							ayes: BoundedVec::truncate_from(proposal.ayes),
+							nays: BoundedVec::truncate_from(proposal.nays),
+							abstains: Default::default(),
+							bucket_id: proposal.bucket_id,
+						}
+					})
+					.collect::<Vec<_>>();
+				Some(BoundedVec::truncate_from(proposals))
+			});
+
+			// the outcome is kept as v0 decided it, with a simple majority
+			ProposalOutcome::<T>::translate::<(BalanceOf<T>, v0::VoteDirection), _>(|(id, proposal_id), (_, direction)| {
+				reads += 2;
+				writes += 1;
+				let proposals = ProposalsForVotingRound::<T>::get(id).unwrap_or_default();
+				let result = match proposals.get(proposal_id as usize) {
+					Some(proposal) => Pallet::<T>::count(proposal),
+					None => TallyResult::default(),
+				};
+				Some(ProposalResult {
+					ayes: result.ayes,
+					nays: result.nays,
+					abstains: result.abstains,
+					credits: result.credits,
+					threshold: Threshold::SimpleMajority,
+					outcome: match direction {
+						v0::VoteDirection::Aye => Outcome::Aye,
+						v0::VoteDirection::Nay => Outcome::Nay,
+					},
+				})
+			});
+
+			VotersForBucket::<T>::translate::<(BalanceOf<T>, BalanceOf<T>), _>(|(id, bucket_id, who), (total, remaining)| {
+				reads += 1;
+				writes += 2;
+				BucketCredits::<T>::mutate((id, bucket_id), |credits| *credits = credits.saturating_add(total));
+				if bonded.contains(&id) {
+					Self::hold(id, &who, total);
+					writes += 1;
+				}
+				Some(VoterBond { total, remaining })
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			log::info!(
+				target: "runtime::quadratic-voting",
+				"migrated storage to v1, {} rounds still hold their bonds",
+				bonded.len()
+			);
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return Ok(())
+			}
+			// the keys do not change, so counting them is enough to catch a value that failed to decode
+			let counts = (
+				VotingRounds::<T>::iter_keys().count() as u32,
+				ProposalsForVotingRound::<T>::iter_keys().count() as u32,
+				ProposalOutcome::<T>::iter_keys().count() as u32,
+				VotersForBucket::<T>::iter_keys().count() as u32,
+			);
+			Self::set_temp_storage(counts, "counts");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return Err("storage version was not set to 1")
+			}
+			if let Some((rounds, proposals, outcomes, bonds)) = Self::get_temp_storage::<(u32, u32, u32, u32)>("counts") {
+				if VotingRounds::<T>::iter_values().count() as u32 != rounds {
+					return Err("some rounds were lost")
+				}
+				if ProposalsForVotingRound::<T>::iter_values().count() as u32 != proposals {
+					return Err("some proposals were lost")
+				}
+				if ProposalOutcome::<T>::iter_values().count() as u32 != outcomes {
+					return Err("some outcomes were lost")
+				}
+				if VotersForBucket::<T>::iter_values().count() as u32 != bonds {
+					return Err("some voter bonds were lost")
+				}
+			}
+			for bond in VotersForBucket::<T>::iter_values() {
+				if bond.remaining > bond.total {
+					return Err("a voter bond has more remaining than bonded")
+				}
+			}
+			if T::Token::free_balance(&Pallet::<T>::account_id()) < T::Token::minimum_balance() {
+				return Err("the pallet account was not endowed")
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> MigrateToV1<T> {
+		fn hold(id: VotingRoundId, who: &T::AccountId, bond: BalanceOf<T>) {
+			UnclaimedRefunds::<T>::mutate((id, who), |refund| *refund = refund.saturating_add(bond));
+		}
+	}
+}
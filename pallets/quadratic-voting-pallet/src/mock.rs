@@ -1,17 +1,24 @@
 use crate as quadratic_voting_pallet;
+#[cfg(feature = "runtime-benchmarks")]
+use codec::Decode;
 use frame_support::{
 	pallet_prelude::EnsureOrigin,
 	parameter_types,
-	traits::{ConstU128, ConstU16, ConstU32, ConstU64, OnFinalize, OnInitialize},
+	traits::{ConstU128, ConstU16, ConstU32, ConstU64, Get, OnFinalize, OnInitialize},
+	PalletId,
 };
 use frame_support_test::TestRandomness;
+use quadratic_voting_pallet::{matching::Quadratic, verifier::Groth16Bn254, ProposalClass, Threshold, UnrevealedPolicy};
 use frame_system as system;
 use frame_system::RawOrigin;
 use sp_core::H256;
+use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey};
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+	Perbill,
 };
+use std::cell::RefCell;
 use system::EnsureRoot;
 
 type Balance = u128;
@@ -98,9 +105,16 @@ parameter_types! {
 	pub const BlocksForVotingPhase: u64 = 10;
 	pub const BlocksForPostVotingPhase: u64 = 10;
 	pub const BlocksForEnactmentPhase: u64 = 10;
+	pub const BlocksForRevealPhase: u64 = 10;
 	pub const MaxProposals: u32 = 10;
 	pub const OneBlock: u64 = 1;
 	pub const BlocksForProposalPhase: u64 = 10;
+	pub const Quorum: Perbill = Perbill::from_percent(50);
+	pub const QuadraticVotingPalletId: PalletId = PalletId(*b"py/qvote");
+	pub const MaxMatchingShare: Perbill = Perbill::from_percent(60);
+	pub const RefundTip: Perbill = Perbill::from_percent(10);
+	pub const RoundsToKeep: u32 = 3;
+	pub const RetentionPeriod: u64 = 10;
 }
 
 impl quadratic_voting_pallet::Config for Test {
@@ -111,6 +125,7 @@ impl quadratic_voting_pallet::Config for Test {
 	type BlocksForPreVotingPhase = BlocksForPreVotingPhase;
 	type BlocksForProposalPhase = BlocksForProposalPhase;
 	type BlocksForEnactmentPhase = BlocksForEnactmentPhase;
+	type BlocksForRevealPhase = BlocksForRevealPhase;
 	type MaxProposals = MaxProposals;
 	type Token = Balances;
 	type BondForVotingRound = ConstU128<1000>;
@@ -120,6 +135,139 @@ impl quadratic_voting_pallet::Config for Test {
 	type MaxVotes = ConstU32<1000>;
 	type Randomness = TestRandomness<Self>;
 	type BucketSize = ConstU32<5>;
+	type Quorum = Quorum;
+	type ClassThreshold = ClassThreshold;
+	type PalletId = QuadraticVotingPalletId;
+	type MaxMatchingShare = MaxMatchingShare;
+	type MatchingFormula = Quadratic;
+	type MaxContributorsPerProposal = ConstU32<100>;
+	type UnrevealedCommits = UnrevealedCommits;
+	type TallyVerifier = Groth16Bn254<TallyVerifyingKey>;
+	type MaxMessageLength = ConstU32<256>;
+	type MaxMessages = ConstU32<100>;
+	type MaxMessagesPerVoter = ConstU32<2>;
+	type MaxProofLength = ConstU32<256>;
+	type DecryptionOrigin = EnsureAlice;
+	type MaxCommitteeMembers = ConstU32<5>;
+	type MaxSharesPerCall = ConstU32<2>;
+	type MaxDecryptionsPerBlock = ConstU32<1>;
+	type MaxTalliesPerBlock = MaxTalliesPerBlock;
+	type MaxRefundsPerBlock = ConstU32<100>;
+	type RefundTip = RefundTip;
+	type RoundsToKeep = RoundsToKeep;
+	type RetentionPeriod = RetentionPeriod;
+	type MaxRemovalsPerReap = ConstU32<5>;
+	type VrfKey = VrfKeys;
+	type CommitteeDifficulty = CommitteeDifficulty;
+	type BondForCommittee = ConstU128<5>;
+	type MaxBucketCommitteeSize = ConstU32<2>;
+	type ChallengePeriod = ConstU64<5>;
+	type BondForChallenge = ConstU128<3>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = BenchmarkHelper;
+}
+
+thread_local! {
+	static TALLY_VERIFYING_KEY: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+	static COMMITTEE_DIFFICULTY: RefCell<Perbill> = RefCell::new(Perbill::one());
+	static TALLIES_PER_BLOCK: RefCell<u32> = RefCell::new(10);
+	static UNREVEALED_COMMITS: RefCell<UnrevealedPolicy> = RefCell::new(UnrevealedPolicy::Slash);
+}
+
+// tests set up the fixture circuit, and install its verifying key here
+pub struct TallyVerifyingKey;
+impl TallyVerifyingKey {
+	pub fn set(key: Vec<u8>) {
+		TALLY_VERIFYING_KEY.with(|k| *k.borrow_mut() = key);
+	}
+}
+impl Get<Vec<u8>> for TallyVerifyingKey {
+	fn get() -> Vec<u8> {
+		TALLY_VERIFYING_KEY.with(|k| k.borrow().clone())
+	}
+}
+
+pub struct ClassThreshold;
+impl Convert<ProposalClass, Threshold<Balance>> for ClassThreshold {
+	fn convert(class: ProposalClass) -> Threshold<Balance> {
+		match class {
+			ProposalClass::General => Threshold::SimpleMajority,
+			ProposalClass::TreasurySpend => Threshold::SuperMajority(Perbill::from_percent(75)),
+			ProposalClass::RuntimeParameter => Threshold::AbsoluteMinimum(3),
+		}
+	}
+}
+
+// every account but 5 has a VRF key, derived from its id
+pub fn vrf_keypair(who: AccountId) -> Keypair {
+	MiniSecretKey::from_bytes(&[who as u8; 32]).unwrap().expand_to_keypair(ExpansionMode::Ed25519)
+}
+
+pub struct VrfKeys;
+impl Convert<AccountId, Option<[u8; 32]>> for VrfKeys {
+	fn convert(who: AccountId) -> Option<[u8; 32]> {
+		match who {
+			5 => None,
+			_ => Some(vrf_keypair(who).public.to_bytes()),
+		}
+	}
+}
+
+// every applicant is eligible, unless a test says otherwise
+pub struct CommitteeDifficulty;
+impl CommitteeDifficulty {
+	pub fn set(difficulty: Perbill) {
+		COMMITTEE_DIFFICULTY.with(|d| *d.borrow_mut() = difficulty);
+	}
+}
+impl Get<Perbill> for CommitteeDifficulty {
+	fn get() -> Perbill {
+		COMMITTEE_DIFFICULTY.with(|d| *d.borrow())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl quadratic_voting_pallet::BenchmarkHelper<AccountId> for BenchmarkHelper {
+	fn vrf_account() -> AccountId {
+		1
+	}
+
+	fn vrf_secret_key() -> [u8; 64] {
+		vrf_keypair(1).secret.to_bytes()
+	}
+
+	fn set_tally_verifying_key(key: Vec<u8>) {
+		TallyVerifyingKey::set(key)
+	}
+}
+
+// rounds are tallied in one block, unless a test says otherwise
+pub struct MaxTalliesPerBlock;
+impl MaxTalliesPerBlock {
+	pub fn set(tallies: u32) {
+		TALLIES_PER_BLOCK.with(|t| *t.borrow_mut() = tallies);
+	}
+}
+impl Get<u32> for MaxTalliesPerBlock {
+	fn get() -> u32 {
+		TALLIES_PER_BLOCK.with(|t| *t.borrow())
+	}
+}
+
+// unrevealed commitments are slashed, unless a test says otherwise
+pub struct UnrevealedCommits;
+impl UnrevealedCommits {
+	pub fn set(policy: UnrevealedPolicy) {
+		UNREVEALED_COMMITS.with(|p| *p.borrow_mut() = policy);
+	}
+}
+impl Get<UnrevealedPolicy> for UnrevealedCommits {
+	fn get() -> UnrevealedPolicy {
+		UNREVEALED_COMMITS.with(|p| p.borrow().clone())
+	}
 }
 
 pub struct EnsureAlice;
@@ -146,7 +294,8 @@ impl EnsureOrigin<Origin> for EnsureAlice {
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
-		balances: vec![(1, 1 << 100), (2, 1 << 100), (3, 10), (4, 10), (5, 2)],
+		// the pallet account is endowed by the genesis of the pallet, which most tests do not build
+		balances: vec![(1, 1 << 100), (2, 1 << 100), (3, 10), (4, 10), (5, 2), (QuadraticVotingPallet::account_id(), 1)],
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
@@ -162,5 +311,6 @@ pub fn run_to_block(n: u64) {
 		System::set_block_number(System::block_number() + 1);
 		System::on_initialize(System::block_number());
 		quadratic_voting_pallet::pallet::Pallet::<Test>::on_initialize(System::block_number());
+		quadratic_voting_pallet::pallet::Pallet::<Test>::do_try_state().expect("the invariants hold after every block");
 	}
 }
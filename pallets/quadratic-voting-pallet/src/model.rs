@@ -0,0 +1,195 @@
+//! A model of the voting state machine over the mock runtime, for property tests and fuzzing.
+//!
+//! It applies a sequence of [`Action`]s to a fresh mock chain, dispatching calls the way a block would, and checks
+//! after every action and every block that funds are conserved, that rounds only move forward through their phases,
+//! and that the invariants of [`Pallet::do_try_state`](crate::Pallet::do_try_state) hold.
+
+use crate::{mock::*, BallotMode, ProposalClass, RoundMode, VoteDirection, VotingPhases, VotingRoundId, VotingRounds};
+use frame_support::traits::{Currency, OnIdle};
+use pallet_identity::{Data, IdentityInfo};
+use sp_runtime::traits::Dispatchable;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// Accounts the actions are taken by, from 1 up to this one.
+pub const ACCOUNTS: u8 = 6;
+
+/// What every account is endowed with.
+pub const ENDOWMENT: u128 = 1_000_000;
+
+/// A step of the model. Fields are taken modulo their range, so that any value is a valid action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Action {
+	/// Starts a round, a funding round if a matching pool is given.
+	StartVotingRound { who: u8, matching_pool: Option<u16> },
+	SubmitProposal { who: u8, class: u8 },
+	RegisterToVote { who: u8, bucket_id: u8, credits: u16 },
+	Vote { who: u8, proposal_id: u8, credits: u16, direction: u8 },
+	/// Advances by one to 32 blocks.
+	AdvanceBlocks(u8),
+}
+
+impl Action {
+	// the signer and call of the action, unless it advances blocks
+	fn call(&self) -> Option<(AccountId, Call)> {
+		let account = |who: &u8| (who % ACCOUNTS + 1) as AccountId;
+		let call = match self {
+			Action::StartVotingRound { who, matching_pool } => (
+				account(who),
+				crate::Call::start_voting_round {
+					mode: match matching_pool {
+						Some(matching_pool) => RoundMode::Funding { matching_pool: (*matching_pool).into() },
+						None => RoundMode::Governance,
+					},
+					ballot: BallotMode::Open,
+				},
+			),
+			Action::SubmitProposal { who, class } => (
+				account(who),
+				crate::Call::submit_proposal {
+					class: match class % 3 {
+						0 => ProposalClass::General,
+						1 => ProposalClass::TreasurySpend,
+						_ => ProposalClass::RuntimeParameter,
+					},
+				},
+			),
+			Action::RegisterToVote { who, bucket_id, credits } => (
+				account(who),
+				// one past the last bucket, to try an invalid one
+				crate::Call::register_to_vote { bucket_id: (bucket_id % 7).into(), votes: (*credits).into() },
+			),
+			Action::Vote { who, proposal_id, credits, direction } => (
+				account(who),
+				crate::Call::vote {
+					proposal_id: (proposal_id % 11).into(),
+					vote: (*credits).into(),
+					direction: match direction % 3 {
+						0 => VoteDirection::Aye,
+						1 => VoteDirection::Nay,
+						_ => VoteDirection::Abstain,
+					},
+				},
+			),
+			Action::AdvanceBlocks(_) => return None,
+		};
+		Some((call.0, Call::QuadraticVotingPallet(call.1)))
+	}
+}
+
+// the order rounds go through their phases in
+fn rank(phase: &VotingPhases) -> u8 {
+	match phase {
+		VotingPhases::Proposal => 0,
+		VotingPhases::PreVoting => 1,
+		VotingPhases::Voting => 2,
+		VotingPhases::Reveal => 3,
+		VotingPhases::Tallying => 4,
+		VotingPhases::PostVoting => 5,
+		VotingPhases::Settling => 6,
+		VotingPhases::Enactment => 7,
+		VotingPhases::Finalized => 8,
+		VotingPhases::Cancelled => 9,
+	}
+}
+
+// a round that has been reaped
+const REAPED: u8 = u8::MAX;
+
+#[derive(Default)]
+struct Observed {
+	latest_voting_round_id: VotingRoundId,
+	// the latest rank of every round seen so far
+	phases: BTreeMap<VotingRoundId, u8>,
+}
+
+impl Observed {
+	fn check(&mut self, issuance: u128) -> Result<(), String> {
+		// every transfer, bond and payout moves funds around, none mint or burn them
+		let held: u128 = frame_system::Account::<Test>::iter().map(|(_, account)| account.data.free + account.data.reserved).sum();
+		if Balances::total_issuance() != issuance || held != issuance {
+			return Err(format!(
+				"funds are not conserved: {} issued at genesis, {} issued now, {} held by accounts",
+				issuance,
+				Balances::total_issuance(),
+				held
+			))
+		}
+
+		let latest_voting_round_id = QuadraticVotingPallet::latest_voting_round().unwrap_or(0);
+		if latest_voting_round_id < self.latest_voting_round_id {
+			return Err(format!("the latest round went back from {} to {}", self.latest_voting_round_id, latest_voting_round_id))
+		}
+		self.latest_voting_round_id = latest_voting_round_id;
+
+		let mut phases: BTreeMap<VotingRoundId, u8> =
+			VotingRounds::<Test>::iter().map(|(id, round)| (id, rank(&round.phase))).collect();
+		for (id, seen) in &self.phases {
+			let now = *phases.entry(*id).or_insert(REAPED);
+			if now < *seen {
+				return Err(format!("round {} went back from phase {} to phase {}", id, seen, now))
+			}
+		}
+		self.phases = phases;
+
+		QuadraticVotingPallet::do_try_state().map_err(Into::into)
+	}
+}
+
+// the mock chain, with every account endowed and backed by an identity
+fn new_ext() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for who in 1..=ACCOUNTS as AccountId {
+			let _ = Balances::deposit_creating(&who, ENDOWMENT);
+			let info = IdentityInfo {
+				legal: Data::None,
+				display: Data::None,
+				email: Data::None,
+				image: Data::None,
+				twitter: Data::None,
+				riot: Data::None,
+				web: Data::None,
+				additional: Default::default(),
+				pgp_fingerprint: None,
+			};
+			Identity::set_identity(Origin::signed(who), Box::new(info)).expect("accounts can pay the deposit");
+		}
+	});
+	ext
+}
+
+/// Applies the actions to a fresh mock chain, and returns the first check that failed, with the action it failed
+/// after.
+pub fn run(actions: &[Action]) -> Result<(), String> {
+	new_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+		let mut observed = Observed::default();
+		observed.check(issuance)?;
+
+		for (step, action) in actions.iter().enumerate() {
+			let failed = |e: String| format!("after action {} ({:?}): {}", step, action, e);
+			match action.call() {
+				// calls that fail are rolled back, like in a block
+				Some((who, call)) => {
+					let _ = call.dispatch(Origin::signed(who));
+				},
+				None => {
+					let blocks = match action {
+						Action::AdvanceBlocks(blocks) => blocks % 32 + 1,
+						_ => 0,
+					};
+					for _ in 0..blocks {
+						let block = System::block_number() + 1;
+						run_to_block(block);
+						QuadraticVotingPallet::on_idle(block, u64::MAX);
+						observed.check(issuance).map_err(&failed)?;
+					}
+				},
+			}
+			observed.check(issuance).map_err(&failed)?;
+		}
+		Ok(())
+	})
+}
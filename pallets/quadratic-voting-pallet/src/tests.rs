@@ -1,7 +1,24 @@
-use crate::{mock::*, Error, ProposalsForVotingRound, VotersForBucket, VotingPhases, VotingRounds, VoteDirection, VotersVotedOnProposal, ProposalOutcome, ProposalCount};
-use frame_support::{assert_noop, assert_ok};
-use pallet_identity::{IdentityInfo};
-use sp_runtime::traits::ConstU32;
+use crate::{mock::*, Error, UnrevealedPolicy, ProposalsForVotingRound, VotersForBucket, VoterBond, VoterStatus, ProposalInfo, VotingPhases, VotingRounds, VoteDirection, VotersVotedOnProposal, ProposalOutcome, ProposalCount, Outcome, BucketCredits, ProposalClass, ProposalResult, Threshold, RoundMode, BallotMode, EncryptedMessage, TallyResult, Event as QuadraticVotingEvent, GenesisConfig, GenesisRound, RoundRandomness, UnclaimedRefunds, LatestVotingRound, Coordinator, VotersVoted, Committees};
+use crate::threshold;
+use crate::fixture::{self, TallyCircuit};
+use crate::matching::{MatchingFormula, Pairwise, Quadratic};
+use crate::merkle;
+use crate::migrations::v1::MigrateToV1;
+use crate::model::{self, Action};
+use codec::Encode;
+use curve25519_dalek::scalar::Scalar;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{ConstU128, Currency, GenesisBuild, Get, GetStorageVersion, OnIdle, OnRuntimeUpgrade, StorageVersion},
+	BoundedVec,
+};
+use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+use pallet_identity::{IdentityInfo, Judgement};
+use proptest::prelude::*;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+use sp_runtime::{traits::{BlakeTwo256, ConstU32}, Perbill};
 
 fn get_default_identity() -> Box<IdentityInfo<ConstU32<2>>> {
 	Box::from(IdentityInfo {
@@ -28,7 +45,7 @@ fn set_identity(id: AccountId) {
 #[test]
 fn can_create_the_first_voting_round() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		assert_eq!(QuadraticVotingPallet::latest_voting_round(), Some(1u32));
 	});
 }
@@ -36,7 +53,7 @@ fn can_create_the_first_voting_round() {
 #[test]
 fn should_not_transition_to_pre_voting_prematurely() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		assert_eq!(QuadraticVotingPallet::latest_voting_round(), Some(1u32));
 		run_to_block(BlocksForPreVotingPhase::get() - 1);
 		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Proposal);
@@ -46,7 +63,7 @@ fn should_not_transition_to_pre_voting_prematurely() {
 #[test]
 fn should_transition_to_pre_voting() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		assert_eq!(QuadraticVotingPallet::latest_voting_round(), Some(1u32));
 		run_to_block(BlocksForPreVotingPhase::get());
 		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PreVoting);
@@ -59,19 +76,27 @@ During the proposal stage, any actor with an identity can propose a thing to be
 #[test]
 fn should_allow_proposal_creation() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		// events are not kept in the genesis block
+		System::set_block_number(1);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		set_identity(1);
-		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1)));
-		assert!(ProposalsForVotingRound::<Test>::get(1u32).is_some())
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General));
+		assert!(ProposalsForVotingRound::<Test>::get(1u32).is_some());
+		System::assert_last_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::ProposalSubmitted {
+			round: 1,
+			proposal: 0,
+			proposer: 1,
+			class: ProposalClass::General,
+		}));
 	})
 }
 
 #[test]
 fn should_not_allow_proposal_creation_by_anon() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		assert_noop!(
-			QuadraticVotingPallet::submit_proposal(Origin::signed(1)),
+			QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General),
 			Error::<Test>::IdentityNotFound,
 		);
 	})
@@ -80,10 +105,10 @@ fn should_not_allow_proposal_creation_by_anon() {
 #[test]
 fn should_allow_multiple_proposal_creation() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		set_identity(1);
 		for _ in 0..MaxProposals::get() - 1 {
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General));
 		}
 		assert!(ProposalsForVotingRound::<Test>::get(1u32).is_some())
 	})
@@ -92,11 +117,11 @@ fn should_allow_multiple_proposal_creation() {
 #[test]
 fn should_not_allow_proposal_creation_during_pre_voting() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		set_identity(1);
 		run_to_block(BlocksForPreVotingPhase::get());
 		assert_noop!(
-			QuadraticVotingPallet::submit_proposal(Origin::signed(1)),
+			QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General),
 			Error::<Test>::CanCallOnlyDuringProposalPhase,
 		);
 	})
@@ -105,14 +130,14 @@ fn should_not_allow_proposal_creation_during_pre_voting() {
 #[test]
 fn should_throw_if_proposal_count_overflows() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		set_identity(1);
 		for _ in 0..MaxProposals::get() {
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General));
 		}
 
 		assert_noop!(
-			QuadraticVotingPallet::submit_proposal(Origin::signed(1)),
+			QuadraticVotingPallet::submit_proposal(Origin::signed(1), ProposalClass::General),
 			Error::<Test>::StorageOverflow,
 		);
 	})
@@ -121,13 +146,13 @@ fn should_throw_if_proposal_count_overflows() {
 #[test]
 fn should_shuffle_on_pre_voting_start() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -143,17 +168,67 @@ fn should_shuffle_on_pre_voting_start() {
 	})
 }
 
+#[test]
+fn should_assign_buckets_in_the_order_drawn_from_the_round_randomness() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		// the recorded randomness is enough to redo the shuffle off chain. The seed is its last byte, repeated
+		let random = RoundRandomness::<Test>::get(1).unwrap();
+		let mut rng = ChaChaRng::from_seed([*random.as_bytes().last().unwrap(); 32]);
+		let mut order: Vec<ProposalCount> = (0..MaxProposals::get()).collect();
+		order.shuffle(&mut rng);
+		let bucket_of = |i: usize| <Test as crate::Config>::BucketSize::get() % (i as u32 + 1);
+		let assigned: Vec<(ProposalCount, ProposalCount, u32)> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				Event::QuadraticVotingPallet(event) => Some(event),
+				_ => None,
+			})
+			.filter_map(|event| {
+				assert_eq!(event.round(), Some(1));
+				match event {
+					QuadraticVotingEvent::BucketAssigned { submitted, proposal, bucket, .. } => Some((submitted, proposal, bucket)),
+					_ => None,
+				}
+			})
+			.collect();
+		let expected: Vec<(ProposalCount, ProposalCount, u32)> = order
+			.iter()
+			.enumerate()
+			.map(|(i, submitted)| (*submitted, i as ProposalCount, bucket_of(i)))
+			.collect();
+		assert_eq!(assigned, expected);
+
+		let proposals = ProposalsForVotingRound::<Test>::get(1u32).unwrap();
+		for (i, proposal) in proposals.iter().enumerate() {
+			assert_eq!(proposal.bucket_id, Some(bucket_of(i)));
+			assert_eq!(proposal.initializer, (order[i] % 2 + 1) as AccountId);
+		}
+	})
+}
+
 #[test]
 fn should_not_allow_voter_registration_by_anon() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -168,14 +243,14 @@ fn should_not_allow_voter_registration_by_anon() {
 #[test]
 fn should_not_allow_invalid_bucket_id() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -190,14 +265,14 @@ fn should_not_allow_invalid_bucket_id() {
 #[test]
 fn should_not_allow_voter_registration_during_other_phases() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get() - 1);
@@ -212,14 +287,14 @@ fn should_not_allow_voter_registration_during_other_phases() {
 #[test]
 fn should_allow_voter_registration() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -227,23 +302,23 @@ fn should_allow_voter_registration() {
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 3, 1));
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
 
-		assert_eq!(VotersForBucket::<Test>::get((1u32, 3, 1)), Some((1, 1)));
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 3, 1)), Some(VoterBond { total: 1, remaining: 1 }));
 
-		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some((1, 1)));
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some(VoterBond { total: 1, remaining: 1 }));
 	})
 }
 
 #[test]
 fn should_transition_to_voting() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -251,9 +326,9 @@ fn should_transition_to_voting() {
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 3, 1));
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
 
-		assert_eq!(VotersForBucket::<Test>::get((1u32, 3, 1)), Some((1, 1)));
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 3, 1)), Some(VoterBond { total: 1, remaining: 1 }));
 
-		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some((1, 1)));
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some(VoterBond { total: 1, remaining: 1 }));
 
 		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
 
@@ -264,7 +339,7 @@ fn should_transition_to_voting() {
 #[test]
 fn should_throw_when_attempting_to_register_when_no_proposals_exist() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 
@@ -280,14 +355,14 @@ fn should_throw_when_attempting_to_register_when_no_proposals_exist() {
 #[test]
 fn should_throw_when_voter_has_no_bond() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -307,14 +382,14 @@ fn should_throw_when_voter_has_no_bond() {
 #[test]
 fn should_throw_when_voter_attempts_to_vote_more_than_bond() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -334,30 +409,38 @@ fn should_throw_when_voter_attempts_to_vote_more_than_bond() {
 #[test]
 fn should_allow_vote() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
 
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 3, 1));
 		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
+		System::assert_last_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::VoterRegistered { round: 1, bucket: 2, who: 2, credits: 1 }));
 
 		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
 
 		assert_ok!(
 			QuadraticVotingPallet::vote(Origin::signed(2), 2, 1, VoteDirection::Aye),
 		);
+		System::assert_last_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::VoteCast {
+			round: 1,
+			proposal: 2,
+			who: 2,
+			votes: 1,
+			direction: VoteDirection::Aye,
+		}));
 
 		assert_eq!(
 			VotersForBucket::<Test>::get((1u32, 2, 2)).unwrap(),
-			(1, 0)
+			VoterBond { total: 1, remaining: 0 }
 		);
 
 		assert_eq!(
@@ -375,14 +458,14 @@ fn should_allow_vote() {
 #[test]
 fn should_transition_to_post_voting() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -402,7 +485,7 @@ fn should_transition_to_post_voting() {
 
 		assert_eq!(
 			ProposalOutcome::<Test>::get(key).unwrap(),
-			(1, VoteDirection::Aye)
+			ProposalResult { ayes: 1, nays: 0, abstains: 0, credits: 1, threshold: Threshold::SimpleMajority, outcome: Outcome::Aye }
 		);
 
 		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
@@ -412,14 +495,14 @@ fn should_transition_to_post_voting() {
 #[test]
 fn should_transition_to_enactment() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -444,14 +527,14 @@ fn should_transition_to_enactment() {
 #[test]
 fn should_transition_to_finalization() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -478,14 +561,14 @@ fn should_transition_to_finalization() {
 #[test]
 fn should_allow_new_voting_round_after_previous_is_finalized() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
 
 		set_identity(1);
 		set_identity(2);
 
 		for i in 0..MaxProposals::get() {
 			let origin = (i % 2) + 1;
-			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId)));
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
 		}
 
 		run_to_block(BlocksForPreVotingPhase::get());
@@ -505,6 +588,1561 @@ fn should_allow_new_voting_round_after_previous_is_finalized() {
 
 		run_to_block(BlocksForProposalPhase::get() + BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 4);
 
-		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1)));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+	})
+}
+
+#[test]
+fn should_track_registered_credits_per_bucket() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 2, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 9));
+		assert_eq!(BucketCredits::<Test>::get((1u32, 2)), 13);
+
+		// re-registering replaces the previous registration
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 16));
+		assert_eq!(BucketCredits::<Test>::get((1u32, 2)), 20);
+	})
+}
+
+#[test]
+fn should_count_abstain_towards_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Abstain),
+		);
+
+		assert_eq!(ProposalsForVotingRound::<Test>::get(1u32).unwrap()[2].abstains[0], 2);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		// quorum is reached, but abstentions do not count as ayes
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!((result.ayes, result.nays, result.abstains), (0, 0, 2));
+		assert_eq!(result.outcome, Outcome::Nay);
+	})
+}
+
+#[test]
+fn should_not_decide_proposals_below_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 16));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 1, VoteDirection::Aye),
+		);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::NoQuorum);
+		// proposals nobody voted on never reach quorum
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 0 as ProposalCount)).unwrap().outcome, Outcome::NoQuorum);
+	})
+}
+
+#[test]
+fn should_measure_turnout_in_credits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		// proposal 2 is the only one of bucket 2, proposals 1 and 3 share bucket 1
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 2, 10_000));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 10_000));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 1, 10_000));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 1, 10_000));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		// 150 votes for 12 500 credits, out of the 20 000 registered in the bucket
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(1), 2, 10_000, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 2_500, VoteDirection::Nay));
+		// 2 votes for 4 credits, well short of half of the bucket
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(1), 1, 4, VoteDirection::Aye));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!((result.ayes, result.nays, result.credits), (100, 50, 12_500));
+		assert_eq!(result.outcome, Outcome::Aye);
+
+		let result = ProposalOutcome::<Test>::get((1u32, 1 as ProposalCount)).unwrap();
+		assert_eq!((result.ayes, result.credits), (2, 4));
+		assert_eq!(result.outcome, Outcome::NoQuorum);
+	})
+}
+
+#[test]
+fn should_apply_supermajority_to_treasury_proposals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::TreasurySpend));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 2, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(1), 2, 4, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 1, VoteDirection::Nay));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		// a simple majority, but short of the 75% required for treasury spends
+		assert_eq!(
+			ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap(),
+			ProposalResult {
+				ayes: 2,
+				nays: 1,
+				abstains: 0,
+				credits: 5,
+				threshold: Threshold::SuperMajority(Perbill::from_percent(75)),
+				outcome: Outcome::Nay,
+			}
+		);
+	})
+}
+
+#[test]
+fn should_require_minimum_ayes_for_runtime_parameter_proposals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::RuntimeParameter));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!(result.ayes, 2);
+		assert_eq!(result.threshold, Threshold::AbsoluteMinimum(3));
+		assert_eq!(result.outcome, Outcome::Nay);
+	})
+}
+
+#[test]
+fn threshold_approval() {
+	assert!(Threshold::<u128>::SimpleMajority.approves(2, 1));
+	assert!(!Threshold::<u128>::SimpleMajority.approves(1, 1));
+	assert!(Threshold::<u128>::SuperMajority(Perbill::from_percent(75)).approves(3, 1));
+	assert!(!Threshold::<u128>::SuperMajority(Perbill::from_percent(75)).approves(2, 1));
+	assert!(!Threshold::<u128>::SuperMajority(Perbill::from_percent(75)).approves(0, 0));
+	assert!(Threshold::<u128>::AbsoluteMinimum(3).approves(3, 0));
+	assert!(!Threshold::<u128>::AbsoluteMinimum(3).approves(2, 0));
+}
+
+#[test]
+fn should_fund_matching_pool_on_funding_round_start() {
+	new_test_ext().execute_with(|| {
+		let pool = QuadraticVotingPallet::matching_pool_account(1);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Funding { matching_pool: 1000 }, BallotMode::Open));
+		assert_eq!(Balances::free_balance(&pool), 1000);
+	})
+}
+
+#[test]
+fn should_only_accept_ayes_in_funding_round() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Funding { matching_pool: 1000 }, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_noop!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Nay),
+			Error::<Test>::OnlyAyeInFundingRound
+		);
+	})
+}
+
+#[test]
+fn should_distribute_matching_pool_quadratically() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Funding { matching_pool: 1000 }, BallotMode::Open));
+
+		for who in 1..=4 {
+			set_identity(who);
+		}
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		// proposal 0 is in bucket 0 and pays 2, proposal 2 is in bucket 2 and pays 1
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(3), 0, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(4), 0, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		let beneficiary_before = Balances::free_balance(&2);
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(3), 0, 4, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(4), 0, 4, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye));
+
+		// contributions are paid to the proposer straight away
+		assert_eq!(Balances::free_balance(&2), beneficiary_before + 8);
+		assert_eq!(Balances::free_balance(&3), 5);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 3);
+
+		// scores are (2 + 2)² = 16 and 2² = 4, so proposal 0 gets 80% of the pool, capped at 60%
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::MatchingPayout { round: 1, proposal: 0, amount: 600 }));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::MatchingPayout { round: 1, proposal: 2, amount: 200 }));
+		assert_eq!(Balances::free_balance(&QuadraticVotingPallet::matching_pool_account(1)), 0);
+		assert_eq!(QuadraticVotingPallet::contributions((1u32, 0 as ProposalCount)).unwrap().into_inner(), vec![(3, 4), (4, 4)]);
+	})
+}
+
+#[test]
+fn should_tally_revealed_votes_in_commit_reveal_round() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::CommitReveal));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		let salt = [7u8; 32];
+		let commitment = QuadraticVotingPallet::vote_commitment(&2, 2, 4, &VoteDirection::Aye, &salt);
+
+		assert_noop!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye),
+			Error::<Test>::MustCommitVote
+		);
+		assert_ok!(QuadraticVotingPallet::commit_vote(Origin::signed(2), 2, commitment, 4));
+		assert_noop!(
+			QuadraticVotingPallet::reveal_vote(Origin::signed(2), 2, 4, VoteDirection::Aye, salt),
+			Error::<Test>::CanCallOnlyDuringRevealPhase
+		);
+
+		// nothing is tallied until the reveal phase is over
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Reveal);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)), None);
+
+		assert_noop!(
+			QuadraticVotingPallet::reveal_vote(Origin::signed(2), 2, 4, VoteDirection::Nay, salt),
+			Error::<Test>::InvalidReveal
+		);
+		assert_ok!(QuadraticVotingPallet::reveal_vote(Origin::signed(2), 2, 4, VoteDirection::Aye, salt));
+		assert_noop!(
+			QuadraticVotingPallet::reveal_vote(Origin::signed(2), 2, 4, VoteDirection::Aye, salt),
+			Error::<Test>::CommitmentNotFound
+		);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForRevealPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!(result.ayes, 2);
+		assert_eq!(result.outcome, Outcome::Aye);
+	})
+}
+
+#[test]
+fn should_slash_unrevealed_commitments() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::CommitReveal));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		let commitment = QuadraticVotingPallet::vote_commitment(&2, 2, 4, &VoteDirection::Aye, &[7u8; 32]);
+		assert_ok!(QuadraticVotingPallet::commit_vote(Origin::signed(2), 2, commitment, 4));
+		assert_noop!(
+			QuadraticVotingPallet::commit_vote(Origin::signed(2), 2, commitment, 1),
+			Error::<Test>::VoterHasVotedForThisProposal
+		);
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some(VoterBond { total: 4, remaining: 0 }));
+
+		let reserved = Balances::reserved_balance(&2);
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForRevealPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::UnrevealedCommitSlashed { round: 1, proposal: 2, who: 2, amount: 4 }));
+		assert_eq!(Balances::reserved_balance(&2), reserved - 4);
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some(VoterBond { total: 0, remaining: 0 }));
+		assert_eq!(BucketCredits::<Test>::get((1u32, 2)), 0);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::NoQuorum);
 	})
 }
+
+#[test]
+fn should_return_the_bond_of_unrevealed_commitments_when_ignored() {
+	new_test_ext().execute_with(|| {
+		UnrevealedCommits::set(UnrevealedPolicy::Ignore);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::CommitReveal));
+
+		set_identity(1);
+		set_identity(2);
+		let identity_deposit = Balances::reserved_balance(&2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		let commitment = QuadraticVotingPallet::vote_commitment(&2, 2, 3, &VoteDirection::Aye, &[7u8; 32]);
+		assert_ok!(QuadraticVotingPallet::commit_vote(Origin::signed(2), 2, commitment, 3));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForRevealPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 4);
+
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::UnrevealedCommitReleased { round: 1, proposal: 2, who: 2, credits: 3 }));
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 2, 2)), Some(VoterBond { total: 4, remaining: 4 }));
+
+		// the commitment was not counted, but the voter took part, and gets the whole bond back
+		let pallet_account = Balances::free_balance(&QuadraticVotingPallet::account_id());
+		assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(2), 1));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BondRefunded { round: 1, who: 2, amount: 4 + 5 * 20, forfeited: 0 }));
+		assert_eq!(Balances::reserved_balance(&2), identity_deposit);
+		assert_eq!(Balances::free_balance(&QuadraticVotingPallet::account_id()), pallet_account);
+	})
+}
+
+#[test]
+fn should_require_a_coordinator_for_encrypted_rounds() {
+	new_test_ext().execute_with(|| {
+		// no tally could be verified without a verifying key
+		assert_ok!(QuadraticVotingPallet::set_coordinator(Origin::signed(1), 9, [9u8; 32]));
+		assert_noop!(
+			QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Encrypted),
+			Error::<Test>::NoTallyVerifier
+		);
+		let (_, vk) = fixture::setup(1, 1);
+		TallyVerifyingKey::set(vk);
+
+		Coordinator::<Test>::kill();
+		assert_noop!(
+			QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Encrypted),
+			Error::<Test>::NoCoordinator
+		);
+
+		assert_ok!(QuadraticVotingPallet::set_coordinator(Origin::signed(1), 9, [9u8; 32]));
+		assert_noop!(
+			QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Funding { matching_pool: 1000 }, BallotMode::Encrypted),
+			Error::<Test>::UnsupportedBallotMode
+		);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Encrypted));
+		assert_eq!(QuadraticVotingPallet::round_coordinator(1u32).unwrap().public_key, [9u8; 32]);
+	})
+}
+
+#[test]
+fn should_tally_encrypted_round_with_verified_proof() {
+	new_test_ext().execute_with(|| {
+		// the fixture circuit proves the four totals of every proposal
+		let (params, vk) = fixture::setup(MaxProposals::get() as usize * 4, 1);
+		TallyVerifyingKey::set(vk);
+
+		assert_ok!(QuadraticVotingPallet::set_coordinator(Origin::signed(1), 9, [9u8; 32]));
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Encrypted));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_noop!(QuadraticVotingPallet::sign_up(Origin::signed(2), [2u8; 32]), Error::<Test>::NotRegistered);
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+		assert_ok!(QuadraticVotingPallet::sign_up(Origin::signed(2), [2u8; 32]));
+		assert_noop!(QuadraticVotingPallet::sign_up(Origin::signed(2), [3u8; 32]), Error::<Test>::AlreadySignedUp);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_noop!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye),
+			Error::<Test>::MustPublishMessage
+		);
+
+		let message = |byte: u8| EncryptedMessage { ephemeral_key: [byte; 32], data: BoundedVec::truncate_from(vec![byte; 64]) };
+		assert_noop!(QuadraticVotingPallet::publish_message(Origin::signed(3), message(1)), Error::<Test>::NotSignedUp);
+		// a vote, then a change of voting key which invalidates it in the eyes of a briber
+		assert_ok!(QuadraticVotingPallet::publish_message(Origin::signed(2), message(1)));
+		assert_ok!(QuadraticVotingPallet::publish_message(Origin::signed(2), message(2)));
+		assert_eq!(QuadraticVotingPallet::message_count(1u32), 2);
+		// the voter has used up its messages, and cannot crowd out the others
+		assert_noop!(
+			QuadraticVotingPallet::publish_message(Origin::signed(2), message(3)),
+			Error::<Test>::TooManyMessagesFromVoter
+		);
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::MessagePublished { round: 1, index: 1, message: message(2) }));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		// the coordinator decrypts the messages and proves that proposal 2 got 2 ayes, for 4 credits
+		let mut tally = vec![TallyResult::default(); MaxProposals::get() as usize];
+		tally[2].ayes = 2;
+		tally[2].credits = 4;
+		let public_inputs = QuadraticVotingPallet::tally_public_inputs(1, &tally);
+		let votes = tally
+			.iter()
+			.flat_map(|result| vec![vec![result.ayes], vec![result.nays], vec![result.abstains], vec![result.credits]])
+			.collect();
+
+		let proof: BoundedVec<u8, _> = fixture::prove(&params, TallyCircuit { public_inputs, votes }).try_into().unwrap();
+
+		let mut forged = tally.clone();
+		forged[2].ayes = 3;
+		assert_noop!(
+			QuadraticVotingPallet::submit_tally(Origin::signed(9), BoundedVec::truncate_from(forged), proof.clone()),
+			Error::<Test>::InvalidTallyProof
+		);
+		assert_noop!(
+			QuadraticVotingPallet::submit_tally(Origin::signed(2), BoundedVec::truncate_from(tally.clone()), proof.clone()),
+			Error::<Test>::NotTheCoordinator
+		);
+		assert_ok!(QuadraticVotingPallet::submit_tally(Origin::signed(9), BoundedVec::truncate_from(tally), proof));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::TallyVerified { round: 1 }));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForRevealPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!(result.ayes, 2);
+		assert_eq!(result.outcome, Outcome::Aye);
+	})
+}
+
+fn set_threshold_key() -> threshold::Dealing {
+	let mut seed = 0u64;
+	let dealing = threshold::deal(2, 3, || {
+		seed += 1;
+		Scalar::from(seed * 7919)
+	});
+	let members = dealing.shares.iter().map(|(i, _, vk)| (6 + *i as AccountId, *vk)).collect();
+	assert_ok!(QuadraticVotingPallet::set_threshold_key(Origin::signed(1), dealing.public_key, 2, BoundedVec::truncate_from(members)));
+	dealing
+}
+
+fn threshold_ballot(public_key: &[u8; 32], plaintext: &[u8], r: u64) -> EncryptedMessage<<Test as crate::Config>::MaxMessageLength> {
+	let (ephemeral_key, data) = threshold::encrypt(public_key, plaintext, Scalar::from(r)).unwrap();
+	EncryptedMessage { ephemeral_key, data: BoundedVec::truncate_from(data) }
+}
+
+#[test]
+fn should_decrypt_threshold_ballots_across_blocks() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::ThresholdEncrypted),
+			Error::<Test>::NoThresholdKey
+		);
+		let dealing = set_threshold_key();
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::ThresholdEncrypted));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		set_identity(3);
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(3), 3, 1));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_noop!(
+			QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye),
+			Error::<Test>::MustCastThresholdBallot
+		);
+
+		let ballot = threshold_ballot(&dealing.public_key, &(2 as ProposalCount, 4u128, VoteDirection::Aye).encode(), 11);
+		assert_noop!(
+			QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(4), ballot.clone()),
+			Error::<Test>::NotRegistered
+		);
+		assert_ok!(QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(2), ballot));
+		// not a vote once decrypted
+		assert_ok!(QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(3), threshold_ballot(&dealing.public_key, b"nonsense", 13)));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		let shares_of = |member: usize, secret: usize, ballots: core::ops::Range<u32>| -> BoundedVec<_, _> {
+			let shares = ballots
+				.map(|i| {
+					let (_, ballot) = QuadraticVotingPallet::threshold_ballots((1u32, i)).unwrap();
+					threshold::decryption_share(&dealing.shares[secret].1, &ballot.ephemeral_key, Scalar::from(17 + member as u64)).unwrap()
+				})
+				.collect::<Vec<_>>();
+			BoundedVec::truncate_from(shares)
+		};
+
+		assert_noop!(
+			QuadraticVotingPallet::submit_decryption_shares(Origin::signed(1), 0, shares_of(0, 0, 0..2)),
+			Error::<Test>::NotACommitteeMember
+		);
+		// member 8 cannot pass off the shares of member 9
+		assert_noop!(
+			QuadraticVotingPallet::submit_decryption_shares(Origin::signed(8), 0, shares_of(1, 2, 0..2)),
+			Error::<Test>::InvalidDecryptionShare
+		);
+
+		// shares come in batches, in the order of the ballots
+		assert_noop!(
+			QuadraticVotingPallet::submit_decryption_shares(Origin::signed(7), 1, shares_of(0, 0, 1..2)),
+			Error::<Test>::SharesOutOfOrder
+		);
+		assert_ok!(QuadraticVotingPallet::submit_decryption_shares(Origin::signed(7), 0, shares_of(0, 0, 0..1)));
+		assert_eq!(QuadraticVotingPallet::shares_submitted((1u32, 7)), 1);
+		assert!(QuadraticVotingPallet::share_submitters(1u32).is_empty());
+		// two shares from the second ballot on, when there are only two ballots
+		assert_noop!(
+			QuadraticVotingPallet::submit_decryption_shares(Origin::signed(7), 1, shares_of(0, 0, 0..2)),
+			Error::<Test>::SharesLengthMismatch
+		);
+		assert_ok!(QuadraticVotingPallet::submit_decryption_shares(Origin::signed(7), 1, shares_of(0, 0, 1..2)));
+		System::assert_last_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::DecryptionSharesSubmitted {
+			round: 1,
+			who: 7,
+		}));
+		assert_noop!(
+			QuadraticVotingPallet::submit_decryption_shares(Origin::signed(7), 2, shares_of(0, 0, 1..2)),
+			Error::<Test>::SharesAlreadySubmitted
+		);
+		assert_ok!(QuadraticVotingPallet::submit_decryption_shares(Origin::signed(8), 0, shares_of(1, 1, 0..2)));
+
+		// one ballot is opened per block
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 4);
+		assert_eq!(QuadraticVotingPallet::decryption_cursor(1u32), 1);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Reveal);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 5);
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BallotRejected { round: 1, index: 1 }));
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!(result.ayes, 2);
+		assert_eq!(result.outcome, Outcome::Aye);
+	})
+}
+
+#[test]
+fn should_cancel_round_without_decryption_shares() {
+	new_test_ext().execute_with(|| {
+		set_threshold_key();
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::ThresholdEncrypted));
+
+		set_identity(2);
+		let identity_deposit = Balances::reserved_balance(&2);
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(2), ProposalClass::General));
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 0, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForRevealPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 3);
+
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::RoundCancelled { round: 1 }));
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Cancelled);
+
+		// every bond is returned, votes or not
+		assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(2), 1));
+		assert_eq!(Balances::reserved_balance(&2), identity_deposit);
+
+		// a cancelled round does not hold up the next one
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+	})
+}
+
+#[test]
+fn should_cap_threshold_ballots_per_voter() {
+	new_test_ext().execute_with(|| {
+		let dealing = set_threshold_key();
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::ThresholdEncrypted));
+
+		set_identity(2);
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(2), ProposalClass::General));
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 0, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		let ballot = |r: u64| threshold_ballot(&dealing.public_key, &(0 as ProposalCount, 1u128, VoteDirection::Aye).encode(), r);
+		assert_ok!(QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(2), ballot(11)));
+		assert_ok!(QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(2), ballot(13)));
+		// the voter cannot add to the shares the committee has to submit any further
+		assert_noop!(
+			QuadraticVotingPallet::cast_threshold_ballot(Origin::signed(2), ballot(17)),
+			Error::<Test>::TooManyMessagesFromVoter
+		);
+		assert_eq!(QuadraticVotingPallet::message_count(1u32), 2);
+		assert_eq!(QuadraticVotingPallet::voter_message_count((1u32, 2)), 2);
+	})
+}
+
+fn committee_vrf(who: AccountId, bucket_id: u32) -> (VRFOutput, VRFProof) {
+	let randomness = QuadraticVotingPallet::round_randomness(1u32).unwrap();
+	let transcript = QuadraticVotingPallet::committee_transcript(1, bucket_id, &randomness);
+	let (inout, proof, _) = vrf_keypair(who).vrf_sign(transcript);
+	(VRFOutput(inout.to_output()), VRFProof(proof))
+}
+
+#[test]
+fn should_select_bucket_committees_by_vrf() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		for who in 1..=4 {
+			set_identity(who);
+		}
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		for who in 2..=4 {
+			assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(who), 2, 1));
+		}
+
+		let (output, proof) = committee_vrf(1, 2);
+		assert_noop!(
+			QuadraticVotingPallet::apply_for_committee(Origin::signed(1), 2, output, proof),
+			Error::<Test>::NotRegistered
+		);
+
+		// the output must come from the applicant's own key
+		let (output, proof) = committee_vrf(3, 2);
+		assert_noop!(
+			QuadraticVotingPallet::apply_for_committee(Origin::signed(2), 2, output, proof),
+			Error::<Test>::InvalidVrfProof
+		);
+
+		let (output, proof) = committee_vrf(2, 2);
+		assert_ok!(QuadraticVotingPallet::apply_for_committee(Origin::signed(2), 2, output.clone(), proof.clone()));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::CommitteeMemberJoined { round: 1, bucket: 2, who: 2 }));
+		assert_noop!(
+			QuadraticVotingPallet::apply_for_committee(Origin::signed(2), 2, output, proof),
+			Error::<Test>::AlreadyInCommittee
+		);
+
+		CommitteeDifficulty::set(Perbill::zero());
+		let (output, proof) = committee_vrf(3, 2);
+		assert_noop!(
+			QuadraticVotingPallet::apply_for_committee(Origin::signed(3), 2, output.clone(), proof.clone()),
+			Error::<Test>::NotEligibleForCommittee
+		);
+		CommitteeDifficulty::set(Perbill::one());
+
+		let reserved = Balances::reserved_balance(&3);
+		assert_ok!(QuadraticVotingPallet::apply_for_committee(Origin::signed(3), 2, output, proof));
+		assert_eq!(Balances::reserved_balance(&3), reserved + 5);
+
+		let (output, proof) = committee_vrf(4, 2);
+		assert_noop!(
+			QuadraticVotingPallet::apply_for_committee(Origin::signed(4), 2, output, proof),
+			Error::<Test>::CommitteeFull
+		);
+		assert_eq!(QuadraticVotingPallet::committees((1u32, 2u32)).into_inner(), vec![2, 3]);
+	})
+}
+
+#[test]
+fn quadratic_matching_scores() {
+	let contributions: Vec<Vec<(AccountId, u128)>> = vec![vec![(1, 4), (2, 4)], vec![(3, 9)], vec![]];
+	assert_eq!(<Quadratic as MatchingFormula<AccountId, u128>>::scores(&contributions), vec![16, 9, 0]);
+}
+
+#[test]
+fn pairwise_matching_bounds_colluding_pairs() {
+	type Matching = Pairwise<ConstU128<9>>;
+
+	// a single contributor gets no matching, a pair gets 2√(9 × 9) × 9 / (9 + 9)
+	let alone: Vec<Vec<(AccountId, u128)>> = vec![vec![(1, 9)], vec![(1, 9), (2, 9)]];
+	assert_eq!(Matching::scores(&alone), vec![0, 9]);
+
+	// the same pair backing a second proposal is matched less on both
+	let together: Vec<Vec<(AccountId, u128)>> = vec![vec![(1, 9), (2, 9)], vec![(1, 9), (2, 9)]];
+	assert_eq!(Matching::scores(&together), vec![6, 6]);
+}
+
+// a round in PostVoting, where 2, 3 and 4 voted aye on the only proposal of bucket 2, and 2 and 3 are its committee
+fn committee_round() {
+	assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+	for who in 1..=4 {
+		set_identity(who);
+	}
+
+	for i in 0..MaxProposals::get() {
+		let origin = (i % 2) + 1;
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+	}
+
+	run_to_block(BlocksForPreVotingPhase::get());
+
+	for who in 2..=4 {
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(who), 2, 1));
+	}
+	for who in 2..=3 {
+		let (output, proof) = committee_vrf(who, 2);
+		assert_ok!(QuadraticVotingPallet::apply_for_committee(Origin::signed(who), 2, output, proof));
+	}
+
+	run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+	for who in 2..=4 {
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(who), 2, 1, VoteDirection::Aye));
+	}
+
+	run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+	assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+}
+
+// every vote of `committee_round` costs one credit
+fn bucket_tally(ayes: u128, nays: u128) -> BoundedVec<TallyResult<u128>, MaxProposals> {
+	BoundedVec::try_from(vec![TallyResult { ayes, nays, abstains: 0, credits: ayes + nays }]).unwrap()
+}
+
+#[test]
+fn should_overturn_a_wrong_committee_tally() {
+	new_test_ext().execute_with(|| {
+		committee_round();
+
+		// the bucket is left to its committee
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)), None);
+
+		assert_noop!(
+			QuadraticVotingPallet::submit_bucket_tally(Origin::signed(4), 2, bucket_tally(0, 3)),
+			Error::<Test>::NotInCommittee
+		);
+		assert_noop!(
+			QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, BoundedVec::default()),
+			Error::<Test>::BucketTallyLengthMismatch
+		);
+
+		// a majority of a committee of two is both members
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, bucket_tally(0, 3)));
+		assert_noop!(
+			QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, bucket_tally(0, 3)),
+			Error::<Test>::BucketTallyAlreadySubmitted
+		);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)), None);
+
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(3), 2, bucket_tally(0, 3)));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BucketTallyAccepted { round: 1, bucket: 2 }));
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::Nay);
+
+		assert_noop!(QuadraticVotingPallet::challenge_tally(Origin::signed(1), 2), Error::<Test>::NotRegistered);
+
+		let challenger = Balances::free_balance(&4);
+		let (reserved_2, reserved_3) = (Balances::reserved_balance(&2), Balances::reserved_balance(&3));
+		assert_ok!(QuadraticVotingPallet::challenge_tally(Origin::signed(4), 2));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BucketTallyOverturned { round: 1, bucket: 2, challenger: 4 }));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::CommitteeMemberSlashed { round: 1, bucket: 2, who: 3, amount: 5 }));
+
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!((result.ayes, result.nays, result.outcome), (3, 0, Outcome::Aye));
+		assert_eq!(Balances::free_balance(&4), challenger + 10);
+		assert_eq!(Balances::reserved_balance(&2), reserved_2 - 5);
+		assert_eq!(Balances::reserved_balance(&3), reserved_3 - 5);
+		assert!(QuadraticVotingPallet::committees((1u32, 2u32)).is_empty());
+
+		assert_noop!(QuadraticVotingPallet::challenge_tally(Origin::signed(4), 2), Error::<Test>::AlreadyChallenged);
+	})
+}
+
+#[test]
+fn should_slash_a_rejected_challenge() {
+	new_test_ext().execute_with(|| {
+		committee_round();
+
+		assert_noop!(QuadraticVotingPallet::challenge_tally(Origin::signed(4), 2), Error::<Test>::BucketTallyNotAccepted);
+
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, bucket_tally(3, 0)));
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(3), 2, bucket_tally(3, 0)));
+		assert_noop!(
+			QuadraticVotingPallet::submit_bucket_tally(Origin::signed(3), 2, bucket_tally(3, 0)),
+			Error::<Test>::BucketTallyAlreadyAccepted
+		);
+
+		let challenger = Balances::free_balance(&4);
+		let reserved = Balances::reserved_balance(&4);
+		let pallet_account = Balances::free_balance(&QuadraticVotingPallet::account_id());
+		let issuance = Balances::total_issuance();
+		assert_ok!(QuadraticVotingPallet::challenge_tally(Origin::signed(4), 2));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::ChallengeRejected { round: 1, bucket: 2, challenger: 4 }));
+		assert_eq!(Balances::free_balance(&4), challenger - 3);
+		assert_eq!(Balances::reserved_balance(&4), reserved);
+		// the bond goes to the pallet account, and is not burned
+		assert_eq!(Balances::free_balance(&QuadraticVotingPallet::account_id()), pallet_account + 3);
+		assert_eq!(Balances::total_issuance(), issuance);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::Aye);
+	})
+}
+
+#[test]
+fn should_count_buckets_without_an_accepted_tally() {
+	new_test_ext().execute_with(|| {
+		committee_round();
+
+		// the members disagree, so nothing is accepted
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, bucket_tally(3, 0)));
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(3), 2, bucket_tally(0, 3)));
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)), None);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 3);
+
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Enactment);
+		let result = ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap();
+		assert_eq!((result.ayes, result.outcome), (3, Outcome::Aye));
+	})
+}
+
+#[test]
+fn should_keep_committee_bonds_through_the_challenge_period() {
+	new_test_ext().execute_with(|| {
+		committee_round();
+		let end = VotingRounds::<Test>::get(1u32).unwrap().post_voting_phase.end_block;
+		let challenge_period = <Test as crate::Config>::ChallengePeriod::get();
+
+		// the last tally that can still be challenged for the whole period
+		run_to_block(end - challenge_period - OneBlock::get());
+		let reserved = Balances::reserved_balance(&2);
+		assert_ok!(QuadraticVotingPallet::submit_bucket_tally(Origin::signed(2), 2, bucket_tally(0, 3)));
+
+		run_to_block(end - challenge_period);
+		assert_noop!(
+			QuadraticVotingPallet::submit_bucket_tally(Origin::signed(3), 2, bucket_tally(0, 3)),
+			Error::<Test>::TooLateForBucketTally
+		);
+
+		// the bond backs the tally until the phase ends
+		run_to_block(end - OneBlock::get());
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+		assert_eq!(Balances::reserved_balance(&2), reserved);
+
+		run_to_block(end);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Enactment);
+		assert_eq!(Balances::reserved_balance(&2), reserved - 5);
+		// the chain counted the bucket instead
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::Aye);
+	})
+}
+
+#[test]
+fn should_tally_across_blocks() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 1, VoteDirection::Aye));
+
+		MaxTalliesPerBlock::set(4);
+
+		// four proposals a block
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Tallying);
+		assert_eq!(QuadraticVotingPallet::tally_cursor(1u32), 4);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 4);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 2 as ProposalCount)).unwrap().outcome, Outcome::Aye);
+	})
+}
+
+#[test]
+fn should_claim_refunds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		for who in 2..=4 {
+			set_identity(who);
+		}
+		let identity_deposit = Balances::reserved_balance(&2);
+
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(2), ProposalClass::General));
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(2), ProposalClass::General));
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		// proposal 0 is in bucket 0, proposal 1 in bucket 1. Only 3 votes
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(3), 0, 4));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(4), 1, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(3), 0, 4, VoteDirection::Aye));
+		assert_noop!(
+			QuadraticVotingPallet::claim_refund(Origin::signed(2), 1),
+			Error::<Test>::CanCallOnlyAfterPostVotingPhase
+		);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 3);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Enactment);
+
+		// bonds are held until they are claimed
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((1u32, 2)), 40);
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+
+		assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(2), 1));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BondRefunded { round: 1, who: 2, amount: 40, forfeited: 0 }));
+		assert_eq!(Balances::reserved_balance(&2), identity_deposit);
+		assert_noop!(QuadraticVotingPallet::claim_refund(Origin::signed(2), 1), Error::<Test>::NothingToClaim);
+
+		assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(3), 1));
+		assert_eq!(Balances::reserved_balance(&3), identity_deposit);
+
+		// the bond of a voter who did not vote is not returned, but paid to the pallet account
+		let pallet_account = Balances::free_balance(&QuadraticVotingPallet::account_id());
+		assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(4), 1));
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::BondRefunded { round: 1, who: 4, amount: 0, forfeited: 4 }));
+		assert_eq!(Balances::reserved_balance(&4), identity_deposit);
+		assert_eq!(Balances::free_balance(&QuadraticVotingPallet::account_id()), pallet_account + 4);
+
+		// anyone can release the bonds left in a finalized round, for a tip
+		assert_noop!(
+			QuadraticVotingPallet::refund_others(Origin::signed(3), 1, 1),
+			Error::<Test>::CanCallOnlyAfterEnactmentPhase
+		);
+		run_to_block(BlocksForProposalPhase::get() + BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 4);
+
+		let free = Balances::free_balance(&3);
+		assert_ok!(QuadraticVotingPallet::refund_others(Origin::signed(3), 1, 1));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&3), free + 100);
+	})
+}
+
+#[test]
+fn merkle_proofs() {
+	let leaves: Vec<_> = (0u32..5).map(|i| merkle::vote_leaf::<BlakeTwo256, _>(i, &VoteDirection::Aye, 0, &1u128)).collect();
+	let root = merkle::root::<BlakeTwo256>(leaves.clone());
+	for (i, leaf) in leaves.iter().enumerate() {
+		let proof = merkle::proof::<BlakeTwo256>(leaves.clone(), i);
+		assert!(merkle::verify::<BlakeTwo256>(&root, *leaf, i, leaves.len(), &proof));
+		assert!(!merkle::verify::<BlakeTwo256>(&root, *leaf, (i + 1) % leaves.len(), leaves.len(), &proof));
+	}
+}
+
+#[test]
+fn should_reap_rounds_past_their_retention_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(2);
+		let identity_deposit = Balances::reserved_balance(&2);
+		assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(2), ProposalClass::General));
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 0, 4));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 0, 4, VoteDirection::Aye));
+
+		run_to_block(BlocksForProposalPhase::get() + BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + BlocksForEnactmentPhase::get() + OneBlock::get() * 4);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::Finalized);
+
+		// the latest round is always kept
+		assert_noop!(QuadraticVotingPallet::reap_round(Origin::signed(3), 1), Error::<Test>::RoundNotReapable);
+
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+		assert_noop!(QuadraticVotingPallet::reap_round(Origin::signed(3), 1), Error::<Test>::RoundNotReapable);
+
+		let finalized_block = VotingRounds::<Test>::get(1u32).unwrap().finalized_block;
+		run_to_block(finalized_block + RetentionPeriod::get());
+
+		// the summary is written first
+		assert_ok!(QuadraticVotingPallet::reap_round(Origin::signed(3), 1));
+		let summary = QuadraticVotingPallet::round_summary(1u32).unwrap();
+		assert_eq!(summary.proposals[0].0, 2);
+		assert_eq!(summary.proposals[0].2.as_ref().unwrap().outcome, Outcome::Aye);
+		assert_eq!((summary.credits, summary.votes), (4, 1));
+
+		let leaf = merkle::vote_leaf::<BlakeTwo256, _>(0, &VoteDirection::Aye, 0, &2u128);
+		assert!(merkle::verify::<BlakeTwo256>(&summary.votes_root, leaf, 0, 1, &[]));
+
+		// then the unclaimed bonds are released, and the round is removed a few entries at a time
+		assert_ok!(QuadraticVotingPallet::reap_round(Origin::signed(3), 1));
+		assert_eq!(Balances::reserved_balance(&2), identity_deposit);
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+		while VotingRounds::<Test>::get(1u32).is_some() {
+			assert_ok!(QuadraticVotingPallet::reap_round(Origin::signed(3), 1));
+		}
+		System::assert_has_event(Event::QuadraticVotingPallet(QuadraticVotingEvent::RoundReaped { round: 1 }));
+		assert!(ProposalsForVotingRound::<Test>::get(1u32).is_none());
+		assert!(VotersForBucket::<Test>::get((1u32, 0, 2)).is_none());
+		assert!(QuadraticVotingPallet::round_summary(1u32).is_some());
+
+		// on_idle moves past the rounds that are already reaped
+		QuadraticVotingPallet::on_idle(System::block_number(), u64::MAX);
+		assert_eq!(QuadraticVotingPallet::next_round_to_reap(), 2);
+	})
+}
+
+#[test]
+fn should_rebuild_a_round_from_its_events() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(1), 3, 1));
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 1));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 1, VoteDirection::Aye));
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+
+		let events: Vec<QuadraticVotingEvent<Test>> = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				Event::QuadraticVotingPallet(event) => Some(event),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(
+			events[0],
+			QuadraticVotingEvent::RoundStarted {
+				round: 1,
+				initializer: 1,
+				mode: RoundMode::Governance,
+				ballot: BallotMode::Open,
+			}
+		);
+
+		// an indexer following the events ends up with the state of the chain
+		let mut phase = None;
+		let mut proposers = Vec::new();
+		let mut buckets = Vec::new();
+		let mut bonds = Vec::new();
+		let mut outcomes = Vec::new();
+		for event in events {
+			match event {
+				QuadraticVotingEvent::PhaseTransition { phase: next, .. } => phase = Some(next),
+				QuadraticVotingEvent::ProposalSubmitted { proposer, .. } => proposers.push(proposer),
+				QuadraticVotingEvent::BucketAssigned { submitted, proposal, bucket, .. } =>
+					buckets.push((proposal, proposers[submitted as usize], bucket)),
+				QuadraticVotingEvent::VoterRegistered { bucket, who, credits, .. } =>
+					bonds.push((bucket, who, VoterBond { total: credits, remaining: credits })),
+				QuadraticVotingEvent::VoteCast { proposal, who, votes, .. } => {
+					let bucket = buckets.iter().find(|(id, _, _)| *id == proposal).unwrap().2;
+					let bond = bonds.iter_mut().find(|(b, voter, _)| *b == bucket && *voter == who).unwrap();
+					bond.2.remaining -= votes;
+				},
+				QuadraticVotingEvent::ProposalTallied { proposal, outcome, .. } => outcomes.push((proposal, outcome)),
+				_ => {},
+			}
+		}
+
+		assert_eq!(phase, Some(VotingPhases::PostVoting));
+		buckets.sort();
+		let proposals = ProposalsForVotingRound::<Test>::get(1u32).unwrap();
+		assert_eq!(buckets.len(), proposals.len());
+		for (id, proposer, bucket) in buckets {
+			assert_eq!(proposals[id as usize].initializer, proposer);
+			assert_eq!(proposals[id as usize].bucket_id, Some(bucket));
+		}
+		for (bucket, who, bond) in bonds {
+			assert_eq!(VotersForBucket::<Test>::get((1u32, bucket, who)), Some(bond));
+		}
+		assert_eq!(outcomes.len(), proposals.len());
+		for (id, outcome) in outcomes {
+			assert_eq!(ProposalOutcome::<Test>::get((1u32, id)).unwrap().outcome, outcome);
+		}
+	})
+}
+
+#[test]
+fn should_migrate_v0_storage_to_v1() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(25);
+
+		// a snapshot of v0 storage, encoded as v0 did: rounds ended with their phase, which had no Reveal,
+		// Tallying or Settling, proposals had no class or abstentions, outcomes were the winning votes and their
+		// direction, and bonds were (total, remaining) tuples
+		let round = |previous: u32, start: u64, phase: u8| {
+			// proposal, pre voting, voting, post voting and enactment phases of 10 blocks
+			let phase_data = |n: u64| (start + n * 11, start + n * 11 + 10);
+			(1u64, phase_data(0), previous, phase_data(1), phase_data(2), phase_data(3), phase_data(4), start + 55, phase)
+		};
+		// round 1 is Finalized, and round 2 is in its Voting phase
+		unhashed::put(&VotingRounds::<Test>::hashed_key_for(1u32), &round(0, 0, 5));
+		unhashed::put(&VotingRounds::<Test>::hashed_key_for(2u32), &round(1, 1, 2));
+		LatestVotingRound::<Test>::put(2);
+
+		unhashed::put(&ProposalsForVotingRound::<Test>::hashed_key_for(1u32), &vec![(2u64, vec![2u128], vec![1u128], Some(0u32))]);
+		unhashed::put(
+			&ProposalsForVotingRound::<Test>::hashed_key_for(2u32),
+			&vec![(2u64, Vec::<u128>::new(), Vec::<u128>::new(), Some(0u32)), (1u64, vec![1u128], Vec::<u128>::new(), Some(1u32))],
+		);
+		// an aye outcome, with 2 votes
+		unhashed::put(&ProposalOutcome::<Test>::hashed_key_for((1u32, 0 as ProposalCount)), &(2u128, 0u8));
+
+		unhashed::put(&VotersForBucket::<Test>::hashed_key_for((1u32, 0, 3)), &(4u128, 2u128));
+		unhashed::put(&VotersForBucket::<Test>::hashed_key_for((1u32, 0, 4)), &(1u128, 0u128));
+		unhashed::put(&VotersForBucket::<Test>::hashed_key_for((2u32, 1, 3)), &(1u128, 0u128));
+		unhashed::put(&VotersForBucket::<Test>::hashed_key_for((2u32, 0, 4)), &(4u128, 4u128));
+		for (round, proposal, who) in [(1u32, 0 as ProposalCount, 3), (1, 0, 4), (2, 1, 3)] {
+			VotersVotedOnProposal::<Test>::insert((round, proposal, who), ());
+		}
+		VotersVoted::<Test>::insert((1u32, 0 as ProposalCount), BoundedVec::truncate_from(vec![3, 4]));
+		VotersVoted::<Test>::insert((2u32, 1 as ProposalCount), BoundedVec::truncate_from(vec![3]));
+
+		// v0 returned the bonds of round 1 when its post voting phase ended, and still holds those of round 2
+		assert_ok!(Balances::reserve(&1, 1000 + 20));
+		assert_ok!(Balances::reserve(&2, 20));
+		assert_ok!(Balances::reserve(&3, 1));
+		assert_ok!(Balances::reserve(&4, 4));
+		// v0 had no pallet account
+		let _ = Balances::make_free_balance_be(&QuadraticVotingPallet::account_id(), 0);
+		StorageVersion::new(0).put::<QuadraticVotingPallet>();
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(QuadraticVotingPallet::on_chain_storage_version(), 1);
+		assert_eq!(QuadraticVotingPallet::on_chain_storage_version(), QuadraticVotingPallet::current_storage_version());
+		assert_eq!(Balances::free_balance(&QuadraticVotingPallet::account_id()), Balances::minimum_balance());
+		assert_ok!(QuadraticVotingPallet::do_try_state());
+
+		let finalized = VotingRounds::<Test>::get(1u32).unwrap();
+		assert_eq!(finalized.phase, VotingPhases::Finalized);
+		assert_eq!((finalized.reveal_phase, finalized.quorum), (None, Perbill::zero()));
+		assert_eq!((finalized.mode, finalized.ballot), (RoundMode::Governance, BallotMode::Open));
+		assert_eq!(finalized.finalized_block, 55);
+		assert_eq!(VotingRounds::<Test>::get(2u32).unwrap().phase, VotingPhases::Voting);
+
+		let proposal = &ProposalsForVotingRound::<Test>::get(1u32).unwrap()[0];
+		assert_eq!((proposal.initializer, proposal.class, proposal.bucket_id), (2, ProposalClass::General, Some(0)));
+		assert_eq!((proposal.ayes.to_vec(), proposal.nays.to_vec(), proposal.abstains.len()), (vec![2], vec![1], 0));
+		assert_eq!(
+			ProposalOutcome::<Test>::get((1u32, 0 as ProposalCount)),
+			Some(ProposalResult { ayes: 2, nays: 1, abstains: 0, credits: 5, threshold: Threshold::SimpleMajority, outcome: Outcome::Aye })
+		);
+
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 0, 3)), Some(VoterBond { total: 4, remaining: 2 }));
+		assert_eq!(VotersForBucket::<Test>::get((2u32, 1, 3)), Some(VoterBond { total: 1, remaining: 0 }));
+		assert_eq!(VotersForBucket::<Test>::iter().count(), 4);
+		assert_eq!(BucketCredits::<Test>::get((1u32, 0)), 5);
+		assert_eq!((BucketCredits::<Test>::get((2u32, 0)), BucketCredits::<Test>::get((2u32, 1))), (4, 1));
+
+		// only the bonds v0 still held can be claimed
+		assert_eq!(UnclaimedRefunds::<Test>::iter_prefix((1u32,)).count(), 0);
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((2u32, 1)), 1000 + 20);
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((2u32, 2)), 20);
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((2u32, 3)), 1);
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((2u32, 4)), 4);
+
+		// running it again does nothing
+		MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(QuadraticVotingPallet::on_chain_storage_version(), 1);
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 0, 3)), Some(VoterBond { total: 4, remaining: 2 }));
+		assert_eq!(QuadraticVotingPallet::unclaimed_refunds((2u32, 4)), 4);
+
+		// the round in flight carries on under v1
+		run_to_block(57);
+		assert_eq!(VotingRounds::<Test>::get(2u32).unwrap().phase, VotingPhases::Finalized);
+		assert_eq!(ProposalOutcome::<Test>::get((2u32, 1 as ProposalCount)).unwrap().outcome, Outcome::Aye);
+		for who in 1..=4 {
+			assert_ok!(QuadraticVotingPallet::claim_refund(Origin::signed(who), 2));
+			assert_eq!(Balances::reserved_balance(&who), 0);
+		}
+	})
+}
+
+#[test]
+fn should_answer_runtime_api_queries() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(QuadraticVotingPallet::start_voting_round(Origin::signed(1), RoundMode::Governance, BallotMode::Open));
+
+		set_identity(1);
+		set_identity(2);
+
+		for i in 0..MaxProposals::get() {
+			let origin = (i % 2) + 1;
+			assert_ok!(QuadraticVotingPallet::submit_proposal(Origin::signed(origin as AccountId), ProposalClass::General));
+		}
+
+		run_to_block(BlocksForPreVotingPhase::get());
+
+		assert_ok!(QuadraticVotingPallet::register_to_vote(Origin::signed(2), 2, 4));
+
+		let round = QuadraticVotingPallet::round_info(1).unwrap();
+		assert_eq!(round.phase, VotingPhases::PreVoting);
+		assert_eq!(Some(round), VotingRounds::<Test>::get(1));
+		assert_eq!(QuadraticVotingPallet::round_info(2), None);
+
+		assert_eq!(
+			QuadraticVotingPallet::bucket_proposals(1, 2),
+			vec![ProposalInfo { proposal_id: 2, proposer: 1, class: ProposalClass::General, votes: 0 }]
+		);
+
+		// five proposal bonds, and the credits registered
+		assert_eq!(
+			QuadraticVotingPallet::voter_status(1, &2),
+			VoterStatus { registrations: vec![(2, VoterBond { total: 4, remaining: 4 })], unclaimed_refund: 5 * 20 + 4 }
+		);
+
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + OneBlock::get());
+
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 2, 4, VoteDirection::Aye));
+
+		assert_eq!(QuadraticVotingPallet::bucket_proposals(1, 2)[0].votes, 1);
+		assert_eq!(QuadraticVotingPallet::voter_status(1, &2).registrations, vec![(2, VoterBond { total: 4, remaining: 2 })]);
+
+		let projected = QuadraticVotingPallet::projected_tally(1);
+		assert_eq!(projected.len(), MaxProposals::get() as usize);
+		assert_eq!(
+			projected[2],
+			(2, ProposalResult { ayes: 2, nays: 0, abstains: 0, credits: 4, threshold: Threshold::SimpleMajority, outcome: Outcome::Aye })
+		);
+		assert_eq!(projected[0].1.outcome, Outcome::NoQuorum);
+		assert!(ProposalOutcome::<Test>::get((1u32, 2)).is_none());
+
+		// once tallied, the recorded results are reported
+		run_to_block(BlocksForPreVotingPhase::get() + BlocksForVotingPhase::get() + BlocksForPostVotingPhase::get() + OneBlock::get() * 2);
+		assert_eq!(Some(projected[2].1.clone()), ProposalOutcome::<Test>::get((1u32, 2)));
+		assert_eq!(QuadraticVotingPallet::projected_tally(1), projected);
+
+		assert_eq!(QuadraticVotingPallet::vote_cost(3), Some(9));
+		assert_eq!(QuadraticVotingPallet::vote_cost(u128::MAX), None);
+	})
+}
+
+// a round started at genesis, voting from block 1 on three proposals, one per bucket
+fn genesis_with_open_round() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 1 << 100), (2, 1 << 100)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	GenesisConfig::<Test> {
+		voting_round_id: 0,
+		voters: vec![(3, 1000)],
+		registrar: Some(1),
+		identities: vec![(1, b"alice".to_vec()), (2, b"bob".to_vec()), (3, b"charlie".to_vec())],
+		initial_round: Some(GenesisRound {
+			initializer: 1,
+			mode: RoundMode::Governance,
+			ballot: BallotMode::Open,
+			proposals: vec![(1, ProposalClass::General), (2, ProposalClass::TreasurySpend), (1, ProposalClass::General)],
+			registrations: vec![(3, 0, 100), (2, 1, 50)],
+			open_voting: true,
+		}),
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::from(t)
+}
+
+#[test]
+fn should_boot_into_a_votable_round_from_genesis() {
+	genesis_with_open_round().execute_with(|| {
+		let identity = pallet_identity::pallet::Pallet::<Test>::identity(3).unwrap();
+		assert_eq!(identity.judgements.into_inner(), vec![(0, Judgement::Reasonable)]);
+
+		let voting_round = VotingRounds::<Test>::get(1u32).unwrap();
+		assert_eq!(voting_round.phase, VotingPhases::Voting);
+		assert_eq!(voting_round.voting_phase.start_block, 1);
+		// the round bond, two proposal bonds and the identity deposit
+		assert_eq!(Balances::reserved_balance(&1), 1000 + 20 * 2 + 1);
+
+		// the proposals keep their order
+		let proposals = ProposalsForVotingRound::<Test>::get(1u32).unwrap();
+		assert_eq!(proposals.iter().map(|proposal| proposal.bucket_id).collect::<Vec<_>>(), vec![Some(0), Some(1), Some(2)]);
+		assert_eq!(proposals[1].initializer, 2);
+		assert_eq!(VotersForBucket::<Test>::get((1u32, 0, 3)), Some(VoterBond { total: 100, remaining: 100 }));
+		assert_eq!(BucketCredits::<Test>::get((1u32, 1)), 50);
+
+		run_to_block(2);
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(3), 0, 100, VoteDirection::Aye));
+
+		run_to_block(voting_round.voting_phase.end_block + 2);
+		assert_eq!(VotingRounds::<Test>::get(1u32).unwrap().phase, VotingPhases::PostVoting);
+		assert_eq!(ProposalOutcome::<Test>::get((1u32, 0)).unwrap().ayes, 10);
+	})
+}
+
+#[test]
+fn should_export_every_storage_item_of_a_round() {
+	genesis_with_open_round().execute_with(|| {
+		assert!(QuadraticVotingPallet::export_round(2).is_none());
+
+		run_to_block(2);
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(3), 0, 100, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(2), 1, 25, VoteDirection::Nay));
+		let voting_round = VotingRounds::<Test>::get(1u32).unwrap();
+		run_to_block(voting_round.voting_phase.end_block + 2);
+
+		let export = QuadraticVotingPallet::export_round(1).unwrap();
+		assert_eq!(export.round.phase, VotingPhases::PostVoting);
+		assert_eq!(export.proposals.len(), 3);
+		assert_eq!(export.proposals[1].nays.clone().into_inner(), vec![5]);
+		assert_eq!(
+			export.registrations,
+			vec![(3, 0, VoterBond { total: 100, remaining: 90 }), (2, 1, VoterBond { total: 50, remaining: 45 })]
+		);
+		assert_eq!(export.bucket_credits, vec![(0, 100), (1, 50)]);
+		assert_eq!(export.voters, vec![(0, vec![3]), (1, vec![2])]);
+		assert!(export.contributions.is_empty());
+		assert_eq!(
+			export.outcomes.iter().map(|(proposal_id, result)| (*proposal_id, result.ayes, result.nays)).collect::<Vec<_>>(),
+			vec![(0, 10, 0), (1, 0, 5), (2, 0, 0)]
+		);
+	})
+}
+
+#[test]
+fn should_catch_storage_that_breaks_the_invariants() {
+	genesis_with_open_round().execute_with(|| {
+		run_to_block(2);
+		assert_ok!(QuadraticVotingPallet::vote(Origin::signed(3), 0, 100, VoteDirection::Aye));
+		assert_ok!(QuadraticVotingPallet::do_try_state());
+
+		VotersForBucket::<Test>::mutate((1u32, 1, 2), |bond| bond.as_mut().unwrap().remaining = 51);
+		assert_eq!(QuadraticVotingPallet::do_try_state(), Err("a voter bond has more remaining than bonded"));
+		VotersForBucket::<Test>::mutate((1u32, 1, 2), |bond| bond.as_mut().unwrap().remaining = 50);
+
+		BucketCredits::<Test>::insert((1u32, 1), 49);
+		assert_eq!(QuadraticVotingPallet::do_try_state(), Err("BucketCredits is not the sum of the bonds in the bucket"));
+		BucketCredits::<Test>::remove((1u32, 1));
+		assert_eq!(QuadraticVotingPallet::do_try_state(), Err("a bucket with bonds has no BucketCredits"));
+		BucketCredits::<Test>::insert((1u32, 1), 50);
+
+		VotersVotedOnProposal::<Test>::remove((1u32, 0, 3));
+		assert_eq!(
+			QuadraticVotingPallet::do_try_state(),
+			Err("a voter in VotersVoted is missing from VotersVotedOnProposal")
+		);
+		VotersVotedOnProposal::<Test>::insert((1u32, 0, 3), ());
+		VotersVotedOnProposal::<Test>::insert((1u32, 1, 3), ());
+		assert_eq!(
+			QuadraticVotingPallet::do_try_state(),
+			Err("a voter in VotersVotedOnProposal is missing from VotersVoted")
+		);
+		VotersVotedOnProposal::<Test>::remove((1u32, 1, 3));
+
+		let bucket_id = ProposalsForVotingRound::<Test>::get(1u32).unwrap()[2].bucket_id;
+		ProposalsForVotingRound::<Test>::mutate(1u32, |proposals| proposals.as_mut().unwrap()[2].bucket_id = None);
+		assert_eq!(
+			QuadraticVotingPallet::do_try_state(),
+			Err("a proposal of a round past its Proposal phase has no bucket")
+		);
+		ProposalsForVotingRound::<Test>::mutate(1u32, |proposals| proposals.as_mut().unwrap()[2].bucket_id = bucket_id);
+
+		// more than the identity deposit and the bond of the voter together
+		UnclaimedRefunds::<Test>::mutate((1u32, 3), |refund| *refund += 1_000_000);
+		assert_eq!(
+			QuadraticVotingPallet::do_try_state(),
+			Err("the reserved balance of an account does not cover its bonds")
+		);
+		UnclaimedRefunds::<Test>::mutate((1u32, 3), |refund| *refund -= 1_000_000);
+
+		// a committee member without the committee bond reserved
+		Committees::<Test>::insert((1u32, 0), BoundedVec::truncate_from(vec![5]));
+		assert_eq!(
+			QuadraticVotingPallet::do_try_state(),
+			Err("the reserved balance of an account does not cover its bonds")
+		);
+		Committees::<Test>::remove((1u32, 0));
+
+		let pallet_account = QuadraticVotingPallet::account_id();
+		Balances::make_free_balance_be(&pallet_account, 0);
+		assert_eq!(QuadraticVotingPallet::do_try_state(), Err("the pallet account forfeited bonds are paid to is not alive"));
+		Balances::make_free_balance_be(&pallet_account, 1);
+
+		LatestVotingRound::<Test>::put(2);
+		assert_eq!(QuadraticVotingPallet::do_try_state(), Err("LatestVotingRound points to a round that does not exist"));
+		LatestVotingRound::<Test>::put(1);
+
+		assert_ok!(QuadraticVotingPallet::do_try_state());
+	})
+}
+
+#[test]
+fn should_keep_the_model_checks_through_a_whole_round() {
+	let actions = [
+		Action::StartVotingRound { who: 0, matching_pool: None },
+		Action::SubmitProposal { who: 0, class: 0 },
+		Action::SubmitProposal { who: 1, class: 1 },
+		Action::SubmitProposal { who: 2, class: 2 },
+		Action::AdvanceBlocks(9),
+		Action::RegisterToVote { who: 3, bucket_id: 0, credits: 400 },
+		Action::RegisterToVote { who: 4, bucket_id: 1, credits: 100 },
+		Action::AdvanceBlocks(10),
+		Action::Vote { who: 3, proposal_id: 0, credits: 400, direction: 0 },
+		Action::Vote { who: 4, proposal_id: 1, credits: 81, direction: 1 },
+		// through tallying, settlement and finalization
+		Action::AdvanceBlocks(31),
+		Action::AdvanceBlocks(31),
+		// the first round is reaped once the next one starts
+		Action::StartVotingRound { who: 1, matching_pool: Some(500) },
+		Action::AdvanceBlocks(31),
+	];
+	assert_eq!(model::run(&actions), Ok(()));
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		1 => (any::<u8>(), any::<Option<u16>>())
+			.prop_map(|(who, matching_pool)| Action::StartVotingRound { who, matching_pool }),
+		3 => (any::<u8>(), any::<u8>()).prop_map(|(who, class)| Action::SubmitProposal { who, class }),
+		4 => (any::<u8>(), any::<u8>(), 0u16..2000)
+			.prop_map(|(who, bucket_id, credits)| Action::RegisterToVote { who, bucket_id, credits }),
+		6 => (any::<u8>(), any::<u8>(), 0u16..500, any::<u8>())
+			.prop_map(|(who, proposal_id, credits, direction)| Action::Vote { who, proposal_id, credits, direction }),
+		4 => any::<u8>().prop_map(Action::AdvanceBlocks),
+	]
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(128))]
+
+	// funds are conserved, and rounds only move forward, whatever the calls and their order
+	#[test]
+	fn should_conserve_funds_and_move_rounds_forward(actions in prop::collection::vec(action(), 1..150)) {
+		prop_assert_eq!(model::run(&actions), Ok(()));
+	}
+}
@@ -0,0 +1,136 @@
+// Threshold ElGamal over Ristretto, for ballots that the decryption committee can only open together.
+//
+// A ballot is `(R, data)`, where `R = r·G` and `data` is the plaintext xor-ed with a keystream derived from
+// `r·Y`, `Y` being the committee's public key. Member `i` holds a Shamir share `x_i` of the secret key and
+// publishes `x_i·R` for every ballot, with a proof that it used the share behind its verification key
+// `x_i·G`. Any `threshold` of these shares combine into `r·Y`.
+use codec::{Decode, Encode, MaxEncodedLen};
+use curve25519_dalek::{
+	constants::RISTRETTO_BASEPOINT_POINT,
+	ristretto::{CompressedRistretto, RistrettoPoint},
+	scalar::Scalar,
+	traits::Identity,
+};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use sp_std::vec::Vec;
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct DecryptionShare {
+	// x_i·R
+	pub share: [u8; 32],
+	// Chaum-Pedersen proof (c, z) that log_G(x_i·G) = log_R(x_i·R)
+	pub proof: [u8; 64],
+}
+
+fn point(bytes: &[u8; 32]) -> Option<RistrettoPoint> {
+	CompressedRistretto(*bytes).decompress()
+}
+
+fn challenge(points: &[&RistrettoPoint]) -> Scalar {
+	let mut bytes = Vec::with_capacity(points.len() * 32);
+	for point in points {
+		bytes.extend_from_slice(point.compress().as_bytes());
+	}
+	Scalar::from_bytes_mod_order(BlakeTwo256::hash(&bytes).0)
+}
+
+// checks that `share` was made with the secret behind `verification_key`
+pub fn verify_share(verification_key: &[u8; 32], ephemeral_key: &[u8; 32], share: &DecryptionShare) -> bool {
+	let (vk, r, d) = match (point(verification_key), point(ephemeral_key), point(&share.share)) {
+		(Some(vk), Some(r), Some(d)) => (vk, r, d),
+		_ => return false,
+	};
+	let mut c = [0u8; 32];
+	let mut z = [0u8; 32];
+	c.copy_from_slice(&share.proof[..32]);
+	z.copy_from_slice(&share.proof[32..]);
+	let (c, z) = match (Scalar::from_canonical_bytes(c), Scalar::from_canonical_bytes(z)) {
+		(Some(c), Some(z)) => (c, z),
+		_ => return false,
+	};
+
+	let a1 = z * RISTRETTO_BASEPOINT_POINT - c * vk;
+	let a2 = z * r - c * d;
+	challenge(&[&vk, &r, &d, &a1, &a2]) == c
+}
+
+// combines shares, indexed from 1, into `r·Y`. The shares must come from distinct members
+pub fn combine(shares: &[(u32, [u8; 32])]) -> Option<RistrettoPoint> {
+	let mut combined = RistrettoPoint::identity();
+	for (i, share) in shares {
+		// lagrange coefficient of member i, at 0
+		let mut coefficient = Scalar::one();
+		for (j, _) in shares {
+			if i != j {
+				let denominator = Scalar::from(*j as u64) - Scalar::from(*i as u64);
+				coefficient *= Scalar::from(*j as u64) * denominator.invert();
+			}
+		}
+		combined += coefficient * point(share)?;
+	}
+	Some(combined)
+}
+
+fn apply_keystream(shared: &RistrettoPoint, data: &[u8]) -> Vec<u8> {
+	let key = shared.compress();
+	data.chunks(32)
+		.enumerate()
+		.flat_map(|(block, chunk)| {
+			let stream = BlakeTwo256::hash(&(key.as_bytes(), block as u32).encode());
+			chunk.iter().zip(stream.0).map(|(byte, mask)| byte ^ mask).collect::<Vec<_>>()
+		})
+		.collect()
+}
+
+pub fn decrypt(shared: &RistrettoPoint, data: &[u8]) -> Vec<u8> {
+	apply_keystream(shared, data)
+}
+
+// The rest is only needed off-chain, by voters and committee members, and by the benchmarks
+
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+pub struct Dealing {
+	pub public_key: [u8; 32],
+	// index, secret share, verification key
+	pub shares: Vec<(u32, [u8; 32], [u8; 32])>,
+}
+
+// trusted dealer key generation: a random polynomial of degree `threshold - 1`, evaluated at 1..=members
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+pub fn deal(threshold: u32, members: u32, mut random_scalar: impl FnMut() -> Scalar) -> Dealing {
+	let coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar()).collect();
+	let shares = (1..=members)
+		.map(|i| {
+			let x = Scalar::from(i as u64);
+			let secret = coefficients.iter().rev().fold(Scalar::zero(), |acc, coefficient| acc * x + coefficient);
+			(i, secret.to_bytes(), (secret * RISTRETTO_BASEPOINT_POINT).compress().to_bytes())
+		})
+		.collect();
+	Dealing { public_key: (coefficients[0] * RISTRETTO_BASEPOINT_POINT).compress().to_bytes(), shares }
+}
+
+// returns the ephemeral key and the ciphertext
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+pub fn encrypt(public_key: &[u8; 32], plaintext: &[u8], r: Scalar) -> Option<([u8; 32], Vec<u8>)> {
+	let shared = r * point(public_key)?;
+	Some(((r * RISTRETTO_BASEPOINT_POINT).compress().to_bytes(), apply_keystream(&shared, plaintext)))
+}
+
+// `k` is the nonce of the proof, and must be random
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+pub fn decryption_share(secret_share: &[u8; 32], ephemeral_key: &[u8; 32], k: Scalar) -> Option<DecryptionShare> {
+	let x = Scalar::from_canonical_bytes(*secret_share)?;
+	let r = point(ephemeral_key)?;
+	let vk = x * RISTRETTO_BASEPOINT_POINT;
+	let d = x * r;
+
+	let c = challenge(&[&vk, &r, &d, &(k * RISTRETTO_BASEPOINT_POINT), &(k * r)]);
+	let z = k + c * x;
+
+	let mut proof = [0u8; 64];
+	proof[..32].copy_from_slice(c.as_bytes());
+	proof[32..].copy_from_slice(z.as_bytes());
+	Some(DecryptionShare { share: d.compress().to_bytes(), proof })
+}
@@ -0,0 +1,52 @@
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{prepare_verifying_key, verify_proof, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use frame_support::traits::Get;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+// checks the proof that the coordinator of an encrypted round posts along with its tally
+pub trait TallyVerifier {
+	// every public input is a little-endian field element
+	fn verify(public_inputs: &[[u8; 32]], proof: &[u8]) -> bool;
+
+	// whether a tally can be verified at all. Encrypted rounds are refused until it can
+	fn can_verify() -> bool;
+}
+
+// rejects every tally
+impl TallyVerifier for () {
+	fn verify(_public_inputs: &[[u8; 32]], _proof: &[u8]) -> bool {
+		false
+	}
+
+	fn can_verify() -> bool {
+		false
+	}
+}
+
+// Groth16 over BN254. `Key` is the compressed, canonically serialized verifying key of the tally circuit,
+// and the proof is expected in the same encoding
+pub struct Groth16Bn254<Key>(PhantomData<Key>);
+
+impl<Key: Get<Vec<u8>>> TallyVerifier for Groth16Bn254<Key> {
+	fn verify(public_inputs: &[[u8; 32]], proof: &[u8]) -> bool {
+		let key = Key::get();
+		let vk = match VerifyingKey::<Bn254>::deserialize(&key[..]) {
+			Ok(vk) => vk,
+			Err(_) => return false,
+		};
+		let proof = match Proof::<Bn254>::deserialize(proof) {
+			Ok(proof) => proof,
+			Err(_) => return false,
+		};
+		// inputs are reduced, so hashes wider than the scalar field can be passed as they are
+		let inputs: Vec<Fr> = public_inputs.iter().map(|input| Fr::from_le_bytes_mod_order(input)).collect();
+
+		verify_proof(&prepare_verifying_key(&vk), &proof, &inputs).unwrap_or(false)
+	}
+
+	fn can_verify() -> bool {
+		VerifyingKey::<Bn254>::deserialize(&Key::get()[..]).is_ok()
+	}
+}
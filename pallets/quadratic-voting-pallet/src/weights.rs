@@ -0,0 +1,474 @@
+//! Weights for quadratic_voting_pallet
+//!
+//! These are placeholders that were not measured, until the benchmarks in `benchmarking.rs` are run on reference
+//! hardware. Generate this file with `./benchmark-weights.sh`, which builds the node with the `runtime-benchmarks`
+//! feature and runs:
+//!
+//! ./target/release/parachain-template-node benchmark pallet \
+//! 	--chain dev \
+//! 	--execution wasm \
+//! 	--wasm-execution compiled \
+//! 	--pallet quadratic_voting_pallet \
+//! 	--extrinsic '*' \
+//! 	--steps 50 \
+//! 	--repeat 20 \
+//! 	--template .maintain/frame-weight-template.hbs \
+//! 	--output pallets/quadratic-voting-pallet/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{constants::RocksDbWeight, Weight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for quadratic_voting_pallet.
+pub trait WeightInfo {
+	fn start_voting_round() -> Weight;
+	fn submit_proposal(p: u32) -> Weight;
+	fn register_to_vote() -> Weight;
+	fn vote(p: u32, v: u32) -> Weight;
+	fn commit_vote(p: u32) -> Weight;
+	fn reveal_vote(p: u32, v: u32) -> Weight;
+	fn set_coordinator() -> Weight;
+	fn sign_up() -> Weight;
+	fn publish_message() -> Weight;
+	fn submit_tally(p: u32) -> Weight;
+	fn set_threshold_key(m: u32) -> Weight;
+	fn cast_threshold_ballot() -> Weight;
+	fn submit_decryption_shares(b: u32) -> Weight;
+	fn apply_for_committee() -> Weight;
+	fn submit_bucket_tally(p: u32, c: u32) -> Weight;
+	fn challenge_tally(p: u32, v: u32, c: u32) -> Weight;
+	fn claim_refund(p: u32) -> Weight;
+	fn refund_others(p: u32) -> Weight;
+	fn on_initialize_idle() -> Weight;
+	fn start_pre_voting(p: u32) -> Weight;
+	fn start_voting() -> Weight;
+	fn start_reveal() -> Weight;
+	fn tally(p: u32, v: u32) -> Weight;
+	fn settle_commitments(c: u32) -> Weight;
+	fn decrypt_ballots(b: u32, t: u32) -> Weight;
+	fn cancel_round(r: u32) -> Weight;
+	fn finish_settling(p: u32, r: u32) -> Weight;
+	fn finalize() -> Weight;
+	fn summarize_round(p: u32, v: u32) -> Weight;
+	fn reap_round(r: u32) -> Weight;
+	fn matching_formula(p: u32, c: u32) -> Weight;
+}
+
+/// Weights for quadratic_voting_pallet using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn start_voting_round() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn submit_proposal(p: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((310_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn register_to_vote() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn vote(p: u32, v: u32) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((420_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((61_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn commit_vote(p: u32) -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn reveal_vote(p: u32, v: u32) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((640_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((61_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn set_coordinator() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn sign_up() -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn publish_message() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn submit_tally(p: u32) -> Weight {
+		(21_400_000_000 as Weight)
+			.saturating_add((910_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_threshold_key(m: u32) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((95_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn cast_threshold_ballot() -> Weight {
+		(39_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn submit_decryption_shares(b: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((262_000_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn apply_for_committee() -> Weight {
+		(412_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn submit_bucket_tally(p: u32, c: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((2_150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_900_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+	fn challenge_tally(p: u32, v: u32, c: u32) -> Weight {
+		(87_000_000 as Weight)
+			.saturating_add((2_600_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_300_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((21_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn claim_refund(p: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn refund_others(p: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize_idle() -> Weight {
+		(11_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+	}
+	fn start_pre_voting(p: u32) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((1_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn start_voting() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn start_reveal() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn tally(p: u32, v: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((5_300_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_300_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+	fn settle_commitments(c: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((31_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	fn decrypt_ballots(b: u32, t: u32) -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add((143_000_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add((9_800_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(b as Weight)))
+	}
+	fn cancel_round(r: u32) -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add((10_900_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn finish_settling(p: u32, r: u32) -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add((19_800_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((10_900_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn finalize() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn summarize_round(p: u32, v: u32) -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add((4_700_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((2_600_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn reap_round(r: u32) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((38_400_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn matching_formula(p: u32, c: u32) -> Weight {
+		(0 as Weight)
+			.saturating_add((1_700_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((2_400_000 as Weight).saturating_mul(c as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn start_voting_round() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn submit_proposal(p: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((310_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn register_to_vote() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn vote(p: u32, v: u32) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((420_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((61_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn commit_vote(p: u32) -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add((300_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn reveal_vote(p: u32, v: u32) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((640_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((61_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn set_coordinator() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn sign_up() -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn publish_message() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn submit_tally(p: u32) -> Weight {
+		(21_400_000_000 as Weight)
+			.saturating_add((910_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_threshold_key(m: u32) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((95_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn cast_threshold_ballot() -> Weight {
+		(39_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn submit_decryption_shares(b: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((262_000_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn apply_for_committee() -> Weight {
+		(412_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn submit_bucket_tally(p: u32, c: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((2_150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_900_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+	fn challenge_tally(p: u32, v: u32, c: u32) -> Weight {
+		(87_000_000 as Weight)
+			.saturating_add((2_600_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_300_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((21_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn claim_refund(p: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn refund_others(p: u32) -> Weight {
+		(52_000_000 as Weight)
+			.saturating_add((2_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize_idle() -> Weight {
+		(11_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+	}
+	fn start_pre_voting(p: u32) -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add((1_100_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn start_voting() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn start_reveal() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn tally(p: u32, v: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((5_300_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((1_300_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+	fn settle_commitments(c: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((31_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	fn decrypt_ballots(b: u32, t: u32) -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add((143_000_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add((9_800_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(b as Weight)))
+	}
+	fn cancel_round(r: u32) -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add((10_900_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn finish_settling(p: u32, r: u32) -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add((19_800_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((10_900_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+	fn finalize() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn summarize_round(p: u32, v: u32) -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add((4_700_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((2_600_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn reap_round(r: u32) -> Weight {
+		(96_000_000 as Weight)
+			.saturating_add((38_400_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(14 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
+	}
+	fn matching_formula(p: u32, c: u32) -> Weight {
+		(0 as Weight)
+			.saturating_add((1_700_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((2_400_000 as Weight).saturating_mul(c as Weight))
+	}
+}
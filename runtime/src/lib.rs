@@ -15,7 +15,7 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
+	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, Convert, IdentifyAccount, Verify},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
@@ -59,6 +59,7 @@ use xcm_executor::XcmExecutor;
 
 /// Import the quadratic voting pallet.
 pub use quadratic_voting_pallet;
+use quadratic_voting_pallet::{matching::Pairwise, verifier::Groth16Bn254, ProposalClass, Threshold, UnrevealedPolicy};
 
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
 pub type Signature = MultiSignature;
@@ -123,8 +124,12 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Storage migrations to run on the next runtime upgrade, oldest first.
+pub type Migrations = (quadratic_voting_pallet::migrations::v1::MigrateToV1<Runtime>,);
+
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
 /// node's balance type.
 ///
@@ -469,6 +474,24 @@ parameter_types! {
 	pub const BlocksForVotingPhase: BlockNumber = 10;
 	pub const MaxProposals: u32 = 10;
 	pub const OneBlock: BlockNumber = 1;
+	pub const Quorum: Perbill = Perbill::from_percent(20);
+	pub const QuadraticVotingPalletId: PalletId = PalletId(*b"py/qvote");
+	pub const MaxMatchingShare: Perbill = Perbill::from_percent(25);
+	pub const PairwiseMatchingBound: Balance = 100 * TOKEN;
+	pub const UnrevealedCommits: UnrevealedPolicy = UnrevealedPolicy::Slash;
+	// verifying key of the tally circuit of encrypted rounds. While it is empty, encrypted rounds cannot be started
+	pub TallyVerifyingKey: Vec<u8> = Vec::new();
+	pub const CommitteeDifficulty: Perbill = Perbill::from_percent(10);
+	pub const BondForCommittee: Balance = 10 * TOKEN;
+	pub const BondForChallenge: Balance = 5 * TOKEN;
+	pub const RefundTip: Perbill = Perbill::from_percent(1);
+	pub const RetentionPeriod: BlockNumber = 30 * DAYS;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+parameter_types! {
+	// the benchmarks install the verifying key of the circuit they prove the tally with
+	pub storage BenchmarkTallyVerifyingKey: Vec<u8> = Vec::new();
 }
 
 /// Configure the pallet in pallets/quadratic-voting-pallet.
@@ -480,6 +503,7 @@ impl quadratic_voting_pallet::Config for Runtime {
 	type BlocksForPreVotingPhase = BlocksForPreVotingPhase;
 	type BlocksForProposalPhase = ConstU32<10>;
 	type BlocksForEnactmentPhase = ConstU32<10>;
+	type BlocksForRevealPhase = ConstU32<10>;
 	type MaxProposals = MaxProposals;
 	type Token = Balances;
 	type BondForVotingRound = ConstU128<1000>;
@@ -490,6 +514,82 @@ impl quadratic_voting_pallet::Config for Runtime {
 	// use BABE!
 	type Randomness = RandomnessCollectiveFlip;
 	type BucketSize = ConstU32<5>;
+	type Quorum = Quorum;
+	type ClassThreshold = ClassThreshold;
+	type PalletId = QuadraticVotingPalletId;
+	type MaxMatchingShare = MaxMatchingShare;
+	// resists collusion between contributors who keep backing the same proposals
+	type MatchingFormula = Pairwise<PairwiseMatchingBound>;
+	type MaxContributorsPerProposal = ConstU32<64>;
+	type UnrevealedCommits = UnrevealedCommits;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type TallyVerifier = Groth16Bn254<TallyVerifyingKey>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type TallyVerifier = Groth16Bn254<BenchmarkTallyVerifyingKey>;
+	type MaxMessageLength = ConstU32<256>;
+	type MaxMessages = ConstU32<10_000>;
+	type MaxMessagesPerVoter = ConstU32<20>;
+	type MaxProofLength = ConstU32<256>;
+	type DecryptionOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
+	type MaxCommitteeMembers = ConstU32<16>;
+	type MaxSharesPerCall = ConstU32<500>;
+	type MaxDecryptionsPerBlock = ConstU32<50>;
+	type MaxTalliesPerBlock = ConstU32<16>;
+	type MaxRefundsPerBlock = ConstU32<256>;
+	type RefundTip = RefundTip;
+	type RoundsToKeep = ConstU32<10>;
+	type RetentionPeriod = RetentionPeriod;
+	type MaxRemovalsPerReap = ConstU32<512>;
+	type VrfKey = AccountVrfKey;
+	type CommitteeDifficulty = CommitteeDifficulty;
+	type BondForCommittee = BondForCommittee;
+	type MaxBucketCommitteeSize = ConstU32<16>;
+	type ChallengePeriod = ConstU32<5>;
+	type BondForChallenge = BondForChallenge;
+	type WeightInfo = quadratic_voting_pallet::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = QuadraticVotingBenchmarkHelper;
+}
+
+/// Approval thresholds for each class of quadratic voting proposals.
+pub struct ClassThreshold;
+impl Convert<ProposalClass, Threshold<Balance>> for ClassThreshold {
+	fn convert(class: ProposalClass) -> Threshold<Balance> {
+		match class {
+			ProposalClass::General => Threshold::SimpleMajority,
+			ProposalClass::TreasurySpend => Threshold::SuperMajority(Perbill::from_percent(66)),
+			ProposalClass::RuntimeParameter => Threshold::AbsoluteMinimum(1_000),
+		}
+	}
+}
+
+/// Accounts are sr25519 public keys, so they double as the VRF keys of committee applicants.
+pub struct AccountVrfKey;
+impl Convert<AccountId, Option<[u8; 32]>> for AccountVrfKey {
+	fn convert(who: AccountId) -> Option<[u8; 32]> {
+		Some(who.into())
+	}
+}
+
+/// The committee benchmarks need an account that is a valid sr25519 key: the Ristretto basepoint is one.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct QuadraticVotingBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl quadratic_voting_pallet::BenchmarkHelper<AccountId> for QuadraticVotingBenchmarkHelper {
+	fn vrf_account() -> AccountId {
+		AccountId::from(hex_literal::hex!("e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76"))
+	}
+
+	// the account is the basepoint, so its secret scalar is one. The nonce is only used to sign
+	fn vrf_secret_key() -> [u8; 64] {
+		let mut key = [0u8; 64];
+		key[0] = 1;
+		key
+	}
+
+	fn set_tally_verifying_key(key: Vec<u8>) {
+		BenchmarkTallyVerifyingKey::set(&key);
+	}
 }
 
 parameter_types! {
@@ -580,7 +680,7 @@ construct_runtime!(
 		TechnicalCommittee: pallet_collective::<Instance2> = 35,
 
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip = 39,
-		QuadraticVotingPallet: quadratic_voting_pallet::{Pallet, Call, Storage, Event<T>}  = 40,
+		QuadraticVotingPallet: quadratic_voting_pallet::{Pallet, Call, Storage, Config<T>, Event<T>}  = 40,
 
 		// Miscellaneous.
 		Sudo: pallet_sudo = 50,
@@ -600,6 +700,7 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[quadratic_voting_pallet, QuadraticVotingPallet]
 	);
 }
 
@@ -704,6 +805,38 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl quadratic_voting_runtime_api::QuadraticVotingApi<Block, AccountId, BlockNumber, Balance> for Runtime {
+		fn round_info(
+			voting_round_id: quadratic_voting_pallet::VotingRoundId,
+		) -> Option<quadratic_voting_pallet::VotingRoundMetadata<AccountId, BlockNumber, Balance>> {
+			QuadraticVotingPallet::round_info(voting_round_id)
+		}
+
+		fn bucket_proposals(
+			voting_round_id: quadratic_voting_pallet::VotingRoundId,
+			bucket_id: quadratic_voting_pallet::BucketId,
+		) -> Vec<quadratic_voting_pallet::ProposalInfo<AccountId>> {
+			QuadraticVotingPallet::bucket_proposals(voting_round_id, bucket_id)
+		}
+
+		fn voter_status(
+			voting_round_id: quadratic_voting_pallet::VotingRoundId,
+			who: AccountId,
+		) -> quadratic_voting_pallet::VoterStatus<Balance> {
+			QuadraticVotingPallet::voter_status(voting_round_id, &who)
+		}
+
+		fn projected_tally(
+			voting_round_id: quadratic_voting_pallet::VotingRoundId,
+		) -> Vec<(quadratic_voting_pallet::ProposalCount, quadratic_voting_pallet::ProposalResult<Balance>)> {
+			QuadraticVotingPallet::projected_tally(voting_round_id)
+		}
+
+		fn vote_cost(votes: Balance) -> Option<Balance> {
+			QuadraticVotingPallet::vote_cost(votes)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -719,7 +852,9 @@ impl_runtime_apis! {
 		}
 
 		fn execute_block_no_check(block: Block) -> Weight {
-			Executive::execute_block_no_check(block)
+			let weight = Executive::execute_block_no_check(block);
+			QuadraticVotingPallet::do_try_state().expect("the invariants of the quadratic voting pallet hold");
+			weight
 		}
 	}
 
@@ -803,3 +938,30 @@ cumulus_pallet_parachain_system::register_validate_block! {
 	BlockExecutor = cumulus_pallet_aura_ext::BlockExecutor::<Runtime, Executive>,
 	CheckInherents = CheckInherents,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use quadratic_voting_runtime_api::runtime_decl_for_QuadraticVotingApi::QuadraticVotingApi;
+
+	#[test]
+	fn quadratic_voting_api_prices_votes_quadratically() {
+		assert_eq!(Runtime::vote_cost(0), Some(0));
+		assert_eq!(Runtime::vote_cost(12), Some(144));
+		assert_eq!(Runtime::vote_cost(Balance::MAX), None);
+	}
+
+	#[test]
+	fn quadratic_voting_api_reports_nothing_for_unknown_rounds() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let who = AccountId::from([1u8; 32]);
+			assert_eq!(Runtime::round_info(1), None);
+			assert!(Runtime::bucket_proposals(1, 0).is_empty());
+			assert!(Runtime::projected_tally(1).is_empty());
+
+			let status = Runtime::voter_status(1, who);
+			assert!(status.registrations.is_empty());
+			assert_eq!(status.unclaimed_refund, 0);
+		});
+	}
+}